@@ -0,0 +1,91 @@
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Distinguishes temporary files written by the same process, so concurrent
+/// saves of one file from different threads never share a temporary file.
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Writes `contents` to a temporary file next to `path`, flushes it to disk,
+/// and renames it over `path`, so a crash leaves either the old file or the
+/// new one but never a truncated mix. The rename replaces an existing file on
+/// Windows too (`fs::rename` uses `MoveFileExW` with
+/// `MOVEFILE_REPLACE_EXISTING`); keeping the temporary file in the same
+/// directory keeps it on the same volume, which both platforms require.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    replace_with(path, contents.as_ref(), write_synced)
+}
+
+fn replace_with(
+    path: &Path,
+    contents: &[u8],
+    write: impl FnOnce(&Path, &[u8]) -> io::Result<()>,
+) -> io::Result<()> {
+    let temp = temp_path(path);
+    let result = write(&temp, contents).and_then(|()| fs::rename(&temp, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+fn write_synced(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut file = File::create(path)?;
+    file.write_all(contents)?;
+    file.sync_all()
+}
+
+/// Hidden and tagged with the process id and a per-process counter, so
+/// neither two instances nor two threads saving the same file write into
+/// each other's temporary file.
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let count = TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+    path.with_file_name(format!(".{name}.{}.{count}.tmp", std::process::id()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{replace_with, temp_path, write_atomic};
+    use std::fs;
+    use std::io::{self, Write};
+
+    #[test]
+    fn replaces_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        fs::write(&path, "old").unwrap();
+        write_atomic(&path, "new").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn a_failed_write_leaves_the_original_intact() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        fs::write(&path, "old").unwrap();
+        let result = replace_with(&path, b"new contents", |temp, contents| {
+            let mut file = fs::File::create(temp)?;
+            file.write_all(&contents[..3])?;
+            Err(io::Error::other("disk full"))
+        });
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+        assert_eq!(
+            fs::read_dir(dir.path()).unwrap().count(),
+            1,
+            "temp file removed"
+        );
+    }
+
+    #[test]
+    fn temp_paths_are_unique_within_a_process() {
+        let path = std::path::Path::new("config.json");
+        assert_ne!(temp_path(path), temp_path(path));
+    }
+}
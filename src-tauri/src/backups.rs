@@ -0,0 +1,103 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::atomic::write_atomic;
+use crate::encoding::strip_bom;
+
+/// Backups kept per persisted config; `.bak.1` is always the newest.
+const MAX_BACKUPS: usize = 3;
+
+#[derive(Serialize, Debug)]
+pub struct ConfigBackup {
+    /// 1 for the most recent backup.
+    pub index: usize,
+    pub path: String,
+    pub bytes: u64,
+    /// Unix time the backup was taken (the file's modification time).
+    pub modified: Option<u64>,
+}
+
+fn backup_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".bak.{index}"));
+    PathBuf::from(name)
+}
+
+/// Shifts the existing backups down one slot, dropping the oldest, and copies
+/// `path` into `.bak.1`. Nothing happens when `path` does not exist or already
+/// holds `next`, so saving the same config repeatedly does not push older
+/// backups out.
+pub fn back_up(path: &Path, next: &str) -> Result<(), String> {
+    let Ok(current) = fs::read_to_string(path) else {
+        return Ok(());
+    };
+    if current == next {
+        return Ok(());
+    }
+    store(path, current)
+}
+
+/// Backs up `path` unconditionally, for when it is about to be deleted.
+pub fn back_up_before_removal(path: &Path) -> Result<(), String> {
+    match fs::read_to_string(path) {
+        Ok(current) => store(path, current),
+        Err(_) => Ok(()),
+    }
+}
+
+fn store(path: &Path, current: String) -> Result<(), String> {
+    let _ = fs::remove_file(backup_path(path, MAX_BACKUPS));
+    for index in (1..MAX_BACKUPS).rev() {
+        let from = backup_path(path, index);
+        if from.is_file() {
+            fs::rename(&from, backup_path(path, index + 1)).map_err(|e| e.to_string())?;
+        }
+    }
+    write_atomic(&backup_path(path, 1), current).map_err(|e| e.to_string())
+}
+
+pub fn clear_backups(path: &Path) -> Result<(), String> {
+    for index in 1..=MAX_BACKUPS {
+        let backup = backup_path(path, index);
+        if backup.is_file() {
+            fs::remove_file(&backup).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+/// Newest first.
+pub fn list_backups(path: &Path) -> Vec<ConfigBackup> {
+    (1..=MAX_BACKUPS)
+        .filter_map(|index| {
+            let backup = backup_path(path, index);
+            let metadata = fs::metadata(&backup).ok().filter(|m| m.is_file())?;
+            Some(ConfigBackup {
+                index,
+                path: backup.to_string_lossy().to_string(),
+                bytes: metadata.len(),
+                modified: metadata
+                    .modified()
+                    .ok()
+                    .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                    .map(|elapsed| elapsed.as_secs()),
+            })
+        })
+        .collect()
+}
+
+/// Puts backup `index` back in place of `path` and returns it. The config
+/// being replaced is backed up first, so a restore can itself be undone.
+pub fn restore_backup(path: &Path, index: usize) -> Result<Value, String> {
+    let backup = backup_path(path, index);
+    let contents = fs::read_to_string(&backup)
+        .map_err(|_| format!("There is no backup {index} of {}", path.display()))?;
+    let config: Value = serde_json::from_str(strip_bom(&contents))
+        .map_err(|e| format!("Backup {} is not valid JSON: {e}", backup.display()))?;
+    back_up(path, &contents)?;
+    write_atomic(path, &contents).map_err(|e| e.to_string())?;
+    Ok(config)
+}
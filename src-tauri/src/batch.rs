@@ -0,0 +1,109 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BatchProgressPayload {
+    pub batch_id: String,
+    pub total_runs: usize,
+    pub completed_runs: usize,
+    pub failed_runs: usize,
+    /// 0–100, like the per-run progress events:
+    /// `(completed_runs + in-flight run fractions) / total_runs`.
+    pub progress: f32,
+    /// Every run has finished; the batch has been forgotten.
+    pub done: bool,
+}
+
+struct Batch {
+    total: usize,
+    completed: usize,
+    failed: usize,
+    /// In-flight runs and how far each has got, 0–1.
+    active: HashMap<String, f32>,
+}
+
+impl Batch {
+    fn payload(&self, batch_id: &str) -> BatchProgressPayload {
+        let in_flight: f32 = self.active.values().sum();
+        let progress = if self.total == 0 {
+            100.0
+        } else {
+            ((self.completed as f32 + in_flight) / self.total as f32 * 100.0).min(100.0)
+        };
+        BatchProgressPayload {
+            batch_id: batch_id.to_string(),
+            total_runs: self.total,
+            completed_runs: self.completed,
+            failed_runs: self.failed,
+            progress,
+            done: self.completed >= self.total,
+        }
+    }
+}
+
+/// Aggregate progress for a group of runs started with the same `batch_id`.
+/// Whoever submits the runs declares the batch size up front with `begin`.
+#[derive(Default)]
+pub struct BatchTracker {
+    batches: Mutex<HashMap<String, Batch>>,
+}
+
+impl BatchTracker {
+    /// Starts (or restarts) tracking `batch_id` for `total_runs` runs.
+    pub fn begin(&self, batch_id: &str, total_runs: usize) -> Result<BatchProgressPayload, String> {
+        if total_runs == 0 {
+            return Err("A batch needs at least one run".into());
+        }
+        let mut batches = self
+            .batches
+            .lock()
+            .map_err(|_| "Batch tracker lock poisoned".to_string())?;
+        let batch = Batch {
+            total: total_runs,
+            completed: 0,
+            failed: 0,
+            active: HashMap::new(),
+        };
+        let payload = batch.payload(batch_id);
+        batches.insert(batch_id.to_string(), batch);
+        Ok(payload)
+    }
+
+    /// Records a run's progress (0–100). Returns `None` for unknown batches.
+    pub fn update(
+        &self,
+        batch_id: &str,
+        run_key: &str,
+        progress: f32,
+    ) -> Option<BatchProgressPayload> {
+        let mut batches = self.batches.lock().ok()?;
+        let batch = batches.get_mut(batch_id)?;
+        batch
+            .active
+            .insert(run_key.to_string(), (progress / 100.0).clamp(0.0, 1.0));
+        Some(batch.payload(batch_id))
+    }
+
+    /// Counts a finished run, forgetting the batch once all runs are in so a
+    /// drained batch leaves no state behind.
+    pub fn finish(
+        &self,
+        batch_id: &str,
+        run_key: &str,
+        success: bool,
+    ) -> Option<BatchProgressPayload> {
+        let mut batches = self.batches.lock().ok()?;
+        let batch = batches.get_mut(batch_id)?;
+        batch.active.remove(run_key);
+        batch.completed += 1;
+        if !success {
+            batch.failed += 1;
+        }
+        let payload = batch.payload(batch_id);
+        if payload.done {
+            batches.remove(batch_id);
+        }
+        Some(payload)
+    }
+}
@@ -0,0 +1,354 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant, SystemTime};
+use tauri::PathResolver;
+use which::which;
+
+pub const BINARY_SETTINGS_FILENAME: &str = "binary.json";
+
+/// Binary chosen in-app (via set_binary_path or install_binary). It takes
+/// precedence over the environment variables and PATH lookup.
+static CONFIGURED_BINARY: RwLock<Option<PathBuf>> = RwLock::new(None);
+/// Path and modification time of the last binary read by `warm_binary`, so a
+/// repeat call in the same session returns without touching the disk.
+static WARMED: Mutex<Option<(PathBuf, SystemTime)>> = Mutex::new(None);
+const WARM_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+/// Upper bounds for `LookupRetry`. The doubling backoff is also cut short
+/// once `MAX_LOOKUP_WAIT` has been spent, so no setting can stall startup for
+/// more than a few seconds.
+const MAX_LOOKUP_RETRIES: u32 = 5;
+const MAX_LOOKUP_BACKOFF_MS: u64 = 2000;
+const MAX_LOOKUP_WAIT: Duration = Duration::from_secs(5);
+static LOOKUP_RETRY: RwLock<LookupRetry> = RwLock::new(LookupRetry::DEFAULT);
+
+/// How often, and how patiently, the configured and environment-variable
+/// binary paths are re-checked before falling back to PATH. This covers
+/// network mounts that are briefly unavailable at startup, so it applies to
+/// the startup lookup only; later lookups check once.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct LookupRetry {
+    pub retries: u32,
+    /// Wait before the first retry; it doubles for each one after that.
+    pub backoff_ms: u64,
+}
+
+impl LookupRetry {
+    const DEFAULT: LookupRetry = LookupRetry {
+        retries: 1,
+        backoff_ms: 200,
+    };
+}
+
+impl Default for LookupRetry {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Where `locate_pioneer_binary` found the binary.
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum BinarySource {
+    /// Chosen in-app with `set_binary_path` or `install_binary`.
+    Configured,
+    /// Named by an environment variable, directly or as a directory holding
+    /// the executable.
+    EnvVar { name: String },
+    /// Found by searching PATH.
+    Path,
+}
+
+#[derive(Clone, Debug)]
+pub struct BinaryCandidate {
+    pub path: PathBuf,
+    pub source: BinarySource,
+}
+
+#[derive(Serialize, Debug)]
+pub struct BinaryCheck {
+    /// Absolute, with symlinks resolved when possible.
+    pub path: String,
+    pub source: BinarySource,
+    pub executable: bool,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct BinarySettings {
+    path: Option<PathBuf>,
+    #[serde(default)]
+    warm_on_startup: bool,
+    /// Program and arguments Pioneer is launched through, e.g.
+    /// `["srun", "--cpus-per-task=16"]`.
+    #[serde(default)]
+    command_wrapper: Option<Vec<String>>,
+    #[serde(default)]
+    lookup_retry: LookupRetry,
+}
+
+#[derive(Serialize, Debug)]
+pub struct WarmupReport {
+    pub path: String,
+    pub bytes: u64,
+    pub elapsed_ms: u64,
+    /// Already read this session; nothing was done.
+    pub already_warm: bool,
+}
+
+fn settings_path(resolver: &PathResolver) -> Option<PathBuf> {
+    resolver
+        .app_config_dir()
+        .map(|dir| dir.join(BINARY_SETTINGS_FILENAME))
+}
+
+/// The configured binary, whether or not it currently exists;
+/// `first_existing` decides that.
+pub fn configured_path() -> Option<PathBuf> {
+    CONFIGURED_BINARY
+        .read()
+        .ok()
+        .and_then(|guard| guard.clone())
+}
+
+/// Restores the binary and lookup retry saved by a previous session. Called
+/// once at startup.
+pub fn load_configured_binary(resolver: &PathResolver) {
+    let settings = load_settings(resolver);
+    if let Ok(mut guard) = CONFIGURED_BINARY.write() {
+        *guard = settings.path;
+    }
+    if let Ok(mut guard) = LOOKUP_RETRY.write() {
+        *guard = settings.lookup_retry;
+    }
+}
+
+pub fn lookup_retry() -> LookupRetry {
+    LOOKUP_RETRY.read().map(|guard| *guard).unwrap_or_default()
+}
+
+/// Clamps to the supported bounds and returns what was saved.
+pub fn set_lookup_retry(
+    resolver: &PathResolver,
+    retry: LookupRetry,
+) -> Result<LookupRetry, String> {
+    let retry = LookupRetry {
+        retries: retry.retries.min(MAX_LOOKUP_RETRIES),
+        backoff_ms: retry.backoff_ms.min(MAX_LOOKUP_BACKOFF_MS),
+    };
+    let settings = BinarySettings {
+        lookup_retry: retry,
+        ..load_settings(resolver)
+    };
+    save_settings(resolver, &settings)?;
+    if let Ok(mut guard) = LOOKUP_RETRY.write() {
+        *guard = retry;
+    }
+    Ok(retry)
+}
+
+/// The first of `candidates` that is a file. With `retry`, the whole list is
+/// re-checked with backoff per `lookup_retry` when none is, waiting at most
+/// `MAX_LOOKUP_WAIT` in total. An empty list is not retried: there is nothing
+/// a stalled mount could be hiding.
+pub fn first_existing(candidates: &[BinaryCandidate], retry: bool) -> Option<BinaryCandidate> {
+    if candidates.is_empty() {
+        return None;
+    }
+    let retry = if retry {
+        lookup_retry()
+    } else {
+        LookupRetry {
+            retries: 0,
+            backoff_ms: 0,
+        }
+    };
+    let mut backoff = Duration::from_millis(retry.backoff_ms);
+    let mut waited = Duration::ZERO;
+    for attempt in 0..=retry.retries {
+        if let Some(found) = candidates.iter().find(|candidate| candidate.path.is_file()) {
+            return Some(found.clone());
+        }
+        let listed: Vec<String> = candidates
+            .iter()
+            .map(|candidate| candidate.path.display().to_string())
+            .collect();
+        eprintln!(
+            "Pioneer binary lookup attempt {}/{}: none of {} exist",
+            attempt + 1,
+            retry.retries + 1,
+            listed.join(", ")
+        );
+        let wait = backoff.min(MAX_LOOKUP_WAIT.saturating_sub(waited));
+        if attempt == retry.retries || wait.is_zero() {
+            break;
+        }
+        std::thread::sleep(wait);
+        waited += wait;
+        backoff *= 2;
+    }
+    None
+}
+
+pub fn check_binary(candidate: &BinaryCandidate) -> BinaryCheck {
+    let path = fs::canonicalize(&candidate.path)
+        .or_else(|_| std::path::absolute(&candidate.path))
+        .unwrap_or_else(|_| candidate.path.clone());
+    BinaryCheck {
+        executable: is_executable(&path),
+        path: path.to_string_lossy().to_string(),
+        source: candidate.source.clone(),
+    }
+}
+
+/// A binary named for one run or query rather than found by the usual
+/// lookup. Relative paths are taken from the working directory.
+pub fn check_override(path: &Path) -> Result<PathBuf, String> {
+    if !path.is_file() {
+        return Err(format!("{} is not a file", path.display()));
+    }
+    if !is_executable(path) {
+        return Err(format!("{} is not executable", path.display()));
+    }
+    Ok(std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf()))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|metadata| metadata.permissions().mode() & 0o111 != 0)
+}
+
+/// Windows has no execute bit; what counts is whether the extension is one
+/// the shell will launch.
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+            .is_some_and(|ext| matches!(ext.as_str(), "exe" | "bat" | "cmd" | "com"))
+}
+
+fn load_settings(resolver: &PathResolver) -> BinarySettings {
+    settings_path(resolver)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save_settings(resolver: &PathResolver, settings: &BinarySettings) -> Result<(), String> {
+    let Some(settings_path) = settings_path(resolver) else {
+        return Ok(());
+    };
+    if let Some(parent) = settings_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let pretty = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    fs::write(settings_path, pretty).map_err(|e| e.to_string())
+}
+
+pub fn warm_on_startup(resolver: &PathResolver) -> bool {
+    load_settings(resolver).warm_on_startup
+}
+
+pub fn set_warm_on_startup(resolver: &PathResolver, enabled: bool) -> Result<(), String> {
+    let settings = BinarySettings {
+        warm_on_startup: enabled,
+        ..load_settings(resolver)
+    };
+    save_settings(resolver, &settings)
+}
+
+pub fn command_wrapper(resolver: &PathResolver) -> Option<Vec<String>> {
+    load_settings(resolver).command_wrapper
+}
+
+/// Checks that the wrapper's program can be found, returning it resolved.
+pub fn check_command_wrapper(wrapper: &[String]) -> Result<PathBuf, String> {
+    let program = wrapper
+        .first()
+        .filter(|program| !program.trim().is_empty())
+        .ok_or_else(|| "The command wrapper needs a program".to_string())?;
+    which(program).map_err(|_| format!("Command wrapper `{program}` was not found on PATH"))
+}
+
+/// An empty wrapper clears the setting.
+pub fn set_command_wrapper(
+    resolver: &PathResolver,
+    wrapper: Option<Vec<String>>,
+) -> Result<(), String> {
+    let wrapper = wrapper.filter(|wrapper| !wrapper.is_empty());
+    if let Some(wrapper) = &wrapper {
+        check_command_wrapper(wrapper)?;
+    }
+    let settings = BinarySettings {
+        command_wrapper: wrapper,
+        ..load_settings(resolver)
+    };
+    save_settings(resolver, &settings)
+}
+
+/// Reads the binary once, discarding the bytes, so the OS page cache holds it
+/// before the first run of a session.
+pub fn warm_binary(path: &Path) -> io::Result<WarmupReport> {
+    let modified = fs::metadata(path)?.modified()?;
+    let key = (path.to_path_buf(), modified);
+    if WARMED
+        .lock()
+        .map(|warmed| warmed.as_ref() == Some(&key))
+        .unwrap_or(false)
+    {
+        return Ok(WarmupReport {
+            path: path.to_string_lossy().to_string(),
+            bytes: 0,
+            elapsed_ms: 0,
+            already_warm: true,
+        });
+    }
+
+    let started = Instant::now();
+    let mut file = File::open(path)?;
+    let mut buffer = vec![0u8; WARM_CHUNK_SIZE];
+    let mut bytes = 0u64;
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        bytes += read as u64;
+    }
+    if let Ok(mut warmed) = WARMED.lock() {
+        *warmed = Some(key);
+    }
+    Ok(WarmupReport {
+        path: path.to_string_lossy().to_string(),
+        bytes,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+        already_warm: false,
+    })
+}
+
+/// Sets (or clears, with `None`) the configured binary and persists the
+/// choice so it survives restarts.
+pub fn set_binary_path(resolver: &PathResolver, path: Option<&Path>) -> Result<(), String> {
+    if let Some(path) = path {
+        if !path.is_file() {
+            return Err(format!("{} is not a file", path.display()));
+        }
+    }
+
+    let settings = BinarySettings {
+        path: path.map(Path::to_path_buf),
+        ..load_settings(resolver)
+    };
+    save_settings(resolver, &settings)?;
+
+    let mut guard = CONFIGURED_BINARY
+        .write()
+        .map_err(|_| "Binary setting lock poisoned".to_string())?;
+    *guard = path.map(Path::to_path_buf);
+    Ok(())
+}
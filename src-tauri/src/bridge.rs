@@ -0,0 +1,249 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::hash_map::RandomState;
+use std::fs;
+use std::hash::{BuildHasher, Hasher};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::PathResolver;
+use tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tungstenite::http::StatusCode;
+use tungstenite::Message;
+
+pub const BRIDGE_SETTINGS_FILENAME: &str = "event_bridge.json";
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Persisted so the bridge comes back on the next launch when enabled.
+#[derive(Serialize, Deserialize, Default, Clone, Copy)]
+pub struct BridgeSettings {
+    pub enabled: bool,
+    /// Fixed port; `None` lets the OS choose.
+    pub port: Option<u16>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BridgeStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+    /// Clients must connect with `?token=<token>`; a new one is made each
+    /// time the bridge starts.
+    pub token: Option<String>,
+    pub clients: usize,
+}
+
+struct Server {
+    port: u16,
+    token: Arc<str>,
+    stop: Arc<AtomicBool>,
+    clients: Arc<Mutex<Vec<Sender<String>>>>,
+}
+
+/// Optional localhost WebSocket server rebroadcasting every `pioneer-*` event
+/// as `{"event": <name>, "payload": <payload>}` text messages. Binding to
+/// localhost alone would let any web page the user visits connect, so the
+/// handshake must also carry the session token.
+#[derive(Default)]
+pub struct EventBridge {
+    server: Mutex<Option<Server>>,
+}
+
+fn settings_path(resolver: &PathResolver) -> Option<PathBuf> {
+    resolver
+        .app_config_dir()
+        .map(|dir| dir.join(BRIDGE_SETTINGS_FILENAME))
+}
+
+pub fn load_settings(resolver: &PathResolver) -> BridgeSettings {
+    settings_path(resolver)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_settings(resolver: &PathResolver, settings: BridgeSettings) -> Result<(), String> {
+    let Some(path) = settings_path(resolver) else {
+        return Ok(());
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let pretty = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+    fs::write(path, pretty).map_err(|e| e.to_string())
+}
+
+impl EventBridge {
+    /// Binds to localhost only. Starting an already-running bridge returns
+    /// its current port.
+    pub fn start(&self, port: Option<u16>) -> Result<u16, String> {
+        let mut server = self
+            .server
+            .lock()
+            .map_err(|_| "Event bridge lock poisoned".to_string())?;
+        if let Some(existing) = server.as_ref() {
+            return Ok(existing.port);
+        }
+
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port.unwrap_or(0)))
+            .map_err(|e| format!("Failed to bind event bridge: {e}"))?;
+        listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+        let bound = listener.local_addr().map_err(|e| e.to_string())?.port();
+
+        let token: Arc<str> = session_token().into();
+        let stop = Arc::new(AtomicBool::new(false));
+        let clients = Arc::new(Mutex::new(Vec::new()));
+        {
+            let token = token.clone();
+            let stop = stop.clone();
+            let clients = clients.clone();
+            std::thread::spawn(move || accept_loop(listener, token, stop, clients));
+        }
+        *server = Some(Server {
+            port: bound,
+            token,
+            stop,
+            clients,
+        });
+        Ok(bound)
+    }
+
+    pub fn stop(&self) {
+        if let Ok(mut server) = self.server.lock() {
+            if let Some(server) = server.take() {
+                server.stop.store(true, Ordering::SeqCst);
+                // Dropping the senders ends each client's forwarding loop.
+                if let Ok(mut clients) = server.clients.lock() {
+                    clients.clear();
+                }
+            }
+        }
+    }
+
+    pub fn status(&self) -> BridgeStatus {
+        let guard = self.server.lock().ok();
+        let server = guard.as_ref().and_then(|server| server.as_ref());
+        BridgeStatus {
+            running: server.is_some(),
+            port: server.map(|server| server.port),
+            token: server.map(|server| server.token.to_string()),
+            clients: server
+                .and_then(|server| server.clients.lock().ok().map(|clients| clients.len()))
+                .unwrap_or(0),
+        }
+    }
+
+    /// Serializes only when a server is running, so a disabled bridge costs
+    /// one uncontended lock per event.
+    pub fn publish<S: Serialize>(&self, event: &str, payload: &S) {
+        let Ok(guard) = self.server.lock() else {
+            return;
+        };
+        let Some(server) = guard.as_ref() else {
+            return;
+        };
+        let payload = serde_json::to_value(payload).unwrap_or(Value::Null);
+        let message = json!({ "event": event, "payload": payload }).to_string();
+        let Ok(mut clients) = server.clients.lock() else {
+            return;
+        };
+        clients.retain(|client| client.send(message.clone()).is_ok());
+    }
+}
+
+/// 128 bits from the standard library's randomly keyed hasher, which is
+/// seeded from the OS, so no RNG dependency is needed for one token.
+fn session_token() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or_default();
+    (0..2)
+        .map(|round| {
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_u128(nanos);
+            hasher.write_u32(std::process::id());
+            hasher.write_u8(round);
+            format!("{:016x}", hasher.finish())
+        })
+        .collect()
+}
+
+fn has_token(query: Option<&str>, token: &str) -> bool {
+    query.is_some_and(|query| {
+        query
+            .split('&')
+            .any(|pair| pair.strip_prefix("token=") == Some(token))
+    })
+}
+
+fn accept_loop(
+    listener: TcpListener,
+    token: Arc<str>,
+    stop: Arc<AtomicBool>,
+    clients: Arc<Mutex<Vec<Sender<String>>>>,
+) {
+    while !stop.load(Ordering::SeqCst) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let (tx, rx) = mpsc::channel();
+                if let Ok(mut clients) = clients.lock() {
+                    clients.push(tx);
+                }
+                let token = token.clone();
+                std::thread::spawn(move || serve_client(stream, rx, &token));
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {
+                std::thread::sleep(ACCEPT_POLL_INTERVAL);
+            }
+            Err(err) => {
+                eprintln!("Event bridge accept failed: {err}");
+                std::thread::sleep(ACCEPT_POLL_INTERVAL);
+            }
+        }
+    }
+}
+
+/// Forwards messages until the client disconnects or the bridge stops. The
+/// bridge is send-only; anything the client sends is ignored. A handshake
+/// without the session token is refused with 401.
+fn serve_client(stream: TcpStream, messages: Receiver<String>, token: &str) {
+    if stream.set_nonblocking(false).is_err() {
+        return;
+    }
+    let check_token = |request: &Request, response: Response| {
+        if has_token(request.uri().query(), token) {
+            return Ok(response);
+        }
+        let mut rejection = ErrorResponse::new(Some("Missing or wrong event bridge token".into()));
+        *rejection.status_mut() = StatusCode::UNAUTHORIZED;
+        Err(rejection)
+    };
+    let Ok(mut socket) = tungstenite::accept_hdr(stream, check_token) else {
+        return;
+    };
+    while let Ok(message) = messages.recv() {
+        if socket.send(Message::Text(message)).is_err() {
+            return;
+        }
+    }
+    let _ = socket.close(None);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{has_token, session_token};
+
+    #[test]
+    fn only_the_exact_token_is_accepted() {
+        let token = session_token();
+        assert_eq!(token.len(), 32);
+        assert!(has_token(Some(&format!("token={token}")), &token));
+        assert!(has_token(Some(&format!("v=1&token={token}")), &token));
+        assert!(!has_token(Some(&format!("token={token}x")), &token));
+        assert!(!has_token(Some("token="), &token));
+        assert!(!has_token(None, &token));
+    }
+}
@@ -0,0 +1,99 @@
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+use crate::atomic::write_atomic;
+use crate::backups::back_up;
+use crate::encoding::strip_bom;
+
+/// Serializes a `Value` with every object's keys in sorted order, whatever
+/// order the map itself iterates in.
+struct Sorted<'a>(&'a Value);
+
+impl Serialize for Sorted<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            Value::Object(map) => {
+                let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+                entries.sort_by(|a, b| a.0.cmp(b.0));
+                let mut out = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    out.serialize_entry(key, &Sorted(value))?;
+                }
+                out.end()
+            }
+            Value::Array(items) => serializer.collect_seq(items.iter().map(Sorted)),
+            other => other.serialize(serializer),
+        }
+    }
+}
+
+/// Pretty-printed JSON with recursively sorted keys, two-space indentation,
+/// and a trailing newline, so equal configs always produce identical text and
+/// diffs in version control show only real changes.
+pub fn canonicalize_config(config: &Value) -> Result<String, String> {
+    if !config.is_object() {
+        return Err("A config must be a JSON object".into());
+    }
+    let mut text = serde_json::to_string_pretty(&Sorted(config)).map_err(|e| e.to_string())?;
+    text.push('\n');
+    Ok(text)
+}
+
+/// Rewrites `path` in canonical form, returning the new contents. The file is
+/// usually the persisted config, so the old text is backed up and replaced
+/// atomically the way a run's save does.
+pub fn canonicalize_file(path: &Path) -> Result<String, String> {
+    let contents =
+        fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {e}", path.display()))?;
+    let config: Value = serde_json::from_str(strip_bom(&contents))
+        .map_err(|e| format!("{} is not valid JSON: {e}", path.display()))?;
+    let text = canonicalize_config(&config)?;
+    back_up(path, &text)?;
+    write_atomic(path, &text).map_err(|e| e.to_string())?;
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{canonicalize_config, canonicalize_file};
+    use crate::backups::list_backups;
+    use serde_json::json;
+    use std::fs;
+
+    #[test]
+    fn equal_objects_in_any_order_canonicalize_identically() {
+        assert_eq!(
+            canonicalize_config(&json!({"b": 1, "a": {"d": 2, "c": 3}})),
+            canonicalize_config(&json!({"a": {"c": 3, "d": 2}, "b": 1}))
+        );
+    }
+
+    #[test]
+    fn sorts_keys_at_every_depth() {
+        let config = json!({"b": [{"z": 1, "a": 2}], "a": {"y": null, "x": "s"}});
+        assert_eq!(
+            canonicalize_config(&config).expect("canonical"),
+            "{\n  \"a\": {\n    \"x\": \"s\",\n    \"y\": null\n  },\n  \"b\": [\n    {\n      \"a\": 2,\n      \"z\": 1\n    }\n  ]\n}\n"
+        );
+    }
+
+    #[test]
+    fn rejects_configs_that_are_not_objects() {
+        assert!(canonicalize_config(&json!([1, 2])).is_err());
+    }
+
+    #[test]
+    fn rewriting_a_file_is_idempotent() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("config.json");
+        fs::write(&path, "\u{feff}{\"b\":1,\"a\":{\"d\":[3,1],\"c\":true}}").expect("write");
+        let first = canonicalize_file(&path).expect("canonicalize");
+        assert_eq!(fs::read_to_string(&path).expect("read"), first);
+        assert_eq!(canonicalize_file(&path).expect("again"), first);
+        // Only the first rewrite changed the text, so only it was backed up.
+        assert_eq!(list_backups(&path).len(), 1);
+        assert!(first.starts_with("{\n  \"a\": {\n    \"c\": true,"));
+    }
+}
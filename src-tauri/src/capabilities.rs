@@ -0,0 +1,209 @@
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use std::sync::Mutex;
+
+use crate::{output_within, FetchControl};
+
+/// What `pioneer --help` lists, so the UI can disable workflows the binary
+/// cannot run or prepare defaults for.
+#[derive(Serialize, Clone, Debug)]
+pub struct BinaryCapabilities {
+    pub path: String,
+    /// Every subcommand found in the help text, in the order listed.
+    pub subcommands: Vec<String>,
+    /// Runs BuildSpecLib.
+    pub predict: bool,
+    /// Runs SearchDIA.
+    pub search: bool,
+    /// Writes BuildSpecLib defaults.
+    pub params_predict: bool,
+    /// Writes SearchDIA defaults.
+    pub params_search: bool,
+    /// Library format versions the binary says it reads; `None` when its
+    /// help does not say.
+    pub library_formats: Option<FormatRange>,
+    pub exit_code: Option<i32>,
+}
+
+/// Inclusive range of library format versions, declared in `--help` by a
+/// line such as `Library formats: 2-3` (or a single `Library format: 3`).
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FormatRange {
+    pub min: u64,
+    pub max: u64,
+}
+
+impl FormatRange {
+    pub fn contains(&self, format: u64) -> bool {
+        (self.min..=self.max).contains(&format)
+    }
+}
+
+impl fmt::Display for FormatRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.min == self.max {
+            write!(f, "{}", self.min)
+        } else {
+            write!(f, "{}–{}", self.min, self.max)
+        }
+    }
+}
+
+/// Capabilities per binary path for the session. Replacing the binary at the
+/// same path is only noticed when the caller asks for a refresh.
+#[derive(Default)]
+pub struct CapabilityCache {
+    entries: Mutex<HashMap<PathBuf, BinaryCapabilities>>,
+}
+
+impl CapabilityCache {
+    pub fn get(&self, pioneer: &Path) -> Option<BinaryCapabilities> {
+        self.entries.lock().ok()?.get(pioneer).cloned()
+    }
+
+    pub fn insert(&self, pioneer: &Path, capabilities: BinaryCapabilities) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(pioneer.to_path_buf(), capabilities);
+        }
+    }
+}
+
+pub fn discover_capabilities(pioneer: &Path) -> Result<BinaryCapabilities, String> {
+    let mut command = StdCommand::new(pioneer);
+    command.arg("--help");
+    let output = output_within(command, &FetchControl::probe())
+        .map_err(|e| format!("Failed to run {} --help: {e}", pioneer.display()))?;
+    // Some builds print usage to stderr, or exit non-zero after printing it.
+    let help = format!(
+        "{}\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let subcommands = parse_subcommands(&help);
+    if subcommands.is_empty() {
+        return Err(format!(
+            "{} --help has no commands section listing subcommands (exit status {:?})",
+            pioneer.display(),
+            output.status.code()
+        ));
+    }
+    let has = |name: &str| subcommands.iter().any(|subcommand| subcommand == name);
+    Ok(BinaryCapabilities {
+        path: pioneer.to_string_lossy().to_string(),
+        predict: has("predict"),
+        search: has("search"),
+        params_predict: has("params-predict"),
+        params_search: has("params-search"),
+        library_formats: parse_library_formats(&help),
+        subcommands,
+        exit_code: output.status.code(),
+    })
+}
+
+/// Subcommands are read only from the section under a `Commands:` (or
+/// `Subcommands:`, `Available commands:`) heading, one per line at the
+/// indentation of the first entry, as in `  search    Run a SearchDIA
+/// analysis`. Usage lines sit outside the section, and wrapped description
+/// text is indented further, so neither is mistaken for a subcommand. The
+/// section ends at the next unindented line.
+fn parse_subcommands(help: &str) -> Vec<String> {
+    let mut subcommands: Vec<String> = Vec::new();
+    let mut lines = help.lines().skip_while(|line| !is_commands_heading(line));
+    lines.next();
+    let mut entry_indent = None;
+    for line in lines {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        if indent == 0 {
+            break;
+        }
+        if *entry_indent.get_or_insert(indent) != indent {
+            continue;
+        }
+        let Some(first) = line.split_whitespace().next() else {
+            continue;
+        };
+        let name = first.trim_end_matches([':', ',']);
+        let is_name = name.starts_with(|c: char| c.is_ascii_lowercase())
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+        if is_name && !subcommands.iter().any(|known| known == name) {
+            subcommands.push(name.to_string());
+        }
+    }
+    subcommands
+}
+
+fn parse_library_formats(help: &str) -> Option<FormatRange> {
+    let pattern = Regex::new(
+        r"(?im)^\s*library formats?(?: versions?)?\s*:\s*(\d+)(?:\s*(?:-|–|\.\.)\s*(\d+))?\s*$",
+    )
+    .expect("valid library format regex");
+    let captures = pattern.captures(help)?;
+    let min = captures[1].parse().ok()?;
+    let max = match captures.get(2) {
+        Some(max) => max.as_str().parse().ok()?,
+        None => min,
+    };
+    (min <= max).then_some(FormatRange { min, max })
+}
+
+fn is_commands_heading(line: &str) -> bool {
+    let heading = line.trim().trim_end_matches(':').to_ascii_lowercase();
+    heading == "commands"
+        || heading == "subcommands"
+        || heading == "available commands"
+        || heading == "available subcommands"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_library_formats, parse_subcommands, FormatRange};
+
+    #[test]
+    fn reads_only_the_commands_section() {
+        let help = "\
+Usage:
+  pioneer <command> [args]
+
+Commands:
+  search          Run a SearchDIA analysis
+  predict         Build a spectral library
+                  and writes it to disk
+  params-search   Write default search parameters
+  params-predict  Write default library parameters
+
+Options:
+  --version       Print the version
+";
+        assert_eq!(
+            parse_subcommands(help),
+            ["search", "predict", "params-search", "params-predict"]
+        );
+    }
+
+    #[test]
+    fn reads_the_declared_library_formats() {
+        assert_eq!(
+            parse_library_formats("Commands:\n  search  Run\n\nLibrary formats: 2-3\n"),
+            Some(FormatRange { min: 2, max: 3 })
+        );
+        assert_eq!(
+            parse_library_formats("Library format: 4"),
+            Some(FormatRange { min: 4, max: 4 })
+        );
+        assert_eq!(parse_library_formats("Usage: pioneer <command>"), None);
+    }
+
+    #[test]
+    fn help_without_a_commands_heading_lists_nothing() {
+        assert!(parse_subcommands("Usage:\n  pioneer search <config>\n").is_empty());
+    }
+}
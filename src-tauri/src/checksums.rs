@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use crate::diff::lookup;
+use crate::RunMode;
+
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum HashKind {
+    /// SHA-256 of the file's bytes.
+    File,
+    /// SHA-256 over a sorted listing of every file in a directory.
+    Manifest,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct InputHash {
+    pub key: String,
+    pub path: String,
+    pub kind: HashKind,
+    pub sha256: String,
+    pub files: usize,
+    pub bytes: u64,
+    /// False for manifests built from names and sizes only.
+    pub content_hashed: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct InputHashes {
+    pub mode: RunMode,
+    pub full_content: bool,
+    pub inputs: Vec<InputHash>,
+    pub errors: Vec<String>,
+}
+
+/// Hashes the inputs a run reads: the FASTA files for a build, the library
+/// and MS data directory for a search. FASTA files are always hashed by
+/// content; directories are listed by relative path and size, and their file
+/// contents are only hashed when `full_content` is set since raw MS data can
+/// run to hundreds of gigabytes.
+pub fn hash_inputs(mode: RunMode, config: &Value, full_content: bool) -> InputHashes {
+    let mut hashes = InputHashes {
+        mode,
+        full_content,
+        inputs: Vec::new(),
+        errors: Vec::new(),
+    };
+    let targets: Vec<(&str, PathBuf)> = match mode {
+        RunMode::BuildSpecLib => path_values(config, "fasta_paths")
+            .into_iter()
+            .map(|path| ("fasta_paths", path))
+            .collect(),
+        RunMode::SearchDia => ["paths.library", "paths.ms_data"]
+            .into_iter()
+            .flat_map(|key| {
+                path_values(config, key)
+                    .into_iter()
+                    .map(move |path| (key, path))
+            })
+            .collect(),
+    };
+
+    for (key, path) in targets {
+        let result = if path.is_dir() {
+            hash_manifest(&path, full_content)
+        } else {
+            hash_file(&path).map(|(sha256, bytes)| (HashKind::File, sha256, 1, bytes))
+        };
+        match result {
+            Ok((kind, sha256, files, bytes)) => hashes.inputs.push(InputHash {
+                key: key.to_string(),
+                path: path.to_string_lossy().to_string(),
+                kind,
+                sha256,
+                files,
+                bytes,
+                content_hashed: matches!(kind, HashKind::File) || full_content,
+            }),
+            Err(err) => hashes
+                .errors
+                .push(format!("`{key}` ({}): {err}", path.display())),
+        }
+    }
+    hashes
+}
+
+fn path_values(config: &Value, key: &str) -> Vec<PathBuf> {
+    match lookup(config, key) {
+        Some(Value::String(path)) if !path.is_empty() => vec![PathBuf::from(path)],
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn hex(digest: &[u8]) -> String {
+    digest.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn hash_file(path: &Path) -> io::Result<(String, u64)> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut bytes = 0u64;
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        bytes += read as u64;
+    }
+    Ok((hex(&hasher.finalize()), bytes))
+}
+
+/// One `relative/path<TAB>size[<TAB>sha256]` line per file in sorted order,
+/// with `/` separators so the same data hashes identically on every platform.
+fn hash_manifest(dir: &Path, full_content: bool) -> io::Result<(HashKind, String, usize, u64)> {
+    let mut files = Vec::new();
+    collect_files(dir, dir, &mut files)?;
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    let mut bytes = 0u64;
+    for relative in &files {
+        let path = dir.join(relative);
+        let size = fs::metadata(&path)?.len();
+        bytes += size;
+        let mut line = format!("{relative}\t{size}");
+        if full_content {
+            line.push('\t');
+            line.push_str(&hash_file(&path)?.0);
+        }
+        line.push('\n');
+        hasher.update(line.as_bytes());
+    }
+    Ok((
+        HashKind::Manifest,
+        hex(&hasher.finalize()),
+        files.len(),
+        bytes,
+    ))
+}
+
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<String>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let path = entry.path();
+        if file_type.is_dir() {
+            collect_files(root, &path, files)?;
+        } else if file_type.is_file() {
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            let parts: Vec<String> = relative
+                .components()
+                .map(|part| part.as_os_str().to_string_lossy().to_string())
+                .collect();
+            files.push(parts.join("/"));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::{RunCompletePayload, RunMode};
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LastCompletion {
+    /// `None` for runs that had no run directory.
+    pub run_id: Option<String>,
+    #[serde(flatten)]
+    pub payload: RunCompletePayload,
+}
+
+/// The most recent `pioneer-run-complete` per workflow, for a frontend that
+/// reloaded while the event was in flight. A new run of the same workflow
+/// clears the entry, so a stale outcome is never mistaken for the new run's.
+#[derive(Default)]
+pub struct CompletionCache {
+    last: Mutex<HashMap<RunMode, LastCompletion>>,
+}
+
+impl CompletionCache {
+    pub fn record(&self, run_id: Option<&str>, payload: &RunCompletePayload) {
+        if let Ok(mut last) = self.last.lock() {
+            last.insert(
+                payload.mode,
+                LastCompletion {
+                    run_id: run_id.map(str::to_string),
+                    payload: payload.clone(),
+                },
+            );
+        }
+    }
+
+    pub fn clear(&self, mode: RunMode) {
+        if let Ok(mut last) = self.last.lock() {
+            last.remove(&mode);
+        }
+    }
+
+    /// With `run_id`, only that run's completion is returned.
+    pub fn get(&self, mode: RunMode, run_id: Option<&str>) -> Option<LastCompletion> {
+        let last = self.last.lock().ok()?;
+        last.get(&mode)
+            .filter(|completion| run_id.is_none() || completion.run_id.as_deref() == run_id)
+            .cloned()
+    }
+}
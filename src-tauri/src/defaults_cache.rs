@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tauri::PathResolver;
+
+use crate::RunMode;
+
+pub const DEFAULTS_CACHE_DIR: &str = "defaults";
+
+/// Identifies a binary without running it. A reinstall or upgrade changes the
+/// size or modification time, which is enough to invalidate the cache.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+struct BinaryFingerprint {
+    path: String,
+    size: u64,
+    modified_nanos: Option<u128>,
+}
+
+impl BinaryFingerprint {
+    fn of(binary: &Path) -> Option<Self> {
+        let metadata = fs::metadata(binary).ok()?;
+        Some(BinaryFingerprint {
+            path: binary.to_string_lossy().to_string(),
+            size: metadata.len(),
+            modified_nanos: metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|duration| duration.as_nanos()),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CachedDefaults {
+    binary: BinaryFingerprint,
+    pub version: Option<String>,
+    pub defaults: Value,
+}
+
+#[derive(Serialize, Clone, Copy, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum CacheStatus {
+    /// Cached defaults matched the current binary; Pioneer was not run.
+    Hit,
+    /// Nothing cached yet.
+    Miss,
+    /// Cached defaults came from a different or since-modified binary.
+    Stale,
+    /// No binary or no cache directory, so the cache was not consulted.
+    Unavailable,
+}
+
+pub fn cache_dir(resolver: &PathResolver) -> Option<PathBuf> {
+    resolver
+        .app_cache_dir()
+        .map(|dir| dir.join(DEFAULTS_CACHE_DIR))
+}
+
+fn cache_path(dir: &Path, mode: RunMode) -> PathBuf {
+    dir.join(format!("{}.json", mode.as_str()))
+}
+
+/// Returns the cached defaults when they were produced by `binary` as it is
+/// on disk now.
+pub fn lookup(
+    dir: Option<&Path>,
+    mode: RunMode,
+    binary: Option<&Path>,
+) -> (Option<CachedDefaults>, CacheStatus) {
+    let (Some(dir), Some(binary)) = (dir, binary) else {
+        return (None, CacheStatus::Unavailable);
+    };
+    let Ok(contents) = fs::read_to_string(cache_path(dir, mode)) else {
+        return (None, CacheStatus::Miss);
+    };
+    let Ok(cached) = serde_json::from_str::<CachedDefaults>(&contents) else {
+        return (None, CacheStatus::Stale);
+    };
+    if BinaryFingerprint::of(binary).as_ref() == Some(&cached.binary) {
+        (Some(cached), CacheStatus::Hit)
+    } else {
+        (None, CacheStatus::Stale)
+    }
+}
+
+pub fn store(
+    dir: &Path,
+    mode: RunMode,
+    binary: &Path,
+    version: Option<&str>,
+    defaults: &Value,
+) -> Result<(), String> {
+    let fingerprint = BinaryFingerprint::of(binary)
+        .ok_or_else(|| format!("Cannot read metadata for {}", binary.display()))?;
+    let cached = CachedDefaults {
+        binary: fingerprint,
+        version: version.map(str::to_string),
+        defaults: defaults.clone(),
+    };
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let pretty = serde_json::to_string_pretty(&cached).map_err(|e| e.to_string())?;
+    fs::write(cache_path(dir, mode), pretty).map_err(|e| e.to_string())
+}
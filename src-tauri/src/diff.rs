@@ -0,0 +1,163 @@
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(Debug, Default, Serialize)]
+pub struct ConfigDiff {
+    pub added: Vec<DiffEntry>,
+    pub removed: Vec<DiffEntry>,
+    pub changed: Vec<DiffEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiffEntry {
+    pub path: String,
+    pub old_value: Option<Value>,
+    pub new_value: Option<Value>,
+}
+
+/// Walks `base` and `other` the way configs are merged: objects are
+/// compared key by key, anything else is treated as a leaf and compared whole.
+pub fn diff_config(base: &Value, other: &Value) -> ConfigDiff {
+    let mut diff = ConfigDiff::default();
+    diff_into(&mut diff, "", base, other);
+    diff
+}
+
+fn diff_into(diff: &mut ConfigDiff, prefix: &str, base: &Value, other: &Value) {
+    match (base, other) {
+        (Value::Object(base_map), Value::Object(other_map)) => {
+            for (key, base_value) in base_map.iter() {
+                let path = join_path(prefix, key);
+                match other_map.get(key) {
+                    Some(other_value) => diff_into(diff, &path, base_value, other_value),
+                    None => diff.removed.push(DiffEntry {
+                        path,
+                        old_value: Some(base_value.clone()),
+                        new_value: None,
+                    }),
+                }
+            }
+            for (key, other_value) in other_map.iter() {
+                if !base_map.contains_key(key) {
+                    diff.added.push(DiffEntry {
+                        path: join_path(prefix, key),
+                        old_value: None,
+                        new_value: Some(other_value.clone()),
+                    });
+                }
+            }
+        }
+        _ => {
+            if base != other {
+                diff.changed.push(DiffEntry {
+                    path: prefix.to_string(),
+                    old_value: Some(base.clone()),
+                    new_value: Some(other.clone()),
+                });
+            }
+        }
+    }
+}
+
+pub fn join_path(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{prefix}.{key}")
+    }
+}
+
+/// Inverse of `merge_saved`: keeps only the parts of `config` that differ from
+/// `defaults`, so `merge_saved(defaults, minimized)` reproduces `config`
+/// (unless it shortens an array that is merged by index).
+/// Keys missing from `config` cannot be expressed as an override and are dropped.
+pub fn minimize_config(defaults: &Value, config: &Value) -> Value {
+    minimize_value(defaults, config).unwrap_or_else(|| Value::Object(Default::default()))
+}
+
+fn minimize_value(defaults: &Value, config: &Value) -> Option<Value> {
+    match (defaults, config) {
+        (Value::Object(default_map), Value::Object(config_map)) => {
+            let mut minimized = serde_json::Map::new();
+            for (key, value) in config_map.iter() {
+                let next = match default_map.get(key) {
+                    Some(default_value) => minimize_value(default_value, value),
+                    None => Some(value.clone()),
+                };
+                if let Some(next) = next {
+                    minimized.insert(key.clone(), next);
+                }
+            }
+            if minimized.is_empty() {
+                None
+            } else {
+                Some(Value::Object(minimized))
+            }
+        }
+        _ if defaults == config => None,
+        _ => Some(config.clone()),
+    }
+}
+
+/// Resolves a dotted path produced by `join_path` back into `value`.
+pub fn lookup<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .try_fold(value, |current, key| current.get(key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff_config, lookup, minimize_config};
+    use crate::merge::merge_saved;
+    use serde_json::json;
+
+    #[test]
+    fn diff_reports_nested_paths() {
+        let base = json!({"a": 1, "b": {"c": true, "d": "x"}});
+        let other = json!({"a": 2, "b": {"c": true, "e": [1]}});
+        let diff = diff_config(&base, &other);
+        let paths = |entries: &[super::DiffEntry]| {
+            entries.iter().map(|e| e.path.clone()).collect::<Vec<_>>()
+        };
+        assert_eq!(paths(&diff.changed), ["a"]);
+        assert_eq!(paths(&diff.removed), ["b.d"]);
+        assert_eq!(paths(&diff.added), ["b.e"]);
+    }
+
+    #[test]
+    fn minimize_keeps_only_overrides() {
+        let defaults = json!({"a": 1, "b": {"c": true, "d": [1, 2]}, "e": "x"});
+        let config = json!({"a": 1, "b": {"c": false, "d": [1, 2]}, "e": "x", "f": 3});
+        let minimized = minimize_config(&defaults, &config);
+        assert_eq!(minimized, json!({"b": {"c": false}, "f": 3}));
+        assert_eq!(merge_saved(&defaults, &minimized), config);
+    }
+
+    #[test]
+    fn minimize_keeps_only_a_deeply_nested_override() {
+        let defaults = json!({
+            "a": 1,
+            "b": {"c": 2, "d": {"e": 3, "f": {"g": 4, "h": [5, 6]}}}
+        });
+        let config = json!({
+            "a": 1,
+            "b": {"c": 2, "d": {"e": 3, "f": {"g": 40, "h": [5, 6]}}}
+        });
+        let minimized = minimize_config(&defaults, &config);
+        assert_eq!(minimized, json!({"b": {"d": {"f": {"g": 40}}}}));
+        assert_eq!(merge_saved(&defaults, &minimized), config);
+    }
+
+    #[test]
+    fn minimizing_the_defaults_leaves_an_empty_object() {
+        let defaults = json!({"a": {"b": 1}});
+        assert_eq!(minimize_config(&defaults, &defaults), json!({}));
+    }
+
+    #[test]
+    fn lookup_follows_joined_paths() {
+        let value = json!({"a": {"b": {"c": 3}}});
+        assert_eq!(lookup(&value, "a.b.c"), Some(&json!(3)));
+        assert_eq!(lookup(&value, "a.x"), None);
+    }
+}
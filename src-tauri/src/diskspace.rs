@@ -0,0 +1,179 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::PathResolver;
+
+use crate::encoding::strip_bom;
+use crate::preflight::output_directories;
+use crate::RunMode;
+
+pub const DISK_SPACE_FILENAME: &str = "disk_space.json";
+const GIB: u64 = 1024 * 1024 * 1024;
+const DEFAULT_MIN_FREE_BYTES: u64 = 5 * GIB;
+
+/// Free space an output volume should have before a run starts. Persisted in
+/// `disk_space.json`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[serde(default)]
+pub struct DiskSpaceSettings {
+    pub min_free_bytes: u64,
+}
+
+impl Default for DiskSpaceSettings {
+    fn default() -> Self {
+        Self {
+            min_free_bytes: DEFAULT_MIN_FREE_BYTES,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct DiskSpaceCheck {
+    pub key: String,
+    pub path: String,
+    /// The directory measured: `path`, or its nearest existing ancestor.
+    pub probed_path: Option<String>,
+    /// `None` when the platform call failed or is unsupported here.
+    pub free_bytes: Option<u64>,
+    pub min_free_bytes: u64,
+    pub low: bool,
+    pub message: Option<String>,
+}
+
+fn settings_path(resolver: &PathResolver) -> Option<PathBuf> {
+    Some(resolver.app_config_dir()?.join(DISK_SPACE_FILENAME))
+}
+
+pub fn load_settings(resolver: &PathResolver) -> DiskSpaceSettings {
+    settings_path(resolver)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(strip_bom(&contents)).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_settings(resolver: &PathResolver, settings: &DiskSpaceSettings) -> Result<(), String> {
+    let path =
+        settings_path(resolver).ok_or_else(|| "App config directory is unavailable".to_string())?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let pretty = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    fs::write(path, pretty).map_err(|e| e.to_string())
+}
+
+/// Measures the volume under each of the workflow's output directories.
+/// Directories on the same volume are each reported; the numbers match.
+/// Relative directories are resolved against the working directory Pioneer
+/// inherits from the GUI, so the search for an existing ancestor ends at a
+/// real root rather than an empty path.
+pub fn check_disk_space(
+    mode: RunMode,
+    config: &Value,
+    settings: DiskSpaceSettings,
+) -> Vec<DiskSpaceCheck> {
+    output_directories(mode, config)
+        .into_iter()
+        .map(|(key, dir)| {
+            let absolute = std::path::absolute(&dir).unwrap_or_else(|_| dir.clone());
+            let probed = absolute.ancestors().find(|ancestor| ancestor.is_dir());
+            let free_bytes = probed.and_then(free_space);
+            let low = free_bytes.is_some_and(|free| free < settings.min_free_bytes);
+            let message = match free_bytes {
+                Some(free) if low => Some(format!(
+                    "Only {} free on the volume holding {} ({} recommended)",
+                    format_bytes(free),
+                    dir.display(),
+                    format_bytes(settings.min_free_bytes)
+                )),
+                Some(_) => None,
+                None => Some(format!(
+                    "Could not determine free space for {}",
+                    dir.display()
+                )),
+            };
+            DiskSpaceCheck {
+                key,
+                path: dir.to_string_lossy().to_string(),
+                probed_path: probed.map(|path| path.to_string_lossy().to_string()),
+                free_bytes,
+                min_free_bytes: settings.min_free_bytes,
+                low,
+                message,
+            }
+        })
+        .collect()
+}
+
+fn format_bytes(bytes: u64) -> String {
+    format!("{:.1} GiB", bytes as f64 / GIB as f64)
+}
+
+#[cfg(unix)]
+fn free_space(dir: &Path) -> Option<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = CString::new(dir.as_os_str().as_bytes()).ok()?;
+    let mut stats = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+    // SAFETY: `path` is NUL-terminated and `stats` is only read after
+    // statvfs reports that it filled it in.
+    if unsafe { libc::statvfs(path.as_ptr(), stats.as_mut_ptr()) } != 0 {
+        return None;
+    }
+    let stats = unsafe { stats.assume_init() };
+    // Blocks available to unprivileged users, not the root-reserved total.
+    #[allow(clippy::unnecessary_cast)]
+    Some(stats.f_bavail as u64 * stats.f_frsize as u64)
+}
+
+/// Asks .NET through PowerShell, as `total_memory` does, to avoid a Windows
+/// API dependency. UNC shares have no drive letter and report nothing.
+#[cfg(windows)]
+fn free_space(dir: &Path) -> Option<u64> {
+    use std::path::Component;
+
+    let Some(Component::Prefix(prefix)) = dir.components().next() else {
+        return None;
+    };
+    let drive = prefix.as_os_str().to_string_lossy().replace('\'', "");
+    let output = std::process::Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-Command",
+            &format!("([System.IO.DriveInfo]::new('{drive}')).AvailableFreeSpace"),
+        ])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+#[cfg(not(any(unix, windows)))]
+fn free_space(_dir: &Path) -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_disk_space, format_bytes, DiskSpaceSettings, DEFAULT_MIN_FREE_BYTES};
+    use crate::RunMode;
+    use serde_json::json;
+    use std::path::Path;
+
+    #[test]
+    fn reports_in_the_same_unit_as_the_default() {
+        assert_eq!(format_bytes(DEFAULT_MIN_FREE_BYTES), "5.0 GiB");
+        assert_eq!(format_bytes(1536 * 1024 * 1024), "1.5 GiB");
+    }
+
+    #[test]
+    fn relative_directories_are_probed_at_an_absolute_ancestor() {
+        let config = json!({"paths": {"results": "not/created/yet"}});
+        let checks = check_disk_space(RunMode::SearchDia, &config, DiskSpaceSettings::default());
+        let probed = checks[0]
+            .probed_path
+            .as_deref()
+            .expect("an existing ancestor");
+        assert!(Path::new(probed).is_absolute());
+    }
+}
@@ -0,0 +1,141 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+use crate::atomic::write_atomic;
+
+const UTF8_BOM: char = '\u{feff}';
+
+#[derive(Serialize, Debug)]
+pub struct FileNormalization {
+    pub path: String,
+    pub bom_removed: bool,
+    pub line_endings_changed: bool,
+    /// Whether the file was rewritten.
+    pub normalized: bool,
+}
+
+/// Editors on Windows often prefix UTF-8 files with a byte-order mark, which
+/// serde_json rejects.
+pub fn strip_bom(contents: &str) -> &str {
+    contents.strip_prefix(UTF8_BOM).unwrap_or(contents)
+}
+
+/// Parses a hand-edited config that may contain `//` or `/* */` comments and
+/// trailing commas. Strict JSON is parsed as is; otherwise comments are
+/// blanked out (keeping line numbers in errors right) and trailing commas
+/// dropped before parsing again, so the result is the same `Value` the file
+/// would give without them.
+pub fn parse_lenient(contents: &str) -> Result<Value, serde_json::Error> {
+    let contents = strip_bom(contents);
+    serde_json::from_str(contents)
+        .or_else(|_| serde_json::from_str(&drop_trailing_commas(&blank_comments(contents))))
+}
+
+/// Replaces comments outside strings with spaces, keeping their newlines.
+fn blank_comments(contents: &str) -> String {
+    let mut out = String::with_capacity(contents.len());
+    let mut chars = contents.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push(c);
+                        break;
+                    }
+                    out.push(' ');
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                out.push_str("  ");
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    out.push(if c == '\n' { c } else { ' ' });
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Drops commas outside strings that are followed only by whitespace before
+/// a closing `}` or `]`.
+fn drop_trailing_commas(contents: &str) -> String {
+    let chars: Vec<char> = contents.chars().collect();
+    let mut out = String::with_capacity(contents.len());
+    let mut in_string = false;
+    let mut idx = 0;
+    while idx < chars.len() {
+        let c = chars[idx];
+        idx += 1;
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' if idx < chars.len() => {
+                    out.push(chars[idx]);
+                    idx += 1;
+                }
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        if c == '"' {
+            in_string = true;
+        } else if c == ',' {
+            let next = chars[idx..].iter().find(|c| !c.is_whitespace());
+            if matches!(next, Some('}') | Some(']')) {
+                continue;
+            }
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Rewrites a JSON config without a BOM and with `\n` line endings. The file
+/// must be valid UTF-8 JSON; it is left untouched when already normalized.
+pub fn normalize_config_file(path: &Path) -> Result<FileNormalization, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {} as UTF-8: {e}", path.display()))?;
+    let without_bom = strip_bom(&contents);
+    serde_json::from_str::<Value>(without_bom)
+        .map_err(|e| format!("{} is not valid JSON: {e}", path.display()))?;
+
+    // JSON strings cannot hold a raw `\r`, so every one is a line ending.
+    let line_endings_changed = without_bom.contains('\r');
+    let normalized = without_bom.replace("\r\n", "\n").replace('\r', "\n");
+    let bom_removed = without_bom.len() != contents.len();
+    let changed = bom_removed || line_endings_changed;
+    if changed {
+        write_atomic(path, normalized).map_err(|e| e.to_string())?;
+    }
+    Ok(FileNormalization {
+        path: path.to_string_lossy().to_string(),
+        bom_removed,
+        line_endings_changed,
+        normalized: changed,
+    })
+}
@@ -0,0 +1,152 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::path::Path;
+
+use crate::memory::total_memory;
+
+/// Variables whose names start with one of these can change what Pioneer
+/// computes or how fast, so they are part of the record.
+const RECORDED_ENV_PREFIXES: &[&str] = &[
+    "JULIA_",
+    "PIONEER_",
+    "OMP_",
+    "OPENBLAS_",
+    "MKL_",
+    "LANG",
+    "LC_",
+];
+/// Name fragments marking a variable whose value must not be written down.
+const SENSITIVE_NAME_MARKERS: &[&str] = &[
+    "TOKEN",
+    "SECRET",
+    "PASSWORD",
+    "PASSWD",
+    "CREDENTIAL",
+    "AUTH",
+    "KEY",
+];
+const REDACTED: &str = "<REDACTED>";
+
+/// The machine and software a run executed on, recorded in `run.json` next to
+/// the config snapshot and input hashes.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RunEnvironment {
+    pub os: String,
+    pub os_version: Option<String>,
+    pub arch: String,
+    pub cpu_model: Option<String>,
+    pub logical_cores: Option<usize>,
+    pub total_memory_bytes: Option<u64>,
+    pub pioneer_path: String,
+    pub pioneer_version: Option<String>,
+    pub gui_version: String,
+    /// Relevant environment variables, with sensitive values redacted.
+    pub env_vars: BTreeMap<String, String>,
+}
+
+pub fn capture_environment(pioneer: &Path, pioneer_version: Option<&str>) -> RunEnvironment {
+    let env_vars = env::vars()
+        .filter(|(name, _)| {
+            RECORDED_ENV_PREFIXES
+                .iter()
+                .any(|prefix| name.starts_with(prefix))
+        })
+        .map(|(name, value)| {
+            let value = redact_value(&name, value);
+            (name, value)
+        })
+        .collect();
+
+    RunEnvironment {
+        os: env::consts::OS.to_string(),
+        os_version: os_version(),
+        arch: env::consts::ARCH.to_string(),
+        cpu_model: cpu_model(),
+        logical_cores: std::thread::available_parallelism()
+            .ok()
+            .map(|cores| cores.get()),
+        total_memory_bytes: total_memory(),
+        pioneer_path: pioneer.to_string_lossy().to_string(),
+        pioneer_version: pioneer_version.map(str::to_string),
+        gui_version: env!("CARGO_PKG_VERSION").to_string(),
+        env_vars,
+    }
+}
+
+/// Hides values of credential-like variables outright, and strips the
+/// `user:password@` part of URLs (package servers often carry one).
+pub fn redact_value(name: &str, value: String) -> String {
+    let upper = name.to_ascii_uppercase();
+    if SENSITIVE_NAME_MARKERS
+        .iter()
+        .any(|marker| upper.contains(marker))
+    {
+        return REDACTED.to_string();
+    }
+    match (value.find("://"), value.find('@')) {
+        (Some(scheme_end), Some(at)) if at > scheme_end => {
+            format!("{}{REDACTED}{}", &value[..scheme_end + 3], &value[at..])
+        }
+        _ => value,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn os_version() -> Option<String> {
+    let release = std::fs::read_to_string("/etc/os-release").ok()?;
+    release
+        .lines()
+        .find_map(|line| line.strip_prefix("PRETTY_NAME="))
+        .map(|name| name.trim_matches('"').to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn cpu_model() -> Option<String> {
+    let cpuinfo = std::fs::read_to_string("/proc/cpuinfo").ok()?;
+    cpuinfo
+        .lines()
+        .find(|line| line.starts_with("model name"))
+        .and_then(|line| line.split_once(':'))
+        .map(|(_, model)| model.trim().to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn os_version() -> Option<String> {
+    command_output("sw_vers", &["-productVersion"])
+}
+
+#[cfg(target_os = "macos")]
+fn cpu_model() -> Option<String> {
+    command_output("sysctl", &["-n", "machdep.cpu.brand_string"])
+}
+
+#[cfg(windows)]
+fn os_version() -> Option<String> {
+    command_output("cmd", &["/C", "ver"])
+}
+
+#[cfg(windows)]
+fn cpu_model() -> Option<String> {
+    env::var("PROCESSOR_IDENTIFIER").ok()
+}
+
+#[cfg(any(target_os = "macos", windows))]
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new(program)
+        .args(args)
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn os_version() -> Option<String> {
+    None
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn cpu_model() -> Option<String> {
+    None
+}
@@ -0,0 +1,190 @@
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+
+const DEFAULT_CLEAVAGE_REGEX: &str = "[KR][^_|$]";
+
+#[derive(Serialize, Clone, Copy, Debug)]
+pub struct PrecursorEstimate {
+    pub proteins: u64,
+    pub peptides: u64,
+    pub precursors: u64,
+}
+
+/// Rough in-silico digest of the configured FASTA files using the build
+/// config's digestion settings. Variable modifications are not enumerated, so
+/// the precursor count is a lower bound when `variable_mods` is populated.
+pub fn estimate_build_precursors(config: &Value) -> Result<PrecursorEstimate, String> {
+    let fasta_paths: Vec<&str> = config
+        .get("fasta_paths")
+        .and_then(Value::as_array)
+        .map(|paths| paths.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    if fasta_paths.is_empty() {
+        return Err("Build config has no `fasta_paths`".into());
+    }
+
+    let digest = config.get("fasta_digest_params");
+    let param = |key: &str, default: u64| {
+        digest
+            .and_then(|d| d.get(key))
+            .and_then(Value::as_u64)
+            .unwrap_or(default)
+    };
+    let min_length = param("min_length", 7) as usize;
+    let max_length = param("max_length", 30) as usize;
+    let missed_cleavages = param("missed_cleavages", 1) as usize;
+    let min_charge = param("min_charge", 2);
+    let max_charge = param("max_charge", 4).max(min_charge);
+    let add_decoys = digest
+        .and_then(|d| d.get("add_decoys"))
+        .and_then(Value::as_bool)
+        .unwrap_or(true);
+    let cleavage = digest
+        .and_then(|d| d.get("cleavage_regex"))
+        .and_then(Value::as_str)
+        .unwrap_or(DEFAULT_CLEAVAGE_REGEX);
+    let cleavage = Regex::new(cleavage).map_err(|e| format!("Invalid cleavage_regex: {e}"))?;
+
+    let mut proteins = 0u64;
+    let mut peptides = HashSet::new();
+    for path in fasta_paths {
+        let contents =
+            fs::read_to_string(path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+        for sequence in fasta_sequences(&contents) {
+            proteins += 1;
+            digest_sequence(
+                &sequence,
+                &cleavage,
+                missed_cleavages,
+                min_length..=max_length,
+                &mut peptides,
+            );
+        }
+    }
+
+    let peptides = peptides.len() as u64;
+    let charges = max_charge - min_charge + 1;
+    let decoy_factor = if add_decoys { 2 } else { 1 };
+    Ok(PrecursorEstimate {
+        proteins,
+        peptides,
+        precursors: peptides * charges * decoy_factor,
+    })
+}
+
+fn fasta_sequences(contents: &str) -> Vec<String> {
+    let mut sequences = Vec::new();
+    let mut current = String::new();
+    for line in contents.lines() {
+        if line.starts_with('>') {
+            if !current.is_empty() {
+                sequences.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.extend(line.chars().filter(|c| c.is_ascii_alphabetic()));
+        }
+    }
+    if !current.is_empty() {
+        sequences.push(current);
+    }
+    sequences
+}
+
+fn digest_sequence(
+    sequence: &str,
+    cleavage: &Regex,
+    missed_cleavages: usize,
+    lengths: std::ops::RangeInclusive<usize>,
+    peptides: &mut HashSet<String>,
+) {
+    // Pioneer's cleavage regexes match the residue before the cut plus the
+    // following one, so the cut lands one past the match start. Matches may
+    // overlap (e.g. "KK"), so each search restarts at the previous cut.
+    // A zero-length match places no cut, and a cut at the very end would only
+    // add an empty peptide.
+    let mut cuts = vec![0];
+    let mut pos = 0;
+    while pos < sequence.len() {
+        let Some(m) = cleavage.find_at(sequence, pos) else {
+            break;
+        };
+        let cut = m.start() + 1;
+        if cut >= sequence.len() {
+            break;
+        }
+        if !m.is_empty() && sequence.is_char_boundary(cut) {
+            cuts.push(cut);
+        }
+        pos = cut;
+    }
+    if *cuts.last().unwrap_or(&0) != sequence.len() {
+        cuts.push(sequence.len());
+    }
+
+    for start in 0..cuts.len() {
+        for end in (start + 1)..cuts.len().min(start + missed_cleavages + 2) {
+            let peptide = &sequence[cuts[start]..cuts[end]];
+            if lengths.contains(&peptide.len()) {
+                peptides.insert(peptide.to_string());
+            }
+        }
+    }
+}
+
+/// Pulls the running count out of predict-stage lines such as
+/// "Predicted 120,000 precursors".
+pub fn parse_predicted_count(line: &str, pattern: &Regex) -> Option<u64> {
+    let digits = pattern.captures(line)?.get(1)?.as_str().replace(',', "");
+    digits.parse().ok()
+}
+
+pub fn predicted_count_regex() -> Regex {
+    Regex::new(r"(?i)predicted\s+([\d,]+)").expect("valid predicted-count regex")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::digest_sequence;
+    use regex::Regex;
+    use std::collections::HashSet;
+
+    fn digest(sequence: &str, cleavage: &str, missed: usize) -> Vec<String> {
+        let mut peptides = HashSet::new();
+        digest_sequence(
+            sequence,
+            &Regex::new(cleavage).unwrap(),
+            missed,
+            1..=50,
+            &mut peptides,
+        );
+        let mut peptides: Vec<String> = peptides.into_iter().collect();
+        peptides.sort();
+        peptides
+    }
+
+    #[test]
+    fn cuts_after_each_cleavage_site() {
+        assert_eq!(
+            digest("PEPKTIDERAB", "[KR][^P]", 0),
+            ["AB", "PEPK", "TIDER"]
+        );
+        assert_eq!(
+            digest("PEPKTIDERAB", "[KR][^P]", 1),
+            ["AB", "PEPK", "PEPKTIDER", "TIDER", "TIDERAB"]
+        );
+    }
+
+    #[test]
+    fn a_site_on_the_last_residue_adds_no_empty_peptide() {
+        assert_eq!(digest("PEPK", "[KR]", 0), ["PEPK"]);
+    }
+
+    #[test]
+    fn zero_length_matches_do_not_panic() {
+        assert_eq!(digest("PEPKTIDE", "K?", 0), ["PEPK", "TIDE"]);
+        assert_eq!(digest("PEPTIDE", "$", 0), ["PEPTIDE"]);
+    }
+}
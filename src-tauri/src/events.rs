@@ -0,0 +1,326 @@
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Manager, Window};
+
+use crate::batch::BatchProgressPayload;
+use crate::bridge::EventBridge;
+use crate::hooks::{HookCompletePayload, HookOutputPayload};
+use crate::loghealth::{LogHealth, Severity};
+use crate::pacing::{StageDuration, StageSummaryPayload, StageTimingPayload};
+use crate::pipeline::{PipelineCompletePayload, PipelineProgressPayload};
+use crate::recorder::EventRecorder;
+use crate::taskbar::{TaskbarProgressPayload, TaskbarStatus};
+use crate::workspace::PruneReport;
+
+use super::{
+    ConfigVersionWarning, FailureCategory, InstallProgressPayload, LogLevelPayload, LogPayload,
+    PreparePayload, ProgressPayload, RunCompletePayload, RunMode, RunStartedPayload, RunState,
+    RunStatus, StageDebugPayload,
+};
+
+pub const RUN_STARTED: &str = "pioneer-run-started";
+pub const PREPARE_PROGRESS: &str = "pioneer-prepare-progress";
+pub const PROGRESS: &str = "pioneer-progress";
+pub const LOG: &str = "pioneer-log";
+pub const LOG_LEVEL: &str = "pioneer-log-level";
+pub const TERMINAL_WARNING: &str = "pioneer-terminal-warning";
+pub const RUN_COMPLETE: &str = "pioneer-run-complete";
+pub const CONFIG_VERSION_WARNING: &str = "pioneer-config-version-warning";
+pub const INSTALL_PROGRESS: &str = "pioneer-install-progress";
+pub const STAGE_DEBUG: &str = "pioneer-stage-debug";
+pub const STAGE_TIMING: &str = "pioneer-stage-timing";
+pub const STAGE_SUMMARY: &str = "pioneer-stage-summary";
+pub const RUN_STATE: &str = "pioneer-run-state";
+pub const RUNS_PRUNED: &str = "pioneer-runs-pruned";
+pub const BATCH_PROGRESS: &str = "pioneer-batch-progress";
+pub const TASKBAR_PROGRESS: &str = "pioneer-taskbar-progress";
+pub const HOOK_OUTPUT: &str = "pioneer-hook-output";
+pub const HOOK_COMPLETE: &str = "pioneer-hook-complete";
+pub const PIPELINE_PROGRESS: &str = "pioneer-pipeline-progress";
+pub const PIPELINE_COMPLETE: &str = "pioneer-pipeline-complete";
+
+/// Emits to `window` and mirrors the event to the WebSocket bridge and any
+/// run recording it. All
+/// backend events go through here (or `emit_all`) so every consumer sees the
+/// same stream.
+pub fn emit<S: Serialize + Clone>(window: &Window, event: &str, payload: S) -> tauri::Result<()> {
+    forward(&window.app_handle(), event, &payload);
+    window.emit(event, payload)
+}
+
+pub fn emit_all<S: Serialize + Clone>(
+    app_handle: &AppHandle,
+    event: &str,
+    payload: S,
+) -> tauri::Result<()> {
+    forward(app_handle, event, &payload);
+    app_handle.emit_all(event, payload)
+}
+
+fn forward<S: Serialize>(app_handle: &AppHandle, event: &str, payload: &S) {
+    if let Some(bridge) = app_handle.try_state::<EventBridge>() {
+        bridge.publish(event, payload);
+    }
+    if let Some(recorder) = app_handle.try_state::<EventRecorder>() {
+        recorder.record(event, payload);
+    }
+}
+
+#[derive(Serialize)]
+pub struct EventDescription {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub example: Value,
+}
+
+fn describe<T: Serialize>(
+    name: &'static str,
+    description: &'static str,
+    example: T,
+) -> EventDescription {
+    EventDescription {
+        name,
+        description,
+        example: serde_json::to_value(example).unwrap_or(Value::Null),
+    }
+}
+
+/// Every event the backend emits, with an example payload serialized from the
+/// same structs the emitters use so the shapes cannot drift.
+pub fn describe_events() -> Vec<EventDescription> {
+    let mode = RunMode::SearchDia;
+    vec![
+        describe(
+            RUN_STARTED,
+            "A run was accepted and its config and log files were written.",
+            RunStartedPayload {
+                mode,
+                run_id: Some("searchDia_1700000000".into()),
+                log_path: "/tmp/pioneer_run.log".into(),
+                config_path: "/tmp/search_params.json".into(),
+                persisted_path: Some("/config/searchdia.json".into()),
+                max_files: None,
+                stdout_log_path: None,
+                stderr_log_path: None,
+                storage_warnings: Vec::new(),
+                resume: None,
+            },
+        ),
+        describe(
+            PREPARE_PROGRESS,
+            "GUI-side preparation before Pioneer is spawned.",
+            PreparePayload {
+                mode,
+                step: "Staging 2 MS files: 1/2".into(),
+                current: Some(1),
+                total: Some(2),
+            },
+        ),
+        describe(
+            PROGRESS,
+            "The run advanced to a new stage.",
+            ProgressPayload {
+                mode,
+                stage_key: "first".into(),
+                stage_label: "Running first pass search".into(),
+                progress: 50.0,
+                eta_seconds: Some(1800),
+            },
+        ),
+        describe(
+            LOG,
+            "One line of Pioneer output.",
+            LogPayload {
+                mode,
+                stream: "stdout",
+                line: "Starting first pass search".into(),
+                severity: None,
+            },
+        ),
+        describe(
+            LOG_LEVEL,
+            "A line of Pioneer output classified as an error or warning, with the run's counts so far.",
+            LogLevelPayload {
+                mode,
+                run_id: Some("searchDia_1700000000".into()),
+                stream: "stderr",
+                severity: Severity::Warning,
+                line: "┌ Warning: Only 3 PSMs passed the first-pass FDR threshold".into(),
+                error_count: 0,
+                warning_count: 1,
+            },
+        ),
+        describe(
+            TERMINAL_WARNING,
+            "The external terminal could not be launched; the payload is the message.",
+            "Could not launch external terminal: No compatible terminal found",
+        ),
+        describe(
+            RUN_COMPLETE,
+            "Pioneer exited, or the run was cancelled (`cancelled` is true).",
+            RunCompletePayload {
+                mode,
+                success: false,
+                exit_code: Some(1),
+                message: Some("Pioneer exited with status Some(1)".into()),
+                failure_category: Some(FailureCategory::MissingInput),
+                outputs: None,
+                cancelled: false,
+                error_count: 1,
+                warning_count: 0,
+                log_health: Some(LogHealth {
+                    errors: 1,
+                    warnings: 0,
+                    error_samples: vec!["ERROR: FASTA file not found".into()],
+                    warning_samples: Vec::new(),
+                }),
+            },
+        ),
+        describe(
+            CONFIG_VERSION_WARNING,
+            "A persisted config was last used with a different Pioneer version.",
+            ConfigVersionWarning {
+                mode,
+                persisted_version: "0.9.0".into(),
+                current_version: "1.0.0".into(),
+                message: "The saved SearchDIA parameters were last used with Pioneer 0.9.0, but Pioneer 1.0.0 is now installed. Review the parameters before running.".into(),
+            },
+        ),
+        describe(
+            INSTALL_PROGRESS,
+            "Bytes received so far while install_binary downloads a Pioneer release.",
+            InstallProgressPayload {
+                url: "https://example.com/releases/pioneer".into(),
+                downloaded: 1_048_576,
+                total: Some(52_428_800),
+            },
+        ),
+        describe(
+            STAGE_DEBUG,
+            "Per-line stage matching trace, only for runs started with `stage_debug`.",
+            StageDebugPayload {
+                mode,
+                line: "Starting first pass search".into(),
+                matched_stage: Some("first".into()),
+                stage_index: 3,
+                stage_key: "first".into(),
+            },
+        ),
+        describe(
+            STAGE_TIMING,
+            "The run left a stage; `elapsed_ms` is the time spent in it.",
+            StageTimingPayload {
+                mode,
+                run_id: Some("searchDia_1700000000".into()),
+                stage_key: "quant".into(),
+                elapsed_ms: 1_200_000,
+                next_stage_key: "finishing".into(),
+            },
+        ),
+        describe(
+            STAGE_SUMMARY,
+            "Time spent in each stage, sent when Pioneer exits.",
+            StageSummaryPayload {
+                mode,
+                run_id: Some("searchDia_1700000000".into()),
+                stages: vec![
+                    StageDuration {
+                        stage_key: "first".into(),
+                        elapsed_ms: 600_000,
+                    },
+                    StageDuration {
+                        stage_key: "quant".into(),
+                        elapsed_ms: 1_200_000,
+                    },
+                ],
+                total_ms: 1_800_000,
+            },
+        ),
+        describe(
+            RUN_STATE,
+            "A run was paused or resumed.",
+            RunStatus {
+                run_id: "searchDia_1700000000".into(),
+                mode,
+                state: RunState::Paused,
+                pid: Some(4242),
+            },
+        ),
+        describe(
+            RUNS_PRUNED,
+            "Startup pruning removed run directories beyond the retention policy.",
+            PruneReport {
+                pruned: vec!["searchDia_1690000000".into()],
+                bytes_reclaimed: 10_485_760,
+                skipped_active: Vec::new(),
+                errors: Vec::new(),
+            },
+        ),
+        describe(
+            BATCH_PROGRESS,
+            "Overall completion of a batch declared with `start_batch`.",
+            BatchProgressPayload {
+                batch_id: "nightly".into(),
+                total_runs: 20,
+                completed_runs: 7,
+                failed_runs: 0,
+                progress: 37.5,
+                done: false,
+            },
+        ),
+        describe(
+            TASKBAR_PROGRESS,
+            "Rounded progress and status for an OS taskbar or dock badge; sent only when either changes, and with status `none` when the run finishes.",
+            TaskbarProgressPayload {
+                mode,
+                status: TaskbarStatus::Running,
+                percent: Some(42),
+            },
+        ),
+        describe(
+            HOOK_OUTPUT,
+            "One line of output from a run's `post_run_command`.",
+            HookOutputPayload {
+                mode,
+                run_id: Some("searchDia_1700000000".into()),
+                stream: "stdout",
+                line: "Uploading results".into(),
+            },
+        ),
+        describe(
+            HOOK_COMPLETE,
+            "A run's `post_run_command` exited (or could not start); sent after `pioneer-run-complete`, whose outcome it never changes.",
+            HookCompletePayload {
+                mode,
+                run_id: Some("searchDia_1700000000".into()),
+                success: true,
+                exit_code: Some(0),
+                message: None,
+            },
+        ),
+        describe(
+            PIPELINE_PROGRESS,
+            "Progress of a `run_pipeline` build-then-search across both phases; `stage_index` indexes the stages `run_pipeline` returned.",
+            PipelineProgressPayload {
+                pipeline_id: "pipeline_1700000000".into(),
+                phase: mode,
+                stage_index: 8,
+                stage_key: "first".into(),
+                stage_label: "Running first pass search".into(),
+                progress: 70.8,
+            },
+        ),
+        describe(
+            PIPELINE_COMPLETE,
+            "A `run_pipeline` finished, or stopped at the phase in `failed_phase`.",
+            PipelineCompletePayload {
+                pipeline_id: "pipeline_1700000000".into(),
+                success: true,
+                failed_phase: None,
+                library_path: Some("/libraries/human.poin".into()),
+                search_run_id: Some("searchDia_1700003600".into()),
+                message: None,
+            },
+        ),
+    ]
+}
@@ -0,0 +1,184 @@
+use serde::Serialize;
+use std::process::ExitStatus;
+
+/// How many trailing stderr lines run_process keeps around for classification.
+pub const STDERR_TAIL_LINES: usize = 200;
+
+#[derive(Clone, Copy, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum FailureCategory {
+    OutOfMemory,
+    DiskFull,
+    Permission,
+    MissingInput,
+    /// Stopped for running past the request's `timeout_secs`.
+    TimedOut,
+    Unknown,
+}
+
+const OOM_PATTERNS: &[&str] = &[
+    "out of memory",
+    "outofmemoryerror",
+    "cannot allocate memory",
+    "memory allocation failed",
+    "oom-kill",
+];
+const DISK_FULL_PATTERNS: &[&str] = &[
+    "no space left on device",
+    "disk full",
+    "enospc",
+    "disk quota exceeded",
+    "not enough space on the disk",
+];
+const PERMISSION_PATTERNS: &[&str] = &[
+    "permission denied",
+    "access is denied",
+    "eacces",
+    "operation not permitted",
+];
+const MISSING_INPUT_PATTERNS: &[&str] = &[
+    "no such file or directory",
+    "file not found",
+    "does not exist",
+    "enoent",
+    "cannot find the path",
+];
+
+const SIGKILL: i32 = 9;
+
+/// Best-effort classification of a failed run. A SIGKILL (or the shell's
+/// 128+9 = 137 exit code) is almost always the kernel OOM killer on Linux, so
+/// it wins over the stderr text; otherwise the most recent matching line decides.
+pub fn classify_failure(
+    stderr_tail: &[String],
+    exit_code: Option<i32>,
+    signal: Option<i32>,
+) -> FailureCategory {
+    if signal == Some(SIGKILL) || exit_code == Some(128 + SIGKILL) {
+        return FailureCategory::OutOfMemory;
+    }
+
+    let categories = [
+        (FailureCategory::OutOfMemory, OOM_PATTERNS),
+        (FailureCategory::DiskFull, DISK_FULL_PATTERNS),
+        (FailureCategory::Permission, PERMISSION_PATTERNS),
+        (FailureCategory::MissingInput, MISSING_INPUT_PATTERNS),
+    ];
+    for line in stderr_tail.iter().rev() {
+        let normalized = line.to_lowercase();
+        for (category, patterns) in categories.iter() {
+            if patterns.iter().any(|pattern| normalized.contains(pattern)) {
+                return *category;
+            }
+        }
+    }
+    FailureCategory::Unknown
+}
+
+/// The failure message for a non-zero exit. On Unix a process killed by a
+/// signal has no exit code, so the signal is named instead.
+pub fn describe_exit(status: &ExitStatus) -> String {
+    match exit_signal(status) {
+        Some(signal) => match signal_name(signal) {
+            Some(name) => format!("Pioneer was terminated by signal {signal} ({name})"),
+            None => format!("Pioneer was terminated by signal {signal}"),
+        },
+        None => format!(
+            "Pioneer exited with status {:?}",
+            status.code().or(Some(-1))
+        ),
+    }
+}
+
+#[cfg(unix)]
+fn signal_name(signal: i32) -> Option<&'static str> {
+    // Numbers differ between Linux and macOS for some of these, so they come
+    // from libc rather than a table.
+    let name = match signal {
+        libc::SIGHUP => "SIGHUP",
+        libc::SIGINT => "SIGINT",
+        libc::SIGQUIT => "SIGQUIT",
+        libc::SIGILL => "SIGILL",
+        libc::SIGABRT => "SIGABRT",
+        libc::SIGBUS => "SIGBUS",
+        libc::SIGFPE => "SIGFPE",
+        libc::SIGKILL => "SIGKILL",
+        libc::SIGSEGV => "SIGSEGV",
+        libc::SIGPIPE => "SIGPIPE",
+        libc::SIGALRM => "SIGALRM",
+        libc::SIGTERM => "SIGTERM",
+        libc::SIGXCPU => "SIGXCPU",
+        libc::SIGXFSZ => "SIGXFSZ",
+        _ => return None,
+    };
+    Some(name)
+}
+
+#[cfg(not(unix))]
+fn signal_name(_signal: i32) -> Option<&'static str> {
+    None
+}
+
+pub fn exit_signal(status: &ExitStatus) -> Option<i32> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        status.signal()
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = status;
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{classify_failure, FailureCategory};
+
+    fn lines(text: &[&str]) -> Vec<String> {
+        text.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn sigkill_is_treated_as_out_of_memory() {
+        assert_eq!(
+            classify_failure(&[], None, Some(9)),
+            FailureCategory::OutOfMemory
+        );
+        assert_eq!(
+            classify_failure(&lines(&["Permission denied"]), Some(137), None),
+            FailureCategory::OutOfMemory
+        );
+    }
+
+    #[test]
+    fn matches_stderr_case_insensitively() {
+        let cases = [
+            ("ERROR: No space left on device", FailureCategory::DiskFull),
+            ("open: Permission Denied", FailureCategory::Permission),
+            (
+                "SystemError: file not found: a.arrow",
+                FailureCategory::MissingInput,
+            ),
+            ("OutOfMemoryError()", FailureCategory::OutOfMemory),
+            ("ERROR: something else", FailureCategory::Unknown),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(
+                classify_failure(&lines(&[line]), Some(1), None),
+                expected,
+                "{line}"
+            );
+        }
+    }
+
+    #[test]
+    fn the_most_recent_matching_line_wins() {
+        let tail = lines(&["file not found: x", "progress 50%", "disk quota exceeded"]);
+        assert_eq!(
+            classify_failure(&tail, Some(1), None),
+            FailureCategory::DiskFull
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tauri::PathResolver;
+
+use crate::runs::unix_now;
+use crate::{RunCompletePayload, RunMode};
+
+pub const HISTORY_FILENAME: &str = "run_history.jsonl";
+/// Records returned by `load_run_history` when the caller sets no limit.
+pub const DEFAULT_HISTORY_LIMIT: usize = 200;
+/// Older records are dropped once the file holds this many.
+const MAX_HISTORY_RECORDS: usize = 1000;
+
+/// One finished run. Unlike `run.json`, this is kept for runs without a run
+/// directory too, and survives the run directory being pruned.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HistoryRecord {
+    #[serde(default)]
+    pub run_id: Option<String>,
+    pub mode: RunMode,
+    pub started_at: u64,
+    pub finished_at: u64,
+    pub success: bool,
+    #[serde(default)]
+    pub cancelled: bool,
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+    pub config_path: String,
+    pub log_path: String,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// What is known about a run when it starts, completed into a
+/// `HistoryRecord` by `record_completion`.
+pub struct PendingRecord {
+    pub history_file: PathBuf,
+    pub run_id: Option<String>,
+    pub started_at: u64,
+    pub config_path: PathBuf,
+    pub log_path: PathBuf,
+}
+
+pub fn history_path(resolver: &PathResolver) -> Option<PathBuf> {
+    Some(resolver.app_config_dir()?.join(HISTORY_FILENAME))
+}
+
+pub fn record_completion(pending: &PendingRecord, payload: &RunCompletePayload) {
+    let record = HistoryRecord {
+        run_id: pending.run_id.clone(),
+        mode: payload.mode,
+        started_at: pending.started_at,
+        finished_at: unix_now(),
+        success: payload.success,
+        cancelled: payload.cancelled,
+        exit_code: payload.exit_code,
+        config_path: pending.config_path.to_string_lossy().to_string(),
+        log_path: pending.log_path.to_string_lossy().to_string(),
+        message: payload.message.clone(),
+    };
+    if let Err(err) = append(&pending.history_file, &record) {
+        eprintln!("Failed to record run history: {err}");
+    }
+}
+
+/// One JSON record per line, so an append never rewrites earlier runs; the
+/// file is only rewritten when it grows past `MAX_HISTORY_RECORDS`.
+fn append(path: &Path, record: &HistoryRecord) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let line = serde_json::to_string(record).map_err(|e| e.to_string())?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| e.to_string())?;
+    writeln!(file, "{line}").map_err(|e| e.to_string())?;
+    drop(file);
+
+    let records = read_records(path)?;
+    if records.len() > MAX_HISTORY_RECORDS {
+        let keep = &records[records.len() - MAX_HISTORY_RECORDS..];
+        let mut contents = String::new();
+        for record in keep {
+            contents.push_str(&serde_json::to_string(record).map_err(|e| e.to_string())?);
+            contents.push('\n');
+        }
+        fs::write(path, contents).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Oldest first. Lines that do not parse are skipped: the usual cause is a
+/// write cut short when the app was killed, and one bad line should not hide
+/// the rest of the history.
+fn read_records(path: &Path) -> Result<Vec<HistoryRecord>, String> {
+    if !path.is_file() {
+        return Ok(Vec::new());
+    }
+    let contents = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    Ok(contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Most recent first, at most `limit` records.
+pub fn load_history(path: &Path, limit: usize) -> Result<Vec<HistoryRecord>, String> {
+    let mut records = read_records(path)?;
+    records.reverse();
+    records.truncate(limit);
+    Ok(records)
+}
+
+pub fn clear_history(path: &Path) -> Result<(), String> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err.to_string()),
+    }
+}
@@ -0,0 +1,220 @@
+use serde::Serialize;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+use tauri::Window;
+use which::which;
+
+use crate::events;
+use crate::registry;
+use crate::RunMode;
+
+/// How long a post-run command may run when the request sets no limit.
+pub const DEFAULT_HOOK_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// A command run after Pioneer exits, with the outcome in environment
+/// variables (`PIONEER_RUN_SUCCESS`, `PIONEER_EXIT_CODE`, …).
+#[derive(Clone)]
+pub struct PostRunHook {
+    pub command: Vec<String>,
+    /// Run after failed and cancelled runs too, not only successful ones.
+    pub always: bool,
+    pub log_path: PathBuf,
+    /// The command is killed if it is still running after this long.
+    pub timeout: Duration,
+}
+
+/// What the hook is told about the run it follows.
+pub struct RunOutcome {
+    pub mode: RunMode,
+    pub run_id: Option<String>,
+    pub run_dir: Option<PathBuf>,
+    pub output_dir: Option<PathBuf>,
+    pub success: bool,
+    pub cancelled: bool,
+    pub exit_code: Option<i32>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct HookOutputPayload {
+    pub mode: RunMode,
+    pub run_id: Option<String>,
+    pub stream: &'static str,
+    pub line: String,
+}
+
+/// Reported separately from `pioneer-run-complete`, which has already been
+/// sent by the time the hook runs, so a failing hook never changes the run's
+/// own outcome.
+#[derive(Serialize, Clone)]
+pub struct HookCompletePayload {
+    pub mode: RunMode,
+    pub run_id: Option<String>,
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    /// Why the hook could not be started or was stopped, when it was.
+    pub message: Option<String>,
+}
+
+/// Checks the hook's program can be found before the run starts, so a typo
+/// surfaces now rather than hours later.
+pub fn check_hook(command: &[String]) -> Result<PathBuf, String> {
+    let program = command
+        .first()
+        .filter(|program| !program.trim().is_empty())
+        .ok_or_else(|| "The post-run command needs a program".to_string())?;
+    if Path::new(program).is_file() {
+        return Ok(PathBuf::from(program));
+    }
+    which(program).map_err(|_| format!("Post-run command `{program}` was not found"))
+}
+
+pub fn should_run(hook: &PostRunHook, outcome: &RunOutcome) -> bool {
+    hook.always || (outcome.success && !outcome.cancelled)
+}
+
+/// Runs the hook on its own thread, so a slow or hung command never holds up
+/// the run's own completion.
+pub fn spawn_post_hook(window: &Window, hook: &PostRunHook, outcome: RunOutcome) {
+    let window = window.clone();
+    let hook = hook.clone();
+    std::thread::spawn(move || run_post_hook(&window, &hook, &outcome));
+}
+
+/// Runs the hook to completion or its timeout, streaming its output as
+/// `pioneer-hook-output` and finishing with `pioneer-hook-complete`.
+fn run_post_hook(window: &Window, hook: &PostRunHook, outcome: &RunOutcome) {
+    let run_id = outcome.run_id.clone();
+    let finish = |success: bool, exit_code: Option<i32>, message: Option<String>| {
+        let _ = events::emit(
+            window,
+            events::HOOK_COMPLETE,
+            &HookCompletePayload {
+                mode: outcome.mode,
+                run_id: run_id.clone(),
+                success,
+                exit_code,
+                message,
+            },
+        );
+    };
+
+    let (program, args) = match hook.command.split_first() {
+        Some(split) => split,
+        None => return finish(false, None, Some("The post-run command is empty".into())),
+    };
+    let path_string = |path: Option<&PathBuf>| {
+        path.map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_default()
+    };
+    let mut command = Command::new(program);
+    command
+        .args(args)
+        .env("PIONEER_RUN_MODE", outcome.mode.as_str())
+        .env("PIONEER_RUN_ID", run_id.clone().unwrap_or_default())
+        .env("PIONEER_RUN_DIR", path_string(outcome.run_dir.as_ref()))
+        .env(
+            "PIONEER_OUTPUT_DIR",
+            path_string(outcome.output_dir.as_ref()),
+        )
+        .env(
+            "PIONEER_LOG_PATH",
+            hook.log_path.to_string_lossy().to_string(),
+        )
+        .env("PIONEER_RUN_SUCCESS", outcome.success.to_string())
+        .env("PIONEER_RUN_CANCELLED", outcome.cancelled.to_string())
+        .env(
+            "PIONEER_EXIT_CODE",
+            outcome
+                .exit_code
+                .map(|code| code.to_string())
+                .unwrap_or_default(),
+        )
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // Its own process group, so the timeout also stops anything it started.
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(err) => {
+            return finish(
+                false,
+                None,
+                Some(format!("Could not start the post-run command: {err}")),
+            )
+        }
+    };
+
+    let (tx, rx) = mpsc::channel();
+    if let Some(stdout) = child.stdout.take() {
+        forward_lines(stdout, "stdout", tx.clone());
+    }
+    if let Some(stderr) = child.stderr.take() {
+        forward_lines(stderr, "stderr", tx.clone());
+    }
+    drop(tx);
+    let deadline = Instant::now() + hook.timeout;
+    let timed_out = || {
+        Some(format!(
+            "The post-run command was stopped after exceeding its {}s time limit",
+            hook.timeout.as_secs()
+        ))
+    };
+    loop {
+        let (stream, line) =
+            match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(received) => received,
+                Err(RecvTimeoutError::Disconnected) => break,
+                Err(RecvTimeoutError::Timeout) => {
+                    registry::kill_run(&mut child);
+                    let _ = child.wait();
+                    return finish(false, None, timed_out());
+                }
+            };
+        let _ = events::emit(
+            window,
+            events::HOOK_OUTPUT,
+            &HookOutputPayload {
+                mode: outcome.mode,
+                run_id: run_id.clone(),
+                stream,
+                line,
+            },
+        );
+    }
+
+    // The command may close its output and keep running.
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return finish(status.success(), status.code(), None),
+            Ok(None) if Instant::now() >= deadline => {
+                registry::kill_run(&mut child);
+                let _ = child.wait();
+                return finish(false, None, timed_out());
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(100)),
+            Err(err) => return finish(false, None, Some(err.to_string())),
+        }
+    }
+}
+
+fn forward_lines<R: Read + Send + 'static>(
+    reader: R,
+    stream: &'static str,
+    tx: mpsc::Sender<(&'static str, String)>,
+) {
+    std::thread::spawn(move || {
+        for line in BufReader::new(reader).lines().map_while(Result::ok) {
+            if tx.send((stream, line)).is_err() {
+                break;
+            }
+        }
+    });
+}
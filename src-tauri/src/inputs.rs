@@ -0,0 +1,229 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use crate::capabilities::FormatRange;
+use crate::diff::{join_path, lookup};
+use crate::library::{check_library_version, FormatStatus};
+use crate::staging::scan_ms_data;
+use crate::validation::ValidationIssue;
+
+/// Key fragments marking a value as a prediction model or weights reference.
+const MODEL_KEY_MARKERS: &[&str] = &["model", "weights"];
+/// Key fragments marking an auxiliary sequence database in a search config.
+const DATABASE_KEY_MARKERS: &[&str] = &["contaminant", "decoy", "entrapment"];
+
+#[derive(Serialize, Debug)]
+pub struct AuxiliaryDatabase {
+    pub key: String,
+    pub path: String,
+    /// FASTA entries (`>` header lines), when the file could be read.
+    pub entries: Option<usize>,
+    pub problem: Option<String>,
+}
+
+/// Checks that the files a BuildSpecLib run reads before prediction exist and
+/// are readable, so a bad path fails now rather than hours into the build.
+pub fn validate_build_inputs(config: &Value) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+
+    match config.get("fasta_paths").and_then(Value::as_array) {
+        Some(paths) if !paths.is_empty() => {
+            for path in paths {
+                match path.as_str() {
+                    Some(path) => check_readable("fasta_paths", Path::new(path), &mut issues),
+                    None => issues.push(ValidationIssue::new(
+                        "fasta_paths",
+                        format!("Expected a file path, found {path}"),
+                    )),
+                }
+            }
+        }
+        _ => issues.push(ValidationIssue::new(
+            "fasta_paths",
+            "At least one FASTA file is required",
+        )),
+    }
+
+    let mut model_paths = Vec::new();
+    collect_marked_paths(config, "", MODEL_KEY_MARKERS, &mut model_paths);
+    for (key, path) in model_paths {
+        check_readable(&key, Path::new(&path), &mut issues);
+    }
+    issues
+}
+
+/// Checks the library, MS data, and any auxiliary databases a SearchDIA run
+/// reads. A library whose format is outside the `library_formats` the binary
+/// reads is reported here rather than failing partway through the search.
+pub fn validate_search_inputs(
+    config: &Value,
+    library_formats: Option<FormatRange>,
+) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+
+    match lookup(config, "paths.library").and_then(Value::as_str) {
+        Some(library) => {
+            let library = Path::new(library);
+            check_readable("paths.library", library, &mut issues);
+            if library.exists() {
+                let check = check_library_version(library, library_formats);
+                if check.status == FormatStatus::Incompatible {
+                    issues.push(ValidationIssue::new(
+                        "paths.library",
+                        check.message.unwrap_or_default(),
+                    ));
+                }
+            }
+        }
+        None => issues.push(ValidationIssue::new(
+            "paths.library",
+            "A spectral library is required",
+        )),
+    }
+
+    match lookup(config, "paths.ms_data").and_then(Value::as_str) {
+        Some(ms_data) => {
+            let ms_data = Path::new(ms_data);
+            if !ms_data.is_dir() {
+                issues.push(ValidationIssue::new(
+                    "paths.ms_data",
+                    format!("{} is not a directory", ms_data.display()),
+                ));
+            } else {
+                match scan_ms_data(ms_data) {
+                    Ok(inventory) if inventory.units.is_empty() => {
+                        issues.push(ValidationIssue::new(
+                            "paths.ms_data",
+                            format!("No MS data found in {}", ms_data.display()),
+                        ))
+                    }
+                    Ok(_) => {}
+                    Err(err) => issues.push(ValidationIssue::new("paths.ms_data", err)),
+                }
+            }
+        }
+        None => issues.push(ValidationIssue::new(
+            "paths.ms_data",
+            "An MS data directory is required",
+        )),
+    }
+
+    for database in check_auxiliary_databases(config) {
+        if let Some(problem) = database.problem {
+            issues.push(ValidationIssue::new(database.key, problem));
+        }
+    }
+    issues
+}
+
+/// Model settings are usually names ("altimeter") resolved by Pioneer itself,
+/// and decoy settings are often flags, so only values under a key containing
+/// one of `markers` that look like filesystem paths are collected.
+fn collect_marked_paths(
+    value: &Value,
+    prefix: &str,
+    markers: &[&str],
+    out: &mut Vec<(String, String)>,
+) {
+    let Value::Object(map) = value else {
+        return;
+    };
+    for (key, child) in map {
+        let path = join_path(prefix, key);
+        match child {
+            Value::Object(_) => collect_marked_paths(child, &path, markers, out),
+            Value::String(raw) if is_marked_key(key, markers) && looks_like_path(raw) => {
+                out.push((path, raw.clone()));
+            }
+            Value::Array(items) if is_marked_key(key, markers) => {
+                for raw in items.iter().filter_map(Value::as_str) {
+                    if looks_like_path(raw) {
+                        out.push((path.clone(), raw.to_string()));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn is_marked_key(key: &str, markers: &[&str]) -> bool {
+    let key = key.to_ascii_lowercase();
+    markers.iter().any(|marker| key.contains(marker))
+}
+
+/// Resolves the contaminant/decoy/entrapment databases a search config
+/// references and checks each one can be read, counting its FASTA entries.
+pub fn check_auxiliary_databases(config: &Value) -> Vec<AuxiliaryDatabase> {
+    let mut paths = Vec::new();
+    collect_marked_paths(config, "", DATABASE_KEY_MARKERS, &mut paths);
+    paths
+        .into_iter()
+        .map(|(key, raw)| {
+            let path = Path::new(&raw);
+            let (entries, problem) = if !path.is_file() {
+                let problem = if path.exists() {
+                    format!("{raw} is not a file")
+                } else {
+                    format!(
+                        "{raw} does not exist. `{key}` points at a database the search will not find; fix the path or remove the setting, since a missing contaminant or decoy database silently changes FDR estimates"
+                    )
+                };
+                (None, Some(problem))
+            } else {
+                match count_fasta_entries(path) {
+                    Ok(0) => (
+                        Some(0),
+                        Some(format!("{raw} contains no FASTA entries (`>` header lines)")),
+                    ),
+                    Ok(entries) => (Some(entries), None),
+                    Err(err) => (None, Some(format!("{raw} is not readable: {err}"))),
+                }
+            };
+            AuxiliaryDatabase {
+                key,
+                path: raw,
+                entries,
+                problem,
+            }
+        })
+        .collect()
+}
+
+fn count_fasta_entries(path: &Path) -> std::io::Result<usize> {
+    let mut entries = 0;
+    for line in BufReader::new(File::open(path)?).split(b'\n') {
+        if line?.first() == Some(&b'>') {
+            entries += 1;
+        }
+    }
+    Ok(entries)
+}
+
+fn looks_like_path(raw: &str) -> bool {
+    raw.contains('/') || raw.contains('\\') || Path::new(raw).extension().is_some()
+}
+
+fn check_readable(key: &str, path: &Path, issues: &mut Vec<ValidationIssue>) {
+    let display = path.display();
+    let result = if path.is_dir() {
+        fs::read_dir(path).map(|_| ())
+    } else if path.exists() {
+        File::open(path).map(|_| ())
+    } else {
+        issues.push(ValidationIssue::new(
+            key,
+            format!("{display} does not exist"),
+        ));
+        return;
+    };
+    if let Err(err) = result {
+        issues.push(ValidationIssue::new(
+            key,
+            format!("{display} is not readable: {err}"),
+        ));
+    }
+}
@@ -0,0 +1,199 @@
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+pub const BIN_DIR: &str = "bin";
+const CHUNK_SIZE: usize = 64 * 1024;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Serialize, Clone)]
+pub struct InstallProgressPayload {
+    pub url: String,
+    pub downloaded: u64,
+    pub total: Option<u64>,
+}
+
+/// File name for the installed binary: the last URL path segment, or a
+/// platform default when the URL has no path or its last segment could name
+/// anything other than a file directly inside `bin/`.
+pub fn binary_filename(url: &str) -> String {
+    let without_query = url.split(['?', '#']).next().unwrap_or(url);
+    let after_scheme = without_query
+        .split_once("://")
+        .map_or(without_query, |(_, rest)| rest);
+    let name = after_scheme
+        .split_once('/')
+        .and_then(|(_, path)| path.rsplit('/').next())
+        .unwrap_or_default();
+    let plain = !name.is_empty()
+        && name != "."
+        && name != ".."
+        && !name.contains(['/', '\\'])
+        && Path::new(name).file_name() == Some(name.as_ref());
+    if plain {
+        name.to_string()
+    } else if cfg!(windows) {
+        "pioneer.exe".to_string()
+    } else {
+        "pioneer".to_string()
+    }
+}
+
+/// Only plain web downloads are accepted; `file://` and other schemes could
+/// read local files or reach services the user did not mean to fetch from.
+fn check_download_url(url: &str) -> Result<(), String> {
+    let scheme = url
+        .split_once("://")
+        .map(|(scheme, _)| scheme.to_ascii_lowercase());
+    if matches!(scheme.as_deref(), Some("http" | "https")) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Refusing to download `{url}`: only http(s) URLs are supported"
+        ))
+    }
+}
+
+/// Downloads `url` to `dest`. The body is streamed into `<dest>.part` and only
+/// renamed into place once it is complete and matches `sha256` (when given),
+/// so an interrupted download never leaves a truncated binary behind.
+pub fn download_binary(
+    url: &str,
+    dest: &Path,
+    sha256: Option<&str>,
+    mut on_progress: impl FnMut(u64, Option<u64>),
+) -> Result<PathBuf, String> {
+    check_download_url(url)?;
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let mut partial = dest.as_os_str().to_owned();
+    partial.push(".part");
+    let partial = PathBuf::from(partial);
+
+    let result = stream_to_file(url, &partial, &mut on_progress).and_then(|digest| {
+        if let Some(expected) = sha256 {
+            let expected = expected.trim().to_ascii_lowercase();
+            if digest != expected {
+                return Err(format!(
+                    "Checksum mismatch: expected {expected}, downloaded file has {digest}"
+                ));
+            }
+        }
+        make_executable(&partial)?;
+        fs::rename(&partial, dest).map_err(|e| e.to_string())
+    });
+    if result.is_err() {
+        let _ = fs::remove_file(&partial);
+    }
+    result.map(|_| dest.to_path_buf())
+}
+
+/// Returns the hex SHA-256 of the downloaded bytes.
+fn stream_to_file(
+    url: &str,
+    path: &Path,
+    on_progress: &mut impl FnMut(u64, Option<u64>),
+) -> Result<String, String> {
+    let response = ureq::get(url)
+        .call()
+        .map_err(|e| format!("Download failed: {e}"))?;
+    let total = response
+        .header("Content-Length")
+        .and_then(|value| value.parse::<u64>().ok());
+    let mut reader = response.into_reader();
+    let mut file = File::create(path).map_err(|e| e.to_string())?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut downloaded = 0u64;
+    let mut last_emit = Instant::now();
+
+    on_progress(0, total);
+    loop {
+        let read = reader
+            .read(&mut buffer)
+            .map_err(|e| format!("Download interrupted: {e}"))?;
+        if read == 0 {
+            break;
+        }
+        file.write_all(&buffer[..read]).map_err(|e| e.to_string())?;
+        hasher.update(&buffer[..read]);
+        downloaded += read as u64;
+        if last_emit.elapsed() >= PROGRESS_INTERVAL {
+            on_progress(downloaded, total);
+            last_emit = Instant::now();
+        }
+    }
+    file.sync_all().map_err(|e| e.to_string())?;
+    on_progress(downloaded, total);
+
+    if let Some(total) = total {
+        if downloaded != total {
+            return Err(format!(
+                "Download incomplete: received {downloaded} of {total} bytes"
+            ));
+        }
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755)).map_err(|e| e.to_string())
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{binary_filename, check_download_url};
+
+    fn fallback() -> &'static str {
+        if cfg!(windows) {
+            "pioneer.exe"
+        } else {
+            "pioneer"
+        }
+    }
+
+    #[test]
+    fn names_the_binary_after_the_last_path_segment() {
+        assert_eq!(
+            binary_filename("https://host/releases/pioneer-1.2?sig=x#top"),
+            "pioneer-1.2"
+        );
+    }
+
+    #[test]
+    fn falls_back_when_the_segment_is_not_a_plain_file_name() {
+        for url in [
+            "https://host",
+            "https://host/",
+            "https://host/dir/..",
+            "https://host/dir/.",
+            "https://host/dir/..\\..\\evil.exe",
+        ] {
+            assert_eq!(binary_filename(url), fallback(), "{url}");
+        }
+    }
+
+    #[test]
+    fn accepts_only_http_downloads() {
+        assert!(check_download_url("https://host/pioneer").is_ok());
+        assert!(check_download_url("HTTP://host/pioneer").is_ok());
+        for url in ["file:///etc/passwd", "ftp://host/pioneer", "host/pioneer"] {
+            assert!(check_download_url(url).is_err(), "{url}");
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::resume::RESUME_FLAG;
+use crate::RunMode;
+
+/// Rejects extra arguments that repeat what the GUI already passes: a
+/// subcommand, a config file, or the resume flag (set through `resume`).
+/// Everything else is handed to Pioneer untouched, so flags the GUI does not
+/// know about still reach it.
+pub fn check_extra_args(args: &[String]) -> Result<(), String> {
+    let subcommands = [RunMode::BuildSpecLib, RunMode::SearchDia].map(|mode| mode.subcommand());
+    let config_names =
+        [RunMode::BuildSpecLib, RunMode::SearchDia].map(|mode| mode.config_filename());
+    for arg in args {
+        if arg.trim().is_empty() {
+            return Err("Extra arguments cannot be empty".into());
+        }
+        if subcommands.contains(&arg.as_str()) {
+            return Err(format!(
+                "Extra argument `{arg}` is a Pioneer subcommand; the GUI already passes it"
+            ));
+        }
+        if arg == RESUME_FLAG {
+            return Err(format!(
+                "Use the run request's `resume` option rather than passing `{RESUME_FLAG}`"
+            ));
+        }
+        let file_name = Path::new(arg)
+            .file_name()
+            .map(|name| name.to_string_lossy());
+        if file_name.is_some_and(|name| config_names.contains(&name.as_ref())) {
+            return Err(format!(
+                "Extra argument `{arg}` names a config file; the GUI already passes the run's config"
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub fn check_env(env: &HashMap<String, String>) -> Result<(), String> {
+    for (name, value) in env {
+        if name.is_empty() || name.contains('=') || name.contains('\0') {
+            return Err(format!("`{name}` is not a valid environment variable name"));
+        }
+        if value.contains('\0') {
+            return Err(format!("The value of `{name}` contains a NUL byte"));
+        }
+    }
+    Ok(())
+}
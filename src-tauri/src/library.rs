@@ -0,0 +1,86 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+use crate::capabilities::FormatRange;
+
+/// Where a `.poin` library records its format: an integer (or a string of
+/// digits) under `format_version` in this file.
+const LIBRARY_METADATA_FILE: &str = "metadata.json";
+const FORMAT_VERSION_KEY: &str = "format_version";
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum FormatStatus {
+    Compatible,
+    Incompatible,
+    /// The library or the binary does not declare a format, so nothing was
+    /// compared.
+    Unknown,
+}
+
+#[derive(Serialize, Debug)]
+pub struct LibraryVersionCheck {
+    pub path: String,
+    pub format_version: Option<u64>,
+    /// What the binary declares it can read, from its `--help`.
+    pub supported_formats: Option<FormatRange>,
+    pub status: FormatStatus,
+    pub message: Option<String>,
+}
+
+fn recorded_format(library: &Path) -> Option<u64> {
+    let contents = fs::read_to_string(library.join(LIBRARY_METADATA_FILE)).ok()?;
+    let value: Value = serde_json::from_str(&contents).ok()?;
+    match value.get(FORMAT_VERSION_KEY)? {
+        Value::Number(number) => number.as_u64(),
+        Value::String(raw) => raw.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Compares the format the library records against the range the binary
+/// declares. Either one missing makes the result `Unknown` rather than a
+/// guess.
+pub fn check_library_version(
+    library: &Path,
+    supported: Option<FormatRange>,
+) -> LibraryVersionCheck {
+    let mut check = LibraryVersionCheck {
+        path: library.to_string_lossy().to_string(),
+        format_version: None,
+        supported_formats: supported,
+        status: FormatStatus::Unknown,
+        message: None,
+    };
+    if !library.exists() {
+        check.message = Some(format!("Library not found: {}", library.display()));
+        return check;
+    }
+    check.format_version = recorded_format(library);
+
+    match (check.format_version, supported) {
+        (Some(format), Some(range)) if range.contains(format) => {
+            check.status = FormatStatus::Compatible;
+        }
+        (Some(format), Some(range)) => {
+            check.status = FormatStatus::Incompatible;
+            check.message = Some(format!(
+                "This library uses format {format} but the selected binary reads formats {range}. Rebuild the library with the current binary before searching."
+            ));
+        }
+        (None, _) => {
+            check.message = Some(format!(
+                "The library does not record a `{FORMAT_VERSION_KEY}` in {LIBRARY_METADATA_FILE}, so its format is unknown"
+            ));
+        }
+        (_, None) => {
+            check.message = Some(
+                "The Pioneer binary does not declare which library formats it reads, so compatibility is unknown"
+                    .into(),
+            );
+        }
+    }
+    check
+}
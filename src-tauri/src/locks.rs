@@ -0,0 +1,125 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+use crate::preflight::output_directories;
+use crate::workspace::list_dir;
+use crate::RunMode;
+
+/// Lock files younger than this may belong to a run that is still starting
+/// up, so they are never offered for removal.
+const MIN_STALE_AGE_SECS: u64 = 10 * 60;
+/// Lock files larger than this are almost certainly data that happens to
+/// share a suffix, and are left alone.
+const MAX_LOCK_BYTES: u64 = 4096;
+const LOCK_SUFFIXES: &[&str] = &[".lock", ".lck", ".pid"];
+
+#[derive(Serialize, Debug)]
+pub struct OutputLock {
+    pub path: String,
+    /// The config key of the output directory it was found under.
+    pub key: String,
+    pub bytes: u64,
+    /// `None` when the file's modification time is unavailable.
+    pub age_secs: Option<u64>,
+    /// PID written in the file, when it holds one.
+    pub pid: Option<u32>,
+    /// `Some(true)` when that process still exists; `None` when the file holds
+    /// no PID or liveness cannot be checked on this platform.
+    pub process_alive: Option<bool>,
+    /// Old enough, and not held by a live process, to remove safely.
+    pub stale: bool,
+}
+
+/// Scans each output directory, and its immediate subdirectories, for files
+/// that look like locks left behind by a crashed run. Nothing is removed.
+pub fn check_output_locks(mode: RunMode, config: &Value) -> Vec<OutputLock> {
+    let mut locks = Vec::new();
+    for (key, dir) in output_directories(mode, config) {
+        for entry in list_dir(&dir) {
+            if entry.is_dir() {
+                locks.extend(
+                    list_dir(&entry)
+                        .into_iter()
+                        .filter_map(|child| inspect(&key, &child)),
+                );
+            } else if let Some(lock) = inspect(&key, &entry) {
+                locks.push(lock);
+            }
+        }
+    }
+    locks.sort_by(|a, b| a.path.cmp(&b.path));
+    locks
+}
+
+/// Removes the requested lock files. Each path must still be reported stale by
+/// a fresh scan of the same config, so a stale listing or a hand-written path
+/// can never delete anything else. Returns the paths removed.
+pub fn clear_output_locks(
+    mode: RunMode,
+    config: &Value,
+    paths: &[String],
+) -> Result<Vec<String>, String> {
+    let current = check_output_locks(mode, config);
+    let mut removed = Vec::new();
+    for path in paths {
+        let Some(lock) = current.iter().find(|lock| &lock.path == path) else {
+            return Err(format!(
+                "{path} is not a lock file in this config's output directories"
+            ));
+        };
+        if !lock.stale {
+            return Err(format!(
+                "{path} is not stale (too recent or held by a running process)"
+            ));
+        }
+    }
+    for path in paths {
+        fs::remove_file(path).map_err(|e| format!("{path}: {e}"))?;
+        removed.push(path.clone());
+    }
+    Ok(removed)
+}
+
+fn inspect(key: &str, path: &Path) -> Option<OutputLock> {
+    let name = path.file_name()?.to_string_lossy().to_ascii_lowercase();
+    if !LOCK_SUFFIXES.iter().any(|suffix| name.ends_with(suffix)) {
+        return None;
+    }
+    let metadata = fs::symlink_metadata(path).ok()?;
+    if !metadata.is_file() || metadata.len() > MAX_LOCK_BYTES {
+        return None;
+    }
+    let age_secs = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .map(|age| age.as_secs());
+    let pid = fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| contents.split_whitespace().next()?.parse::<u32>().ok());
+    let process_alive = pid.and_then(process_alive);
+    let stale =
+        age_secs.is_some_and(|age| age >= MIN_STALE_AGE_SECS) && process_alive != Some(true);
+    Some(OutputLock {
+        path: path.to_string_lossy().to_string(),
+        key: key.to_string(),
+        bytes: metadata.len(),
+        age_secs,
+        pid,
+        process_alive,
+        stale,
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn process_alive(pid: u32) -> Option<bool> {
+    Some(Path::new(&format!("/proc/{pid}")).exists())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn process_alive(_pid: u32) -> Option<bool> {
+    None
+}
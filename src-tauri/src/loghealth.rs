@@ -0,0 +1,163 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::PathResolver;
+
+pub const LOG_PATTERNS_FILENAME: &str = "log_patterns.json";
+/// Matching lines kept per severity in a run's summary.
+const SAMPLE_LINES: usize = 5;
+
+/// Julia's logging prints `┌ Error:` / `┌ Warning:` blocks; plain prefixes
+/// cover messages Pioneer prints itself. Panics and fatal errors from native
+/// code can appear anywhere in a line.
+const DEFAULT_ERROR_PATTERNS: &[&str] = &[
+    r"^\s*(┌\s*)?Error:",
+    r"^\s*ERROR\b",
+    r"(?i)\b(fatal|panic(ked)?)\b",
+];
+const DEFAULT_WARNING_PATTERNS: &[&str] = &[r"^\s*(┌\s*)?Warning:", r"^\s*WARN(ING)?\b"];
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// Regexes per severity, persisted in `log_patterns.json`. Error patterns are
+/// tried first.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct LogPatterns {
+    pub error: Vec<String>,
+    pub warning: Vec<String>,
+}
+
+impl Default for LogPatterns {
+    fn default() -> Self {
+        Self {
+            error: DEFAULT_ERROR_PATTERNS
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+            warning: DEFAULT_WARNING_PATTERNS
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+        }
+    }
+}
+
+pub struct LogClassifier {
+    error: Vec<Regex>,
+    warning: Vec<Regex>,
+}
+
+impl LogClassifier {
+    pub fn new(patterns: &LogPatterns) -> Result<Self, String> {
+        let compile = |sources: &[String]| -> Result<Vec<Regex>, String> {
+            sources
+                .iter()
+                .map(|source| {
+                    Regex::new(source).map_err(|e| format!("Invalid pattern `{source}`: {e}"))
+                })
+                .collect()
+        };
+        Ok(Self {
+            error: compile(&patterns.error)?,
+            warning: compile(&patterns.warning)?,
+        })
+    }
+
+    pub fn classify(&self, line: &str) -> Option<Severity> {
+        if self.error.iter().any(|pattern| pattern.is_match(line)) {
+            Some(Severity::Error)
+        } else if self.warning.iter().any(|pattern| pattern.is_match(line)) {
+            Some(Severity::Warning)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for LogClassifier {
+    fn default() -> Self {
+        Self::new(&LogPatterns::default()).expect("default log patterns are valid")
+    }
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct LogHealth {
+    pub errors: usize,
+    pub warnings: usize,
+    /// The first few matching lines of each severity.
+    pub error_samples: Vec<String>,
+    pub warning_samples: Vec<String>,
+}
+
+impl LogHealth {
+    pub fn record(&mut self, severity: Severity, line: &str) {
+        let (count, samples) = match severity {
+            Severity::Error => (&mut self.errors, &mut self.error_samples),
+            Severity::Warning => (&mut self.warnings, &mut self.warning_samples),
+        };
+        *count += 1;
+        if samples.len() < SAMPLE_LINES {
+            samples.push(line.to_string());
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct LineClassification {
+    pub line: String,
+    pub severity: Option<Severity>,
+}
+
+fn patterns_path(resolver: &PathResolver) -> Option<PathBuf> {
+    Some(resolver.app_config_dir()?.join(LOG_PATTERNS_FILENAME))
+}
+
+pub fn load_patterns(resolver: &PathResolver) -> LogPatterns {
+    patterns_path(resolver)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Rejects the whole set if any pattern fails to compile, so a typo never
+/// silently disables classification.
+pub fn save_patterns(resolver: &PathResolver, patterns: &LogPatterns) -> Result<(), String> {
+    LogClassifier::new(patterns)?;
+    let path =
+        patterns_path(resolver).ok_or_else(|| "App config directory is unavailable".to_string())?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let pretty = serde_json::to_string_pretty(patterns).map_err(|e| e.to_string())?;
+    fs::write(path, pretty).map_err(|e| e.to_string())
+}
+
+/// The persisted patterns compiled, falling back to the defaults if a
+/// hand-edited file no longer compiles.
+pub fn load_classifier(resolver: &PathResolver) -> LogClassifier {
+    LogClassifier::new(&load_patterns(resolver)).unwrap_or_else(|err| {
+        eprintln!("Ignoring {LOG_PATTERNS_FILENAME}: {err}");
+        LogClassifier::default()
+    })
+}
+
+pub fn classify_lines(
+    patterns: &LogPatterns,
+    lines: Vec<String>,
+) -> Result<Vec<LineClassification>, String> {
+    let classifier = LogClassifier::new(patterns)?;
+    Ok(lines
+        .into_iter()
+        .map(|line| LineClassification {
+            severity: classifier.classify(&line),
+            line,
+        })
+        .collect())
+}
@@ -2,18 +2,128 @@
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::env;
 use std::fs::{self, OpenOptions};
-use std::io::{BufRead, BufReader, Write};
+use std::io::{BufRead, BufReader, Read, Write};
 use std::path::{Path, PathBuf};
-use std::process::{Command as StdCommand, Stdio};
-use std::sync::mpsc;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use tauri::{AppHandle, PathResolver, Window};
-use tempfile::tempdir;
+use std::process::{Child, Command as StdCommand, ExitStatus, Output, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Manager, PathResolver, State, Window};
 use thiserror::Error;
 use which::which;
 
+mod atomic;
+mod backups;
+mod batch;
+mod binary;
+mod bridge;
+mod canonical;
+mod capabilities;
+mod checksums;
+mod completion;
+mod defaults_cache;
+mod diff;
+mod diskspace;
+mod encoding;
+mod environment;
+mod estimate;
+mod events;
+mod failure;
+mod history;
+mod hooks;
+mod inputs;
+mod install;
+mod invocation;
+mod library;
+mod locks;
+mod loghealth;
+mod memory;
+mod merge;
+mod models;
+mod outputs;
+mod pacing;
+mod parallelism;
+mod paths;
+mod pipeline;
+mod policy;
+mod preflight;
+mod profiles;
+mod progressfile;
+mod recorder;
+mod redact;
+mod registry;
+mod reproducer;
+mod resume;
+mod rules;
+mod runs;
+mod schema;
+mod sections;
+mod stagematch;
+mod staging;
+mod statistics;
+mod storage;
+mod taskbar;
+mod terminal;
+mod validation;
+mod workspace;
+
+use backups::ConfigBackup;
+use batch::{BatchProgressPayload, BatchTracker};
+use binary::{BinaryCandidate, BinaryCheck, BinarySource, LookupRetry, WarmupReport};
+use bridge::{BridgeSettings, BridgeStatus, EventBridge};
+use capabilities::{BinaryCapabilities, CapabilityCache, FormatRange};
+use checksums::InputHashes;
+use completion::{CompletionCache, LastCompletion};
+use defaults_cache::CacheStatus;
+use diff::{diff_config, lookup, ConfigDiff};
+use diskspace::DiskSpaceSettings;
+use encoding::FileNormalization;
+use environment::RunEnvironment;
+use estimate::PrecursorEstimate;
+use failure::{classify_failure, describe_exit, exit_signal, FailureCategory, STDERR_TAIL_LINES};
+use history::{HistoryRecord, PendingRecord};
+use hooks::{PostRunHook, RunOutcome};
+use inputs::AuxiliaryDatabase;
+use install::InstallProgressPayload;
+use library::LibraryVersionCheck;
+use locks::OutputLock;
+use loghealth::{LineClassification, LogClassifier, LogHealth, LogPatterns, Severity};
+use memory::MemoryBudget;
+use merge::{ArrayMerge, ArrayPolicy, MergePreview};
+use models::ModelList;
+use outputs::RunOutputs;
+use pacing::{
+    EtaEstimator, ProgressHints, StageSummaryPayload, StageTimeline, StageTimingPayload,
+    StageWeightCache, StageWeights,
+};
+use parallelism::ParallelismReport;
+use paths::NormalizedPaths;
+use pipeline::{PipelineCompletePayload, PipelineLink, PipelineStartedPayload, RangeConsistency};
+use policy::PolicyViolation;
+use preflight::{OutputSubdirs, PreflightReport, WritableCheck};
+use profiles::ConfigProfile;
+use progressfile::{ProgressSource, PROGRESS_STREAM};
+use recorder::{EventRecorder, Recording};
+use redact::RedactedConfig;
+use registry::{LogPeek, RunRegistry, RunState, RunStatus};
+use reproducer::{ReproducerOptions, ReproducerReport};
+use resume::ResumePlan;
+use rules::RuleViolation;
+use runs::RunMetadata;
+use schema::SchemaViolation;
+use sections::SectionManifest;
+use stagematch::{StageMatcher, StageMatching};
+use staging::{FilenameCollision, MsDataInventory, StagingCheck};
+use statistics::StatisticsIssue;
+use storage::StorageSpeed;
+use taskbar::TaskbarTracker;
+use terminal::TerminalSettings;
+use validation::ValidationIssue;
+use workspace::{app_tempdir, CleanWorkspaceOptions, CleanWorkspaceReport, RetentionPolicy};
+
 static FALLBACK_BUILD: &str = include_str!("../fallback/default_build.json");
 static FALLBACK_BUILD_SIMPLIFIED: &str = include_str!("../fallback/default_build_simplified.json");
 static FALLBACK_SEARCH: &str = include_str!("../fallback/default_search.json");
@@ -32,11 +142,15 @@ enum ConfigLoadError {
     NonZeroExit(Option<i32>),
     #[error("Failed to parse JSON output: {0}")]
     Json(#[from] serde_json::Error),
+    #[error("Pioneer did not finish within {0} seconds")]
+    Timeout(u64),
+    #[error("Fetching defaults was cancelled")]
+    Cancelled,
     #[error("{0}")]
     Other(String),
 }
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 enum RunMode {
     BuildSpecLib,
@@ -71,6 +185,75 @@ impl RunMode {
             RunMode::SearchDia => "searchDia",
         }
     }
+
+    fn label(&self) -> &'static str {
+        match self {
+            RunMode::BuildSpecLib => "BuildSpecLib",
+            RunMode::SearchDia => "SearchDIA",
+        }
+    }
+}
+
+const DEFAULTS_FETCH_TIMEOUT: Duration = Duration::from_secs(120);
+/// Limit on quick queries such as `--version` and `--help`; generous because
+/// a cold Julia start alone can take several seconds.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(30);
+/// Longest output line kept per read; anything past it is dropped so a
+/// single pathological line cannot exhaust memory.
+const DEFAULT_MAX_LINE_BYTES: usize = 256 * 1024;
+const OUTPUT_MTIME_SLACK: Duration = Duration::from_secs(2);
+const STDOUT_LOG_FILENAME: &str = "stdout.log";
+const STDERR_LOG_FILENAME: &str = "stderr.log";
+/// How often a quiet run checks for a pending cancellation.
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Per-mode cancellation flags for the `params-*` default fetches.
+#[derive(Default)]
+struct DefaultsFetchState {
+    build_cancelled: AtomicBool,
+    search_cancelled: AtomicBool,
+}
+
+impl DefaultsFetchState {
+    fn flag(&self, mode: RunMode) -> &AtomicBool {
+        match mode {
+            RunMode::BuildSpecLib => &self.build_cancelled,
+            RunMode::SearchDia => &self.search_cancelled,
+        }
+    }
+
+    fn control(&self, mode: RunMode, timeout: Duration) -> FetchControl<'_> {
+        let cancel = self.flag(mode);
+        cancel.store(false, Ordering::SeqCst);
+        FetchControl {
+            timeout,
+            cancel: Some(cancel),
+        }
+    }
+}
+
+struct FetchControl<'a> {
+    timeout: Duration,
+    cancel: Option<&'a AtomicBool>,
+}
+
+impl Default for FetchControl<'_> {
+    fn default() -> Self {
+        FetchControl {
+            timeout: DEFAULTS_FETCH_TIMEOUT,
+            cancel: None,
+        }
+    }
+}
+
+impl FetchControl<'_> {
+    /// For `--version` / `--help` queries, which should answer quickly.
+    fn probe() -> Self {
+        FetchControl {
+            timeout: PROBE_TIMEOUT,
+            cancel: None,
+        }
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -168,12 +351,65 @@ enum ConfigSource {
     Fallback,
 }
 
+/// Sidecar written next to each persisted config recording which Pioneer
+/// build it was last run with.
+#[derive(Serialize, Deserialize, Default)]
+struct PersistedMeta {
+    binary_version: Option<String>,
+}
+
+#[derive(Serialize)]
+struct PersistedConfigDiff {
+    mode: RunMode,
+    /// Where the defaults compared against came from: `binary` or `fallback`.
+    source: ConfigSource,
+    persisted_path: Option<String>,
+    #[serde(flatten)]
+    diff: ConfigDiff,
+}
+
+#[derive(Serialize)]
+struct PioneerVersionInfo {
+    path: String,
+    /// Trimmed `--version` output; `None` when the binary printed nothing or
+    /// exited with an error.
+    version: Option<String>,
+    exit_code: Option<i32>,
+    /// Raw stderr, kept when no version could be read so the cause is visible.
+    stderr: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+struct ConfigVersionWarning {
+    mode: RunMode,
+    persisted_version: String,
+    current_version: String,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct ModeConfigResponse {
+    mode: RunMode,
+    config: ConfigSet,
+    source: ConfigSource,
+    binary_error: Option<String>,
+    version_warning: Option<ConfigVersionWarning>,
+}
+
 #[derive(Serialize)]
 struct LoadConfigsResponse {
     build: ConfigSet,
     search: ConfigSet,
     source: ConfigSource,
     binary_error: Option<String>,
+    version_warnings: Vec<ConfigVersionWarning>,
+    cache: DefaultsCacheReport,
+}
+
+#[derive(Serialize)]
+struct DefaultsCacheReport {
+    build: CacheStatus,
+    search: CacheStatus,
 }
 
 #[derive(Deserialize)]
@@ -181,14 +417,182 @@ struct LoadConfigsResponse {
 struct RunRequest {
     mode: RunMode,
     config: Value,
+    #[serde(default)]
+    max_files: Option<usize>,
+    #[serde(default)]
+    combined_output: bool,
+    #[serde(default)]
+    max_line_bytes: Option<usize>,
+    #[serde(default)]
+    log_filename_template: Option<String>,
+    /// Write the run log here rather than into the run directory, so it
+    /// outlives pruning, or a session's temp directory when there is no run
+    /// directory.
+    #[serde(default)]
+    log_dir: Option<String>,
+    #[serde(default)]
+    stage_debug: bool,
+    /// Record input checksums in the run's metadata.
+    #[serde(default)]
+    record_input_hashes: bool,
+    /// Hash MS data contents rather than just names and sizes.
+    #[serde(default)]
+    hash_full_content: bool,
+    /// Batch declared with `start_batch` that this run belongs to.
+    #[serde(default)]
+    batch_id: Option<String>,
+    /// Also write each stream to its own `stdout.log` / `stderr.log`.
+    #[serde(default)]
+    split_stream_logs: bool,
+    /// Benchmark the output and temporary directories before starting.
+    #[serde(default)]
+    check_storage: bool,
+    /// Pick up an interrupted search from its results directory.
+    #[serde(default)]
+    resume: bool,
+    /// Where stage progress is read from: stdout parsing (the default),
+    /// Pioneer's own progress file, or both.
+    #[serde(default)]
+    progress_source: ProgressSource,
+    /// How output lines move the run between stages.
+    #[serde(default)]
+    stage_matching: StageMatching,
+    /// Program and arguments run once Pioneer exits, e.g. to start a
+    /// downstream step.
+    #[serde(default)]
+    post_run_command: Option<Vec<String>>,
+    /// Run `post_run_command` after failed or cancelled runs as well.
+    #[serde(default)]
+    post_run_always: bool,
+    /// Kill `post_run_command` if it is still running after this many
+    /// seconds. Defaults to ten minutes.
+    #[serde(default)]
+    post_run_timeout_secs: Option<u64>,
+    /// Appended to Pioneer's command line after the config path, for flags
+    /// the GUI does not expose.
+    #[serde(default)]
+    extra_args: Vec<String>,
+    /// Set in Pioneer's environment on top of the GUI's own.
+    #[serde(default)]
+    env: HashMap<String, String>,
+    /// `Some(false)` skips the external tail terminal, for frontends that
+    /// render `pioneer-log` lines themselves; the log file is still written.
+    #[serde(default)]
+    external_terminal: Option<bool>,
+    /// Launch without checking that inputs exist and outputs are writable,
+    /// e.g. when they only exist on the node a command wrapper runs on.
+    #[serde(default)]
+    skip_preflight: bool,
+    /// Run this binary instead of the one `locate_pioneer_binary` finds.
+    #[serde(default)]
+    binary_path: Option<String>,
+    /// Kill Pioneer if it is still running this many seconds after it
+    /// started. Time spent paused counts.
+    #[serde(default)]
+    timeout_secs: Option<u64>,
+    /// Set by `run_pipeline` for each of its phases.
+    #[serde(skip)]
+    pipeline: Option<PipelineLink>,
+}
+
+/// Per-run knobs threaded from `RunRequest` into `run_process`.
+struct RunOptions {
+    run_dir: Option<PathBuf>,
+    /// Key under which the live process is registered for pause/resume.
+    run_id: Option<String>,
+    /// `run_id`, or a mode/timestamp stand-in when the run has no directory.
+    run_key: String,
+    /// Batch this run counts towards in `pioneer-batch-progress`.
+    batch_id: Option<String>,
+    expected_precursors: Option<u64>,
+    combined_output: bool,
+    stage_weights: StageWeights,
+    eta: EtaEstimator,
+    max_line_bytes: usize,
+    /// Emit a stage-matching trace for every output line.
+    stage_debug: bool,
+    /// Directories scanned for produced files once the run succeeds.
+    output_dirs: Vec<PathBuf>,
+    /// Marker the tail terminal waits for when it should close with the run.
+    end_token: String,
+    log_path: PathBuf,
+    /// Set once the end marker is in the log, so it is written only once.
+    end_marked: Arc<AtomicBool>,
+    /// Open a terminal tailing the log when the run starts.
+    external_terminal: bool,
+    auto_close_terminal: bool,
+    classifier: LogClassifier,
+    /// Per-stream log files, when the run asked for them.
+    stream_logs: Option<(PathBuf, PathBuf)>,
+    /// Index of the stage a resumed run starts in.
+    resume_stage: Option<usize>,
+    progress_source: ProgressSource,
+    stage_matching: StageMatching,
+    /// Pioneer's progress file, tailed when `progress_source` includes it.
+    progress_file: Option<PathBuf>,
+    /// Program and arguments Pioneer is launched through.
+    command_wrapper: Option<Vec<String>>,
+    extra_args: Vec<String>,
+    env: HashMap<String, String>,
+    /// Wall-clock limit from spawning Pioneer to it exiting.
+    timeout: Option<Duration>,
+    post_run: Option<PostRunHook>,
+    pipeline: Option<PipelineLink>,
+    /// Completed into a run-history record; `None` without a config dir.
+    history: Option<PendingRecord>,
 }
 
 #[derive(Serialize)]
 struct RunStartedPayload {
     mode: RunMode,
+    run_id: Option<String>,
     log_path: String,
     config_path: String,
     persisted_path: Option<String>,
+    max_files: Option<usize>,
+    stdout_log_path: Option<String>,
+    stderr_log_path: Option<String>,
+    /// Slow or network storage found by the `check_storage` pre-flight.
+    storage_warnings: Vec<String>,
+    /// Set when this run resumes an interrupted search.
+    resume: Option<ResumePlan>,
+}
+
+/// What `run_pioneer` would launch for a request, without launching it.
+#[derive(Serialize)]
+struct CommandPreview {
+    mode: RunMode,
+    /// `program` and `args` quoted as a user would type them.
+    command_line: String,
+    program: String,
+    args: Vec<String>,
+    /// Set on top of the GUI's own environment.
+    env: BTreeMap<String, String>,
+    /// A real run writes the config into its run directory; the preview
+    /// names the file relative to wherever `config` is saved.
+    config_path: String,
+    config: String,
+}
+
+#[derive(Serialize)]
+struct PreparePayload {
+    mode: RunMode,
+    step: String,
+    current: Option<usize>,
+    total: Option<usize>,
+}
+
+/// Trace of stage matching for one output line, emitted only for runs started
+/// with `stage_debug`.
+#[derive(Serialize)]
+struct StageDebugPayload {
+    mode: RunMode,
+    line: String,
+    /// Stage whose keywords matched this line, if any.
+    matched_stage: Option<String>,
+    /// Current stage after this line was processed.
+    stage_index: usize,
+    stage_key: String,
 }
 
 #[derive(Serialize)]
@@ -197,6 +601,9 @@ struct ProgressPayload {
     stage_key: String,
     stage_label: String,
     progress: f32,
+    /// Projected seconds until the run finishes, once it is at least 5
+    /// points past where it started.
+    eta_seconds: Option<u32>,
 }
 
 #[derive(Serialize)]
@@ -204,61 +611,132 @@ struct LogPayload {
     mode: RunMode,
     stream: &'static str,
     line: String,
+    severity: Option<Severity>,
 }
 
-#[derive(Serialize)]
+/// Sent alongside `pioneer-log` for each line classified as an error or
+/// warning, with the run's totals so far.
+#[derive(Serialize, Clone)]
+struct LogLevelPayload {
+    mode: RunMode,
+    run_id: Option<String>,
+    stream: &'static str,
+    severity: Severity,
+    line: String,
+    error_count: usize,
+    warning_count: usize,
+}
+
+#[derive(Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 struct RunCompletePayload {
     mode: RunMode,
     success: bool,
     exit_code: Option<i32>,
     message: Option<String>,
+    failure_category: Option<FailureCategory>,
+    /// Files written during a successful run.
+    outputs: Option<RunOutputs>,
+    cancelled: bool,
+    /// Lines classified as errors and warnings, as counted in `log_health`;
+    /// zero if Pioneer never started.
+    error_count: usize,
+    warning_count: usize,
+    /// Error and warning lines seen in the output; `None` if Pioneer never
+    /// started.
+    log_health: Option<LogHealth>,
 }
 
 #[tauri::command]
-async fn load_configs(app_handle: AppHandle) -> Result<LoadConfigsResponse, String> {
-    let fallback_build: Value = serde_json::from_str(FALLBACK_BUILD).map_err(|e| e.to_string())?;
-    let fallback_build_simplified: Value =
-        serde_json::from_str(FALLBACK_BUILD_SIMPLIFIED).map_err(|e| e.to_string())?;
-    let fallback_search: Value =
-        serde_json::from_str(FALLBACK_SEARCH).map_err(|e| e.to_string())?;
-    let fallback_search_simplified: Value =
-        serde_json::from_str(FALLBACK_SEARCH_SIMPLIFIED).map_err(|e| e.to_string())?;
+async fn load_configs(
+    app_handle: AppHandle,
+    fetch_state: State<'_, DefaultsFetchState>,
+) -> Result<LoadConfigsResponse, String> {
+    let resolver = app_handle.path_resolver();
+    let override_dir = fallback_override_dir(&resolver);
+    let fallback_build = fallback_defaults(RunMode::BuildSpecLib, override_dir.as_deref())
+        .map_err(|e| e.to_string())?;
+    let fallback_build_simplified =
+        fallback_simplified(RunMode::BuildSpecLib, override_dir.as_deref())
+            .map_err(|e| e.to_string())?;
+    let fallback_search = fallback_defaults(RunMode::SearchDia, override_dir.as_deref())
+        .map_err(|e| e.to_string())?;
+    let fallback_search_simplified =
+        fallback_simplified(RunMode::SearchDia, override_dir.as_deref())
+            .map_err(|e| e.to_string())?;
 
     let mut errors = Vec::new();
     let mut build_defaults = fallback_build.clone();
     let mut search_defaults = fallback_search.clone();
     let mut source = ConfigSource::Fallback;
 
-    match try_fetch_build_defaults() {
-        Ok(value) => {
-            build_defaults = value;
-            source = ConfigSource::Partial;
-        }
-        Err(err) => errors.push(format!("BuildSpecLib defaults: {err}")),
-    }
+    let cache_dir = defaults_cache::cache_dir(&resolver);
+    let mut current_version = None;
+
+    // Locate the binary once: when it is missing, both fetches would fail
+    // the same way and report the same error twice.
+    let (build_cache, search_cache) = match locate_pioneer_binary_at_startup() {
+        Ok(binary) => {
+            let (build_result, build_cache) = cached_or_fetch_defaults(
+                RunMode::BuildSpecLib,
+                &binary,
+                cache_dir.as_deref(),
+                &fetch_state.control(RunMode::BuildSpecLib, DEFAULTS_FETCH_TIMEOUT),
+                &mut current_version,
+            );
+            match build_result {
+                Ok(value) => {
+                    build_defaults = value;
+                    source = ConfigSource::Partial;
+                }
+                Err(err) => errors.push(format!("BuildSpecLib defaults: {err}")),
+            }
 
-    match try_fetch_search_defaults() {
-        Ok(value) => {
-            search_defaults = value;
-            source = match source {
-                ConfigSource::Partial | ConfigSource::Binary => ConfigSource::Binary,
-                ConfigSource::Fallback => ConfigSource::Partial,
-            };
+            let (search_result, search_cache) = cached_or_fetch_defaults(
+                RunMode::SearchDia,
+                &binary,
+                cache_dir.as_deref(),
+                &fetch_state.control(RunMode::SearchDia, DEFAULTS_FETCH_TIMEOUT),
+                &mut current_version,
+            );
+            match search_result {
+                Ok(value) => {
+                    search_defaults = value;
+                    source = match source {
+                        ConfigSource::Partial | ConfigSource::Binary => ConfigSource::Binary,
+                        ConfigSource::Fallback => ConfigSource::Partial,
+                    };
+                }
+                Err(err) => errors.push(format!("SearchDIA defaults: {err}")),
+            }
+            (build_cache, search_cache)
         }
-        Err(err) => errors.push(format!("SearchDIA defaults: {err}")),
-    }
+        Err(err) => {
+            errors.push(err.to_string());
+            (CacheStatus::Unavailable, CacheStatus::Unavailable)
+        }
+    };
 
     if matches!(source, ConfigSource::Partial) && errors.len() == 2 {
         source = ConfigSource::Fallback;
     }
-    let resolver = app_handle.path_resolver();
     let build_path = config_storage_path(RunMode::BuildSpecLib, &resolver);
     let search_path = config_storage_path(RunMode::SearchDia, &resolver);
 
     let build_persisted = load_persisted_config(build_path.as_deref(), &build_defaults);
     let search_persisted = load_persisted_config(search_path.as_deref(), &search_defaults);
 
+    let version_warnings: Vec<ConfigVersionWarning> = [
+        (RunMode::BuildSpecLib, build_path.as_deref()),
+        (RunMode::SearchDia, search_path.as_deref()),
+    ]
+    .into_iter()
+    .filter_map(|(mode, path)| config_version_warning(mode, path, current_version.as_deref()))
+    .collect();
+    for warning in &version_warnings {
+        let _ = events::emit_all(&app_handle, events::CONFIG_VERSION_WARNING, warning);
+    }
+
     let response = LoadConfigsResponse {
         build: ConfigSet {
             default_config: build_defaults,
@@ -278,32 +756,208 @@ async fn load_configs(app_handle: AppHandle) -> Result<LoadConfigsResponse, Stri
         } else {
             Some(errors.join("\n"))
         },
+        version_warnings,
+        cache: DefaultsCacheReport {
+            build: build_cache,
+            search: search_cache,
+        },
     };
 
     Ok(response)
 }
 
-fn try_fetch_build_defaults() -> Result<Value, ConfigLoadError> {
-    let pioneer = locate_pioneer_binary()?;
-    let temp_dir = tempdir().map_err(|e| ConfigLoadError::Other(e.to_string()))?;
+#[tauri::command]
+async fn load_build_defaults(
+    app_handle: AppHandle,
+    timeout_secs: Option<u64>,
+) -> Result<ModeConfigResponse, String> {
+    load_mode_config_blocking(app_handle, RunMode::BuildSpecLib, timeout_secs).await
+}
+
+#[tauri::command]
+async fn load_search_defaults(
+    app_handle: AppHandle,
+    timeout_secs: Option<u64>,
+) -> Result<ModeConfigResponse, String> {
+    load_mode_config_blocking(app_handle, RunMode::SearchDia, timeout_secs).await
+}
+
+/// Runs `load_mode_config` off the async runtime, so a binary that hangs while
+/// writing one workflow's defaults cannot hold up loading the other's.
+async fn load_mode_config_blocking(
+    app_handle: AppHandle,
+    mode: RunMode,
+    timeout_secs: Option<u64>,
+) -> Result<ModeConfigResponse, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let fetch_state = app_handle.state::<DefaultsFetchState>();
+        load_mode_config(&app_handle, &fetch_state, mode, timeout_secs)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn cancel_defaults_fetch(
+    fetch_state: State<'_, DefaultsFetchState>,
+    mode: RunMode,
+) -> Result<(), String> {
+    fetch_state.flag(mode).store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+fn load_mode_config(
+    app_handle: &AppHandle,
+    fetch_state: &DefaultsFetchState,
+    mode: RunMode,
+    timeout_secs: Option<u64>,
+) -> Result<ModeConfigResponse, String> {
+    let timeout = timeout_secs
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULTS_FETCH_TIMEOUT);
+    let control = fetch_state.control(mode, timeout);
+    let resolver = app_handle.path_resolver();
+    let override_dir = fallback_override_dir(&resolver);
+
+    let (defaults, source, binary_error) = match try_fetch_defaults_with(mode, &control) {
+        Ok(value) => (value, ConfigSource::Binary, None),
+        Err(err) => (
+            fallback_defaults(mode, override_dir.as_deref()).map_err(|e| e.to_string())?,
+            ConfigSource::Fallback,
+            Some(format!("{} defaults: {err}", mode.label())),
+        ),
+    };
+    let simplified =
+        fallback_simplified(mode, override_dir.as_deref()).map_err(|e| e.to_string())?;
+
+    // An explicit reload always asks the binary, then refreshes the cache that
+    // `load_configs` reads on the next launch.
+    let binary = locate_pioneer_binary().ok();
+    let current_version = binary
+        .as_deref()
+        .and_then(|binary| read_pioneer_version(binary).ok());
+    if let (ConfigSource::Binary, Some(binary), Some(dir)) =
+        (&source, &binary, defaults_cache::cache_dir(&resolver))
+    {
+        if let Err(err) =
+            defaults_cache::store(&dir, mode, binary, current_version.as_deref(), &defaults)
+        {
+            eprintln!("Failed to cache {} defaults: {err}", mode.as_str());
+        }
+    }
+
+    let path = config_storage_path(mode, &resolver);
+    let persisted = load_persisted_config(path.as_deref(), &defaults);
+    let version_warning = config_version_warning(mode, path.as_deref(), current_version.as_deref());
+    if let Some(warning) = &version_warning {
+        let _ = events::emit_all(app_handle, events::CONFIG_VERSION_WARNING, warning);
+    }
+
+    Ok(ModeConfigResponse {
+        mode,
+        config: ConfigSet {
+            default_config: defaults,
+            simplified_config: simplified,
+            persisted_config: persisted,
+            persisted_path: path.map(|p| p.to_string_lossy().to_string()),
+        },
+        source,
+        binary_error,
+        version_warning,
+    })
+}
+
+/// Waits for a `params-*` invocation while honouring the fetch timeout and
+/// cancellation flag, killing the child if either trips.
+fn wait_for_params_command(
+    mut command: StdCommand,
+    control: &FetchControl,
+) -> Result<(), ConfigLoadError> {
+    let mut child = command.spawn()?;
+    let status = wait_within(&mut child, control)?;
+    if !status.success() {
+        return Err(ConfigLoadError::NonZeroExit(status.code()));
+    }
+    Ok(())
+}
+
+/// `Command::output` bounded by `control`. Both pipes are drained on their
+/// own threads so a child that writes a lot cannot stall on a full pipe.
+fn output_within(
+    mut command: StdCommand,
+    control: &FetchControl,
+) -> Result<Output, ConfigLoadError> {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut child = command.spawn()?;
+    let stdout = drain_pipe(child.stdout.take());
+    let stderr = drain_pipe(child.stderr.take());
+    let status = wait_within(&mut child, control)?;
+    Ok(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+fn drain_pipe<R: Read + Send + 'static>(pipe: Option<R>) -> std::thread::JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    })
+}
+
+/// Polls `child` until it exits, killing it if the timeout or cancellation
+/// flag in `control` trips first.
+fn wait_within(child: &mut Child, control: &FetchControl) -> Result<ExitStatus, ConfigLoadError> {
+    let deadline = Instant::now() + control.timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(status);
+        }
+
+        let cancelled = control
+            .cancel
+            .map(|flag| flag.load(Ordering::SeqCst))
+            .unwrap_or(false);
+        if cancelled || Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(if cancelled {
+                ConfigLoadError::Cancelled
+            } else {
+                ConfigLoadError::Timeout(control.timeout.as_secs())
+            });
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+}
+
+fn try_fetch_build_defaults(
+    pioneer: &Path,
+    control: &FetchControl,
+) -> Result<Value, ConfigLoadError> {
+    let temp_dir = app_tempdir().map_err(|e| ConfigLoadError::Other(e.to_string()))?;
     let lib_out = temp_dir.path().join("library_preview");
     fs::create_dir_all(&lib_out).map_err(|e| ConfigLoadError::Other(e.to_string()))?;
     let fasta_path = temp_dir.path().join("preview.fasta");
     fs::write(&fasta_path, b">Example\nM\n").map_err(|e| ConfigLoadError::Other(e.to_string()))?;
     let params_path = temp_dir.path().join("build_params.json");
 
-    let status = StdCommand::new(pioneer)
+    let mut command = StdCommand::new(pioneer);
+    command
         .arg("params-predict")
         .arg(lib_out.as_os_str())
         .arg("PreviewLibrary")
         .arg(fasta_path.as_os_str())
         .arg("--params-path")
-        .arg(&params_path)
-        .status()?;
-
-    if !status.success() {
-        return Err(ConfigLoadError::NonZeroExit(status.code()));
-    }
+        .arg(&params_path);
+    wait_for_params_command(command, control)?;
 
     let file =
         fs::read_to_string(&params_path).map_err(|e| ConfigLoadError::Other(e.to_string()))?;
@@ -311,9 +965,11 @@ fn try_fetch_build_defaults() -> Result<Value, ConfigLoadError> {
     Ok(json)
 }
 
-fn try_fetch_search_defaults() -> Result<Value, ConfigLoadError> {
-    let pioneer = locate_pioneer_binary()?;
-    let temp_dir = tempdir().map_err(|e| ConfigLoadError::Other(e.to_string()))?;
+fn try_fetch_search_defaults(
+    pioneer: &Path,
+    control: &FetchControl,
+) -> Result<Value, ConfigLoadError> {
+    let temp_dir = app_tempdir().map_err(|e| ConfigLoadError::Other(e.to_string()))?;
     let library_path = temp_dir.path().join("example_library.poin");
     fs::write(&library_path, b"").map_err(|e| ConfigLoadError::Other(e.to_string()))?;
     let ms_data_dir = temp_dir.path().join("ms_data");
@@ -322,18 +978,15 @@ fn try_fetch_search_defaults() -> Result<Value, ConfigLoadError> {
     fs::create_dir_all(&results_dir).map_err(|e| ConfigLoadError::Other(e.to_string()))?;
     let params_path = temp_dir.path().join("search_params.json");
 
-    let status = StdCommand::new(pioneer)
+    let mut command = StdCommand::new(pioneer);
+    command
         .arg("params-search")
         .arg(library_path.as_os_str())
         .arg(ms_data_dir.as_os_str())
         .arg(results_dir.as_os_str())
         .arg("--params-path")
-        .arg(&params_path)
-        .status()?;
-
-    if !status.success() {
-        return Err(ConfigLoadError::NonZeroExit(status.code()));
-    }
+        .arg(&params_path);
+    wait_for_params_command(command, control)?;
 
     let file =
         fs::read_to_string(&params_path).map_err(|e| ConfigLoadError::Other(e.to_string()))?;
@@ -341,178 +994,2234 @@ fn try_fetch_search_defaults() -> Result<Value, ConfigLoadError> {
     Ok(json)
 }
 
-#[tauri::command]
-async fn read_config(path: String) -> Result<Value, String> {
-    let contents = fs::read_to_string(&path).map_err(|e| e.to_string())?;
-    serde_json::from_str(&contents).map_err(|e| e.to_string())
+/// `app_config_dir()/fallback`, where users may drop JSON files that replace
+/// the embedded fallbacks without a rebuild.
+fn fallback_override_dir(resolver: &PathResolver) -> Option<PathBuf> {
+    Some(resolver.app_config_dir()?.join("fallback"))
 }
 
-#[tauri::command]
-async fn save_config(path: String, config: Value) -> Result<(), String> {
-    let pretty = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
-    fs::write(&path, pretty).map_err(|e| e.to_string())
+fn load_fallback(
+    name: &str,
+    embedded: &str,
+    override_dir: Option<&Path>,
+) -> Result<Value, ConfigLoadError> {
+    if let Some(path) = override_dir.map(|dir| dir.join(format!("{name}.json"))) {
+        if path.is_file() {
+            match fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|contents| {
+                    serde_json::from_str::<Value>(&contents).map_err(|e| e.to_string())
+                }) {
+                Ok(value) => return Ok(value),
+                Err(err) => eprintln!(
+                    "Ignoring fallback override {}: {err}; using the embedded {name}",
+                    path.display()
+                ),
+            }
+        }
+    }
+    Ok(serde_json::from_str(embedded)?)
 }
 
-#[tauri::command]
-async fn run_pioneer(
-    window: Window,
-    app_handle: AppHandle,
-    request: RunRequest,
-) -> Result<RunStartedPayload, String> {
-    let pioneer_path = locate_pioneer_binary().map_err(|e| e.to_string())?;
-    let temp_dir = tempdir().map_err(|e| e.to_string())?;
-    let config_path = temp_dir.path().join(request.mode.config_filename());
-    let config_str = serde_json::to_string_pretty(&request.config).map_err(|e| e.to_string())?;
-    fs::write(&config_path, config_str).map_err(|e| e.to_string())?;
-
-    let persisted_path = persist_config(&app_handle, request.mode, &request.config)?;
-
-    let persisted_path_string = persisted_path.map(|p| p.to_string_lossy().to_string());
-
-    let timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or(Duration::from_secs(0))
-        .as_secs();
-    let log_path = temp_dir.path().join(format!("pioneer_run_{timestamp}.log"));
-    FileCreator::create_empty(&log_path).map_err(|e| e.to_string())?;
+fn fallback_defaults(mode: RunMode, override_dir: Option<&Path>) -> Result<Value, ConfigLoadError> {
+    match mode {
+        RunMode::BuildSpecLib => load_fallback("default_build", FALLBACK_BUILD, override_dir),
+        RunMode::SearchDia => load_fallback("default_search", FALLBACK_SEARCH, override_dir),
+    }
+}
 
-    let payload = RunStartedPayload {
-        mode: request.mode,
-        log_path: log_path.to_string_lossy().to_string(),
-        config_path: config_path.to_string_lossy().to_string(),
-        persisted_path: persisted_path_string.clone(),
-    };
+fn fallback_simplified(
+    mode: RunMode,
+    override_dir: Option<&Path>,
+) -> Result<Value, ConfigLoadError> {
+    match mode {
+        RunMode::BuildSpecLib => load_fallback(
+            "default_build_simplified",
+            FALLBACK_BUILD_SIMPLIFIED,
+            override_dir,
+        ),
+        RunMode::SearchDia => load_fallback(
+            "default_search_simplified",
+            FALLBACK_SEARCH_SIMPLIFIED,
+            override_dir,
+        ),
+    }
+}
 
-    window
-        .emit("pioneer-run-started", &payload)
-        .map_err(|e| e.to_string())?;
+/// Serves defaults from the cache when it was filled by the current binary,
+/// so a normal launch never spawns Pioneer. On a miss the live result is
+/// cached together with the binary's version, which is also reported through
+/// `version` for the persisted-config version check.
+fn cached_or_fetch_defaults(
+    mode: RunMode,
+    binary: &Path,
+    cache_dir: Option<&Path>,
+    control: &FetchControl,
+    version: &mut Option<String>,
+) -> (Result<Value, ConfigLoadError>, CacheStatus) {
+    let (cached, status) = defaults_cache::lookup(cache_dir, mode, Some(binary));
+    if let Some(cached) = cached {
+        if version.is_none() {
+            *version = cached.version;
+        }
+        return (Ok(cached.defaults), status);
+    }
 
-    let thread_window = window.clone();
-    std::thread::spawn(move || {
-        let _temp_dir = temp_dir;
-        if let Err(err) = run_process(
-            thread_window,
-            pioneer_path,
-            request.mode,
-            config_path,
-            log_path,
-        ) {
-            eprintln!("Failed to run Pioneer: {err}");
+    let result = try_fetch_defaults_from(mode, binary, control);
+    if let (Ok(defaults), Some(dir)) = (&result, cache_dir) {
+        if version.is_none() {
+            *version = read_pioneer_version(binary).ok();
         }
-    });
+        if let Err(err) = defaults_cache::store(dir, mode, binary, version.as_deref(), defaults) {
+            eprintln!("Failed to cache {} defaults: {err}", mode.as_str());
+        }
+    }
+    (result, status)
+}
 
-    Ok(payload)
+fn try_fetch_defaults(mode: RunMode) -> Result<Value, ConfigLoadError> {
+    try_fetch_defaults_with(mode, &FetchControl::default())
 }
 
-fn persist_config(
-    app_handle: &AppHandle,
+fn try_fetch_defaults_with(
     mode: RunMode,
-    config: &Value,
-) -> Result<Option<PathBuf>, String> {
-    let resolver = app_handle.path_resolver();
-    let Some(path) = config_storage_path(mode, &resolver) else {
-        return Ok(None);
-    };
+    control: &FetchControl,
+) -> Result<Value, ConfigLoadError> {
+    let pioneer = locate_pioneer_binary()?;
+    try_fetch_defaults_from(mode, &pioneer, control)
+}
 
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+/// Asks a specific binary for its defaults. The scratch inputs live in a
+/// tempdir that is removed when the fetch returns, whether or not it succeeded.
+fn try_fetch_defaults_from(
+    mode: RunMode,
+    pioneer: &Path,
+    control: &FetchControl,
+) -> Result<Value, ConfigLoadError> {
+    match mode {
+        RunMode::BuildSpecLib => try_fetch_build_defaults(pioneer, control),
+        RunMode::SearchDia => try_fetch_search_defaults(pioneer, control),
     }
+}
 
-    let pretty = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
-    fs::write(&path, pretty).map_err(|e| e.to_string())?;
-    Ok(Some(path))
+fn resolve_defaults(mode: RunMode, resolver: &PathResolver) -> Result<Value, ConfigLoadError> {
+    try_fetch_defaults(mode)
+        .or_else(|_| fallback_defaults(mode, fallback_override_dir(resolver).as_deref()))
 }
-fn run_process(
-    window: Window,
-    pioneer: PathBuf,
+
+/// Diffs the defaults of two Pioneer binaries, `path_a` as the base, to show
+/// what an upgrade would change.
+#[tauri::command]
+async fn diff_binary_defaults(
     mode: RunMode,
-    config_path: PathBuf,
-    log_path: PathBuf,
-) -> Result<(), String> {
-    if let Err(err) = open_terminal_tail(&log_path) {
-        let _ = window.emit(
-            "pioneer-terminal-warning",
-            &format!("Could not launch external terminal: {err}"),
-        );
-    }
+    path_a: String,
+    path_b: String,
+) -> Result<ConfigDiff, String> {
+    let fetch = move |path: &str| {
+        try_fetch_defaults_from(mode, Path::new(path), &FetchControl::default())
+            .map_err(|e| format!("{} defaults from {path}: {e}", mode.as_str()))
+    };
+    tauri::async_runtime::spawn_blocking(move || {
+        let base = fetch(&path_a)?;
+        let other = fetch(&path_b)?;
+        Ok(diff_config(&base, &other))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
 
-    let mut command = StdCommand::new(&pioneer);
-    command
-        .arg(mode.subcommand())
-        .arg(&config_path)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped());
+/// How the fallback defaults a run would actually fall back to (including
+/// any override in the config directory's `fallback/`) differ from the
+/// binary's.
+#[tauri::command]
+async fn fallback_drift(app_handle: AppHandle, mode: RunMode) -> Result<ConfigDiff, String> {
+    let override_dir = fallback_override_dir(&app_handle.path_resolver());
+    tauri::async_runtime::spawn_blocking(move || {
+        let live =
+            try_fetch_defaults(mode).map_err(|e| format!("{} defaults: {e}", mode.as_str()))?;
+        let fallback =
+            fallback_defaults(mode, override_dir.as_deref()).map_err(|e| e.to_string())?;
+        Ok(diff_config(&fallback, &live))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
 
-    let mut child = command.spawn().map_err(|e| e.to_string())?;
-    let stdout = child
-        .stdout
-        .take()
-        .ok_or_else(|| "Missing stdout pipe".to_string())?;
-    let stderr = child
-        .stderr
-        .take()
-        .ok_or_else(|| "Missing stderr pipe".to_string())?;
+/// Which parameters the persisted config for `mode` overrides, leaf by leaf,
+/// against the binary's defaults, or the fallback defaults when the binary
+/// cannot be run. `added` holds keys these defaults do not have, `removed`
+/// defaults the persisted config lacks. Empty when nothing is persisted.
+#[tauri::command]
+async fn config_diff(app_handle: AppHandle, mode: RunMode) -> Result<PersistedConfigDiff, String> {
+    let resolver = app_handle.path_resolver();
+    let override_dir = fallback_override_dir(&resolver);
+    let persisted_path = config_storage_path(mode, &resolver).filter(|path| path.is_file());
+    tauri::async_runtime::spawn_blocking(move || {
+        let (defaults, source) = match try_fetch_defaults(mode) {
+            Ok(defaults) => (defaults, ConfigSource::Binary),
+            Err(_) => (
+                fallback_defaults(mode, override_dir.as_deref()).map_err(|e| e.to_string())?,
+                ConfigSource::Fallback,
+            ),
+        };
+        let diff = match &persisted_path {
+            Some(path) => {
+                let contents = fs::read_to_string(path).map_err(|e| e.to_string())?;
+                let persisted: Value = serde_json::from_str(encoding::strip_bom(&contents))
+                    .map_err(|e| format!("{} is not valid JSON: {e}", path.display()))?;
+                diff_config(&defaults, &persisted)
+            }
+            None => ConfigDiff::default(),
+        };
+        Ok(PersistedConfigDiff {
+            mode,
+            source,
+            persisted_path: persisted_path.map(|path| path.to_string_lossy().to_string()),
+            diff,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
 
-    let (tx, rx) = mpsc::channel::<(&'static str, String)>();
+#[tauri::command]
+async fn minimize_config(
+    app_handle: AppHandle,
+    mode: RunMode,
+    config: Value,
+) -> Result<Value, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let defaults =
+            resolve_defaults(mode, &app_handle.path_resolver()).map_err(|e| e.to_string())?;
+        Ok(diff::minimize_config(&defaults, &config))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
 
-    spawn_reader(stdout, tx.clone(), "stdout");
-    spawn_reader(stderr, tx.clone(), "stderr");
-    drop(tx);
+/// Shows what loading `persisted` would produce, leaf by leaf, without
+/// touching the saved config.
+#[tauri::command]
+async fn preview_merged_config(
+    app_handle: AppHandle,
+    mode: RunMode,
+    persisted: Value,
+) -> Result<MergePreview, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let defaults =
+            resolve_defaults(mode, &app_handle.path_resolver()).map_err(|e| e.to_string())?;
+        Ok(merge::preview_merge(&defaults, &persisted))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn describe_events() -> Result<Vec<events::EventDescription>, String> {
+    Ok(events::describe_events())
+}
+
+#[tauri::command]
+async fn clean_workspace(
+    app_handle: AppHandle,
+    options: Option<CleanWorkspaceOptions>,
+) -> Result<CleanWorkspaceReport, String> {
+    let resolver = app_handle.path_resolver();
+    let registry = app_handle.state::<RunRegistry>();
+    Ok(workspace::clean_workspace(
+        &resolver,
+        &options.unwrap_or_default(),
+        |run_id| registry.is_active(run_id),
+        |dir| registry.uses_temp_dir(dir),
+    ))
+}
+
+#[tauri::command]
+async fn get_log_patterns(app_handle: AppHandle) -> Result<LogPatterns, String> {
+    Ok(loghealth::load_patterns(&app_handle.path_resolver()))
+}
+
+/// Used by runs started afterwards. `None` restores the defaults.
+#[tauri::command]
+async fn set_log_patterns(
+    app_handle: AppHandle,
+    patterns: Option<LogPatterns>,
+) -> Result<LogPatterns, String> {
+    let patterns = patterns.unwrap_or_default();
+    loghealth::save_patterns(&app_handle.path_resolver(), &patterns)?;
+    Ok(patterns)
+}
+
+/// Classifies sample lines with `patterns`, or the saved patterns when
+/// omitted, without persisting anything.
+#[tauri::command]
+async fn test_log_patterns(
+    app_handle: AppHandle,
+    patterns: Option<LogPatterns>,
+    lines: Vec<String>,
+) -> Result<Vec<LineClassification>, String> {
+    let patterns =
+        patterns.unwrap_or_else(|| loghealth::load_patterns(&app_handle.path_resolver()));
+    loghealth::classify_lines(&patterns, lines)
+}
+
+/// Lets the frontend fall back to the in-app log viewer up front instead of
+/// learning mid-run that no terminal could be opened.
+#[tauri::command]
+async fn terminal_available() -> Result<Option<String>, String> {
+    Ok(terminal::terminal_available())
+}
+
+#[tauri::command]
+async fn get_terminal_settings(app_handle: AppHandle) -> Result<TerminalSettings, String> {
+    Ok(terminal::load_settings(&app_handle.path_resolver()))
+}
+
+/// Applies to terminals opened by later runs.
+#[tauri::command]
+async fn set_terminal_settings(
+    app_handle: AppHandle,
+    settings: TerminalSettings,
+) -> Result<TerminalSettings, String> {
+    terminal::save_settings(&app_handle.path_resolver(), &settings)?;
+    Ok(settings)
+}
+
+#[tauri::command]
+async fn get_output_subdirectories(app_handle: AppHandle) -> Result<OutputSubdirs, String> {
+    Ok(preflight::load_output_subdirs(&app_handle.path_resolver()))
+}
+
+#[tauri::command]
+async fn set_output_subdirectories(
+    app_handle: AppHandle,
+    subdirs: OutputSubdirs,
+) -> Result<OutputSubdirs, String> {
+    preflight::save_output_subdirs(&app_handle.path_resolver(), &subdirs)?;
+    Ok(subdirs)
+}
+
+#[tauri::command]
+async fn get_disk_space_settings(app_handle: AppHandle) -> Result<DiskSpaceSettings, String> {
+    Ok(diskspace::load_settings(&app_handle.path_resolver()))
+}
+
+#[tauri::command]
+async fn set_disk_space_settings(
+    app_handle: AppHandle,
+    settings: DiskSpaceSettings,
+) -> Result<DiskSpaceSettings, String> {
+    diskspace::save_settings(&app_handle.path_resolver(), &settings)?;
+    Ok(settings)
+}
+
+#[tauri::command]
+async fn get_retention_policy(app_handle: AppHandle) -> Result<RetentionPolicy, String> {
+    Ok(workspace::load_retention_policy(
+        &app_handle.path_resolver(),
+    ))
+}
+
+/// Takes effect on the next launch.
+#[tauri::command]
+async fn set_retention_policy(
+    app_handle: AppHandle,
+    policy: RetentionPolicy,
+) -> Result<RetentionPolicy, String> {
+    workspace::save_retention_policy(&app_handle.path_resolver(), &policy)?;
+    Ok(policy)
+}
+
+/// Applies the retention policy once per launch, off the main thread. The
+/// summary is only emitted when something was pruned or failed to be.
+fn prune_runs_on_startup(app_handle: &AppHandle) {
+    let resolver = app_handle.path_resolver();
+    let Some(runs_dir) = workspace::runs_root(&resolver) else {
+        return;
+    };
+    let policy = workspace::load_retention_policy(&resolver);
+    let registry = app_handle.state::<RunRegistry>();
+    let report = workspace::prune_runs(&runs_dir, &policy, |run_id| registry.is_active(run_id));
+    if report.pruned.is_empty() && report.errors.is_empty() {
+        return;
+    }
+    let _ = events::emit_all(app_handle, events::RUNS_PRUNED, &report);
+}
+
+#[tauri::command]
+async fn hash_inputs(
+    mode: RunMode,
+    config: Value,
+    full_content: Option<bool>,
+) -> Result<InputHashes, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        checksums::hash_inputs(mode, &config, full_content.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn estimate_precursors(config: Value) -> Result<PrecursorEstimate, String> {
+    estimate::estimate_build_precursors(&config)
+}
+
+#[tauri::command]
+async fn sanity_check_statistics(config: Value) -> Result<Vec<StatisticsIssue>, String> {
+    Ok(statistics::sanity_check_statistics(&config))
+}
+
+#[tauri::command]
+async fn check_output_locks(mode: RunMode, config: Value) -> Result<Vec<OutputLock>, String> {
+    tauri::async_runtime::spawn_blocking(move || locks::check_output_locks(mode, &config))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// A run Pioneer GUI itself started may hold a lock that looks stale where
+/// process liveness cannot be checked, so nothing is cleared while one is live.
+#[tauri::command]
+async fn clear_output_locks(
+    registry: State<'_, RunRegistry>,
+    mode: RunMode,
+    config: Value,
+    paths: Vec<String>,
+) -> Result<Vec<String>, String> {
+    if registry.any_active() {
+        return Err("Lock files cannot be cleared while a run is in progress".into());
+    }
+    tauri::async_runtime::spawn_blocking(move || locks::clear_output_locks(mode, &config, &paths))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn merge_configs(
+    layers: Vec<Value>,
+    array_merge: Option<ArrayMerge>,
+    array_merge_by_path: Option<BTreeMap<String, ArrayMerge>>,
+) -> Result<Value, String> {
+    let policy = ArrayPolicy {
+        default: array_merge.unwrap_or_default(),
+        by_path: array_merge_by_path.unwrap_or_default(),
+    };
+    merge::merge_configs(&layers, &policy)
+}
+
+#[tauri::command]
+async fn check_parameter_rules(
+    app_handle: AppHandle,
+    mode: RunMode,
+    config: Value,
+) -> Result<Vec<RuleViolation>, String> {
+    let rules = rules::load_rules(&app_handle.path_resolver())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let version = current_binary_version();
+        rules::check_parameter_rules(mode, &config, &rules, version.as_deref())
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn check_policy(
+    app_handle: AppHandle,
+    mode: RunMode,
+    config: Value,
+) -> Result<Vec<PolicyViolation>, String> {
+    Ok(policy::load_policy(&app_handle.path_resolver())?
+        .map(|policy| policy::check_policy(mode, &config, &policy))
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+async fn validate_config_external(
+    config: Value,
+    schema_path: String,
+) -> Result<Vec<SchemaViolation>, String> {
+    schema::validate_config_external(&config, Path::new(&schema_path))
+}
+
+/// Checks `config`'s keys and value types against the defaults of the
+/// configured binary, so parameters that binary would not recognise are
+/// caught before a run is launched.
+#[tauri::command]
+async fn validate_config(
+    mode: RunMode,
+    config: Value,
+    binary_path: Option<String>,
+) -> Result<Vec<ValidationIssue>, String> {
+    let defaults = tauri::async_runtime::spawn_blocking(move || {
+        let pioneer = pioneer_binary_for(binary_path.as_deref())?;
+        try_fetch_defaults_from(mode, &pioneer, &FetchControl::default()).map_err(|e| {
+            format!(
+                "Cannot fetch {} defaults to validate against: {e}",
+                mode.as_str()
+            )
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    Ok(validation::validate_config_keys(&config, &defaults))
+}
+
+#[tauri::command]
+async fn validate_param_ranges(
+    app_handle: AppHandle,
+    mode: RunMode,
+    config: Value,
+) -> Result<Vec<ValidationIssue>, String> {
+    let overrides = app_handle
+        .path_resolver()
+        .app_config_dir()
+        .map(|dir| dir.join(validation::PARAM_RANGES_FILENAME));
+    let rules = validation::range_table(mode, overrides.as_deref())?;
+    Ok(validation::validate_param_ranges(&config, &rules))
+}
+
+#[tauri::command]
+async fn check_range_consistency(
+    build_config: Value,
+    search_config: Value,
+) -> Result<RangeConsistency, String> {
+    Ok(pipeline::check_range_consistency(
+        &build_config,
+        &search_config,
+    ))
+}
+
+#[tauri::command]
+async fn validate_pipeline(
+    app_handle: AppHandle,
+    build_config: Value,
+    search_config: Value,
+) -> Result<Vec<ValidationIssue>, String> {
+    let overrides = app_handle
+        .path_resolver()
+        .app_config_dir()
+        .map(|dir| dir.join(validation::PARAM_RANGES_FILENAME));
+    let build_rules = validation::range_table(RunMode::BuildSpecLib, overrides.as_deref())?;
+    let search_rules = validation::range_table(RunMode::SearchDia, overrides.as_deref())?;
+    Ok(pipeline::validate_pipeline(
+        &build_config,
+        &search_config,
+        &build_rules,
+        &search_rules,
+    ))
+}
+
+#[tauri::command]
+async fn check_auxiliary_databases(config: Value) -> Result<Vec<AuxiliaryDatabase>, String> {
+    Ok(inputs::check_auxiliary_databases(&config))
+}
+
+#[tauri::command]
+async fn check_storage_speed(path: String) -> Result<StorageSpeed, String> {
+    tauri::async_runtime::spawn_blocking(move || storage::check_storage_speed(Path::new(&path)))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn validate_search_inputs(
+    app_handle: AppHandle,
+    config: Value,
+) -> Result<Vec<ValidationIssue>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let formats = current_library_formats(&app_handle);
+        inputs::validate_search_inputs(&config, formats)
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn check_library_version(
+    app_handle: AppHandle,
+    path: String,
+) -> Result<LibraryVersionCheck, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let formats = current_library_formats(&app_handle);
+        library::check_library_version(Path::new(&path), formats)
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn redact_config(mode: RunMode, config: Value) -> Result<RedactedConfig, String> {
+    Ok(redact::redact_config(mode, &config))
+}
+
+#[tauri::command]
+async fn normalize_config_paths(mode: RunMode, config: Value) -> Result<NormalizedPaths, String> {
+    Ok(paths::normalize_config_paths(mode, &config))
+}
+
+#[tauri::command]
+async fn validate_build_inputs(config: Value) -> Result<Vec<ValidationIssue>, String> {
+    Ok(inputs::validate_build_inputs(&config))
+}
+
+#[tauri::command]
+async fn scan_ms_data(dir: String) -> Result<MsDataInventory, String> {
+    staging::scan_ms_data(Path::new(&dir))
+}
+
+/// Probes from a fresh app tempdir, the same place `run_pioneer` stages into.
+#[tauri::command]
+async fn check_staging_strategy(
+    config: Value,
+    max_files: Option<usize>,
+) -> Result<Vec<StagingCheck>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let temp_dir = app_tempdir().map_err(|e| e.to_string())?;
+        Ok(staging::check_staging_strategy(
+            &config,
+            temp_dir.path(),
+            max_files,
+        ))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn detect_filename_collisions(dirs: Vec<String>) -> Result<Vec<FilenameCollision>, String> {
+    let dirs: Vec<PathBuf> = dirs.into_iter().map(PathBuf::from).collect();
+    staging::filename_collisions(&dirs)
+}
+
+#[tauri::command]
+async fn effective_parallelism(mode: RunMode, config: Value) -> Result<ParallelismReport, String> {
+    Ok(parallelism::effective_parallelism(mode, &config))
+}
+
+#[tauri::command]
+async fn check_memory_budget(mode: RunMode, config: Value) -> Result<MemoryBudget, String> {
+    tauri::async_runtime::spawn_blocking(move || memory::check_memory_budget(mode, &config))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Lets a frontend that reloaded mid-run learn an outcome whose
+/// `pioneer-run-complete` it missed.
+#[tauri::command]
+async fn get_last_completion(
+    cache: State<'_, CompletionCache>,
+    mode: RunMode,
+    run_id: Option<String>,
+) -> Result<Option<LastCompletion>, String> {
+    Ok(cache.get(mode, run_id.as_deref()))
+}
+
+#[tauri::command]
+async fn pause_run(
+    window: Window,
+    registry: State<'_, RunRegistry>,
+    run_id: String,
+) -> Result<RunStatus, String> {
+    let status = registry.set_paused(&run_id, true)?;
+    let _ = events::emit(&window, events::RUN_STATE, &status);
+    taskbar::report_paused(&window, status.mode, true);
+    Ok(status)
+}
+
+#[tauri::command]
+async fn resume_run(
+    window: Window,
+    registry: State<'_, RunRegistry>,
+    run_id: String,
+) -> Result<RunStatus, String> {
+    let status = registry.set_paused(&run_id, false)?;
+    let _ = events::emit(&window, events::RUN_STATE, &status);
+    taskbar::report_paused(&window, status.mode, false);
+    Ok(status)
+}
+
+/// Declares a batch of `total_runs` runs; runs started with its `batch_id`
+/// then drive `pioneer-batch-progress`. Restarting an id resets its counts.
+#[tauri::command]
+async fn start_batch(
+    window: Window,
+    tracker: State<'_, BatchTracker>,
+    batch_id: String,
+    total_runs: usize,
+) -> Result<BatchProgressPayload, String> {
+    let payload = tracker.begin(&batch_id, total_runs)?;
+    let _ = events::emit(&window, events::BATCH_PROGRESS, &payload);
+    Ok(payload)
+}
+
+/// Cancels a run whether or not its process has been spawned yet; the run
+/// finishes with a `cancelled` completion event either way. Takes the run id,
+/// or a mode to cancel that workflow's active run.
+#[tauri::command]
+async fn cancel_run(
+    registry: State<'_, RunRegistry>,
+    run_id: Option<String>,
+    mode: Option<RunMode>,
+) -> Result<(), String> {
+    let run_id = match (run_id, mode) {
+        (Some(run_id), _) => run_id,
+        (None, Some(mode)) => registry
+            .active_run_for_mode(mode)
+            .ok_or_else(|| format!("No {} run is in progress", mode.label()))?,
+        (None, None) => return Err("cancel_run needs a run_id or a mode".into()),
+    };
+    registry.request_cancel(&run_id)
+}
+
+#[tauri::command]
+async fn peek_active_log(
+    registry: State<'_, RunRegistry>,
+    n: Option<usize>,
+) -> Result<LogPeek, String> {
+    Ok(registry.peek_active_log(n.unwrap_or(10)))
+}
+
+/// Live state for runs still executing, otherwise the outcome recorded in the
+/// run's metadata.
+#[tauri::command]
+async fn get_run_status(
+    app_handle: AppHandle,
+    registry: State<'_, RunRegistry>,
+    run_id: String,
+) -> Result<RunStatus, String> {
+    if let Some(status) = registry.status(&run_id) {
+        return Ok(status);
+    }
+    let root = workspace::runs_root(&app_handle.path_resolver())
+        .ok_or_else(|| "App data directory is unavailable".to_string())?;
+    let metadata = runs::read_metadata(&runs::resolve_run_dir(&root, &run_id)?)?;
+    let state = match metadata.success {
+        Some(true) => RunState::Succeeded,
+        Some(false) => RunState::Failed,
+        None => RunState::Unknown,
+    };
+    Ok(RunStatus {
+        run_id: metadata.run_id,
+        mode: metadata.mode,
+        state,
+        pid: None,
+    })
+}
+
+#[tauri::command]
+async fn list_runs(app_handle: AppHandle) -> Result<Vec<RunMetadata>, String> {
+    Ok(workspace::runs_root(&app_handle.path_resolver())
+        .map(|root| runs::list_runs(&root))
+        .unwrap_or_default())
+}
+
+/// Finished runs, most recent first. Unlike `list_runs`, this includes runs
+/// without a run directory and runs whose directory has been pruned.
+#[tauri::command]
+async fn load_run_history(
+    app_handle: AppHandle,
+    limit: Option<usize>,
+) -> Result<Vec<HistoryRecord>, String> {
+    let Some(path) = history::history_path(&app_handle.path_resolver()) else {
+        return Ok(Vec::new());
+    };
+    let limit = limit.unwrap_or(history::DEFAULT_HISTORY_LIMIT);
+    tauri::async_runtime::spawn_blocking(move || history::load_history(&path, limit))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn clear_run_history(app_handle: AppHandle) -> Result<(), String> {
+    match history::history_path(&app_handle.path_resolver()) {
+        Some(path) => history::clear_history(&path),
+        None => Ok(()),
+    }
+}
+
+/// The machine and software snapshot recorded when the run started.
+#[tauri::command]
+async fn get_run_environment(
+    app_handle: AppHandle,
+    run_id: String,
+) -> Result<RunEnvironment, String> {
+    let root = workspace::runs_root(&app_handle.path_resolver())
+        .ok_or_else(|| "App data directory is unavailable".to_string())?;
+    runs::read_metadata(&runs::resolve_run_dir(&root, &run_id)?)?
+        .environment
+        .ok_or_else(|| format!("Run `{run_id}` recorded no environment"))
+}
+
+/// Copies a run's log to `dest`, or into it when `dest` is a directory, and
+/// returns the bytes copied. A running run's log is copied as it stands.
+#[tauri::command]
+async fn copy_log(app_handle: AppHandle, run_id: String, dest: String) -> Result<u64, String> {
+    let root = workspace::runs_root(&app_handle.path_resolver())
+        .ok_or_else(|| "App data directory is unavailable".to_string())?;
+    let metadata = runs::read_metadata(&runs::resolve_run_dir(&root, &run_id)?)?;
+    let source = PathBuf::from(&metadata.log_path);
+    let mut dest = PathBuf::from(dest);
+    if dest.is_dir() {
+        if let Some(name) = source.file_name() {
+            dest.push(name);
+        }
+    }
+    // fs::copy onto the source truncates it before reading, losing the log.
+    let source = source
+        .canonicalize()
+        .map_err(|e| format!("Run log {} is unavailable: {e}", source.display()))?;
+    if canonical_destination(&dest).is_some_and(|dest| dest == source) {
+        return Err(format!(
+            "{} is the run's log itself; choose another destination",
+            dest.display()
+        ));
+    }
+    fs::copy(&source, &dest).map_err(|e| {
+        format!(
+            "Failed to copy {} to {}: {e}",
+            source.display(),
+            dest.display()
+        )
+    })
+}
+
+/// `dest` with symlinks and `..` resolved, through its parent when `dest`
+/// does not exist yet.
+fn canonical_destination(dest: &Path) -> Option<PathBuf> {
+    if let Ok(dest) = dest.canonicalize() {
+        return Some(dest);
+    }
+    let parent = match dest.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    Some(parent.canonicalize().ok()?.join(dest.file_name()?))
+}
+
+/// Bundles a finished run into a zip a colleague can rerun from.
+#[tauri::command]
+async fn export_reproducer(
+    app_handle: AppHandle,
+    run_id: String,
+    dest_zip: String,
+    options: Option<ReproducerOptions>,
+) -> Result<ReproducerReport, String> {
+    let root = workspace::runs_root(&app_handle.path_resolver())
+        .ok_or_else(|| "App data directory is unavailable".to_string())?;
+    let run_dir = runs::resolve_run_dir(&root, &run_id)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        reproducer::export_reproducer(&run_dir, Path::new(&dest_zip), options.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Copies the run's `events.jsonl` to `dest` for replaying in the UI.
+#[tauri::command]
+async fn export_run_events(
+    app_handle: AppHandle,
+    run_id: String,
+    dest: String,
+) -> Result<usize, String> {
+    let root = workspace::runs_root(&app_handle.path_resolver())
+        .ok_or_else(|| "App data directory is unavailable".to_string())?;
+    let run_dir = runs::resolve_run_dir(&root, &run_id)?;
+    recorder::export_events(&run_dir, Path::new(&dest))
+}
+
+#[tauri::command]
+async fn label_run(
+    app_handle: AppHandle,
+    run_id: String,
+    label: String,
+    rename: Option<bool>,
+) -> Result<RunMetadata, String> {
+    let root = workspace::runs_root(&app_handle.path_resolver())
+        .ok_or_else(|| "App data directory is unavailable".to_string())?;
+    runs::label_run(&root, &run_id, &label, rename.unwrap_or(false))
+}
+
+/// Starts the localhost event bridge and remembers it as enabled, on `port`
+/// or an OS-chosen one.
+#[tauri::command]
+async fn start_event_bridge(
+    app_handle: AppHandle,
+    bridge: State<'_, EventBridge>,
+    port: Option<u16>,
+) -> Result<BridgeStatus, String> {
+    bridge.start(port)?;
+    bridge::save_settings(
+        &app_handle.path_resolver(),
+        BridgeSettings {
+            enabled: true,
+            port,
+        },
+    )?;
+    Ok(bridge.status())
+}
+
+#[tauri::command]
+async fn stop_event_bridge(
+    app_handle: AppHandle,
+    bridge: State<'_, EventBridge>,
+) -> Result<BridgeStatus, String> {
+    bridge.stop();
+    let resolver = app_handle.path_resolver();
+    let settings = bridge::load_settings(&resolver);
+    bridge::save_settings(
+        &resolver,
+        BridgeSettings {
+            enabled: false,
+            ..settings
+        },
+    )?;
+    Ok(bridge.status())
+}
+
+#[tauri::command]
+async fn event_bridge_status(bridge: State<'_, EventBridge>) -> Result<BridgeStatus, String> {
+    Ok(bridge.status())
+}
+
+/// Pages the Pioneer binary (the located one unless `path` is given) into
+/// the OS cache ahead of a run.
+#[tauri::command]
+async fn warm_binary(path: Option<String>) -> Result<WarmupReport, String> {
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => locate_pioneer_binary().map_err(|e| e.to_string())?,
+    };
+    tauri::async_runtime::spawn_blocking(move || binary::warm_binary(&path))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn list_models(app_handle: AppHandle) -> Result<ModelList, String> {
+    let pioneer = locate_pioneer_binary().map_err(|e| e.to_string())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let version = read_pioneer_version(&pioneer).ok();
+        models::list_models(&app_handle.path_resolver(), &pioneer, version.as_deref())
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_binary_lookup_retry() -> Result<LookupRetry, String> {
+    Ok(binary::lookup_retry())
+}
+
+/// Retries are capped at 5 and the initial backoff at 2 s.
+#[tauri::command]
+async fn set_binary_lookup_retry(
+    app_handle: AppHandle,
+    retry: LookupRetry,
+) -> Result<LookupRetry, String> {
+    binary::set_lookup_retry(&app_handle.path_resolver(), retry)
+}
+
+/// Which binary runs would use, where it was found, and whether it can be
+/// executed.
+#[tauri::command]
+async fn check_binary() -> Result<BinaryCheck, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        let candidate = locate_pioneer_candidate().map_err(|e| e.to_string())?;
+        Ok(binary::check_binary(&candidate))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Subcommands the binary lists in `--help`, cached for the session;
+/// `refresh` re-reads them, e.g. after replacing the binary in place.
+#[tauri::command]
+async fn discover_binary_capabilities(
+    app_handle: AppHandle,
+    refresh: Option<bool>,
+    binary_path: Option<String>,
+) -> Result<BinaryCapabilities, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let pioneer = pioneer_binary_for(binary_path.as_deref())?;
+        let cache = app_handle.state::<CapabilityCache>();
+        if !refresh.unwrap_or(false) {
+            if let Some(capabilities) = cache.get(&pioneer) {
+                return Ok(capabilities);
+            }
+        }
+        let capabilities = capabilities::discover_capabilities(&pioneer)?;
+        cache.insert(&pioneer, capabilities.clone());
+        Ok(capabilities)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn get_pioneer_version(binary_path: Option<String>) -> Result<PioneerVersionInfo, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let pioneer = pioneer_binary_for(binary_path.as_deref())?;
+        let output = read_version_output(&pioneer).map_err(|e| e.to_string())?;
+        let version = version_from_output(&output);
+        let stderr = version
+            .is_none()
+            .then(|| String::from_utf8_lossy(&output.stderr).to_string());
+        Ok(PioneerVersionInfo {
+            path: pioneer.to_string_lossy().to_string(),
+            version,
+            exit_code: output.status.code(),
+            stderr,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn get_command_wrapper(app_handle: AppHandle) -> Result<Option<Vec<String>>, String> {
+    Ok(binary::command_wrapper(&app_handle.path_resolver()))
+}
+
+/// Applies to runs started afterwards; `None` or an empty list removes it.
+#[tauri::command]
+async fn set_command_wrapper(
+    app_handle: AppHandle,
+    wrapper: Option<Vec<String>>,
+) -> Result<(), String> {
+    binary::set_command_wrapper(&app_handle.path_resolver(), wrapper)
+}
+
+#[tauri::command]
+async fn set_warm_on_startup(app_handle: AppHandle, enabled: bool) -> Result<(), String> {
+    binary::set_warm_on_startup(&app_handle.path_resolver(), enabled)
+}
+
+#[tauri::command]
+async fn set_binary_path(app_handle: AppHandle, path: Option<String>) -> Result<(), String> {
+    binary::set_binary_path(&app_handle.path_resolver(), path.as_deref().map(Path::new))
+}
+
+#[tauri::command]
+async fn install_binary(
+    app_handle: AppHandle,
+    window: Window,
+    url: String,
+    dest: Option<String>,
+    sha256: Option<String>,
+) -> Result<String, String> {
+    let resolver = app_handle.path_resolver();
+    let dest = match dest {
+        Some(dest) => PathBuf::from(dest),
+        None => resolver
+            .app_data_dir()
+            .ok_or_else(|| "App data directory is unavailable".to_string())?
+            .join(install::BIN_DIR)
+            .join(install::binary_filename(&url)),
+    };
+
+    let progress_url = url.clone();
+    let installed = tauri::async_runtime::spawn_blocking(move || {
+        install::download_binary(&url, &dest, sha256.as_deref(), |downloaded, total| {
+            let _ = events::emit(
+                &window,
+                events::INSTALL_PROGRESS,
+                InstallProgressPayload {
+                    url: progress_url.clone(),
+                    downloaded,
+                    total,
+                },
+            );
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    binary::set_binary_path(&resolver, Some(&installed))?;
+    Ok(installed.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+async fn stage_weights(
+    app_handle: AppHandle,
+    cache: State<'_, StageWeightCache>,
+    mode: RunMode,
+) -> Result<StageWeights, String> {
+    Ok(cache.get(
+        mode,
+        workspace::runs_root(&app_handle.path_resolver()).as_deref(),
+    ))
+}
+
+#[tauri::command]
+async fn check_output_writable(
+    app_handle: AppHandle,
+    mode: RunMode,
+    config: Value,
+) -> Result<Vec<WritableCheck>, String> {
+    Ok(preflight::check_output_writable(
+        mode,
+        &config,
+        app_handle.path_resolver().app_config_dir().as_deref(),
+    ))
+}
+
+/// The checks `run_pioneer` makes before launching, all run and reported
+/// together rather than stopping at the first problem.
+#[tauri::command]
+async fn preflight_run(
+    app_handle: AppHandle,
+    mode: RunMode,
+    config: Value,
+) -> Result<PreflightReport, String> {
+    let config_dir = app_handle.path_resolver().app_config_dir();
+    let subdirs = preflight::load_output_subdirs(&app_handle.path_resolver());
+    let disk_settings = diskspace::load_settings(&app_handle.path_resolver());
+    tauri::async_runtime::spawn_blocking(move || {
+        let formats = current_library_formats(&app_handle);
+        let mut outputs = preflight::check_output_writable(mode, &config, config_dir.as_deref());
+        outputs.extend(preflight::check_output_subdirs(
+            mode,
+            &config,
+            subdirs.for_mode(mode),
+        ));
+        outputs.retain(|check| !check.writable);
+        let inputs = preflight::check_inputs(mode, &config, formats);
+        Ok(PreflightReport {
+            clear: inputs.is_empty() && outputs.is_empty(),
+            existing_results: preflight::check_existing_results(mode, &config),
+            disk_space: diskspace::check_disk_space(mode, &config, disk_settings),
+            inputs,
+            outputs,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[derive(Serialize)]
+struct AssembledConfig {
+    mode: RunMode,
+    config: Value,
+}
+
+#[tauri::command]
+async fn split_config(
+    mode: RunMode,
+    config: Value,
+    dest_dir: String,
+) -> Result<SectionManifest, String> {
+    sections::split_config(mode, &config, Path::new(&dest_dir))
+}
+
+#[tauri::command]
+async fn assemble_config(dest_dir: String) -> Result<AssembledConfig, String> {
+    let (mode, config) = sections::assemble_config(Path::new(&dest_dir))?;
+    Ok(AssembledConfig { mode, config })
+}
+
+#[tauri::command]
+async fn read_config(path: String) -> Result<Value, String> {
+    let contents = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    encoding::parse_lenient(&contents).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn normalize_config_file(path: String) -> Result<FileNormalization, String> {
+    encoding::normalize_config_file(Path::new(&path))
+}
+
+#[tauri::command]
+async fn canonicalize_config(config: Value) -> Result<String, String> {
+    canonical::canonicalize_config(&config)
+}
+
+/// Rewrites the persisted config for `mode` with sorted keys.
+#[tauri::command]
+async fn canonicalize_persisted_config(
+    app_handle: AppHandle,
+    mode: RunMode,
+) -> Result<String, String> {
+    let path = config_storage_path(mode, &app_handle.path_resolver())
+        .filter(|path| path.is_file())
+        .ok_or_else(|| format!("No persisted {} config to rewrite", mode.label()))?;
+    canonical::canonicalize_file(&path)
+}
+
+/// Backups of the persisted config for `mode`, newest first. One is taken
+/// each time a run overwrites the saved config with different content.
+#[tauri::command]
+async fn list_config_backups(
+    app_handle: AppHandle,
+    mode: RunMode,
+) -> Result<Vec<ConfigBackup>, String> {
+    Ok(config_storage_path(mode, &app_handle.path_resolver())
+        .map(|path| backups::list_backups(&path))
+        .unwrap_or_default())
+}
+
+/// Restores backup `index` (1 is the newest) as the persisted config for
+/// `mode` and returns it merged over the defaults, ready for the form.
+#[tauri::command]
+async fn restore_config_backup(
+    app_handle: AppHandle,
+    mode: RunMode,
+    index: usize,
+) -> Result<Value, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let resolver = app_handle.path_resolver();
+        let path = config_storage_path(mode, &resolver)
+            .ok_or_else(|| "No configuration directory is available".to_string())?;
+        let restored = backups::restore_backup(&path, index)?;
+        let defaults = resolve_defaults(mode, &resolver).map_err(|e| e.to_string())?;
+        Ok(merge::merge_saved(&defaults, &restored))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Saves `config` as a named preset for `mode`, replacing one of the same
+/// name.
+#[tauri::command]
+async fn save_profile(
+    app_handle: AppHandle,
+    mode: RunMode,
+    name: String,
+    config: Value,
+) -> Result<ConfigProfile, String> {
+    let dir = profiles_dir(mode, &app_handle.path_resolver())
+        .ok_or_else(|| "No configuration directory is available".to_string())?;
+    profiles::save_profile(&dir, &name, &config)
+}
+
+#[tauri::command]
+async fn list_profiles(app_handle: AppHandle, mode: RunMode) -> Result<Vec<ConfigProfile>, String> {
+    match profiles_dir(mode, &app_handle.path_resolver()) {
+        Some(dir) => profiles::list_profiles(&dir),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Returns profile `name` merged over the current defaults, as a persisted
+/// config is on load, so parameters added since it was saved are filled in.
+#[tauri::command]
+async fn load_profile(app_handle: AppHandle, mode: RunMode, name: String) -> Result<Value, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let resolver = app_handle.path_resolver();
+        let dir = profiles_dir(mode, &resolver)
+            .ok_or_else(|| "No configuration directory is available".to_string())?;
+        let profile = profiles::load_profile(&dir, &name)?;
+        let defaults = resolve_defaults(mode, &resolver).map_err(|e| e.to_string())?;
+        Ok(merge::merge_saved(&defaults, &profile))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn delete_profile(app_handle: AppHandle, mode: RunMode, name: String) -> Result<(), String> {
+    let dir = profiles_dir(mode, &app_handle.path_resolver())
+        .ok_or_else(|| "No configuration directory is available".to_string())?;
+    profiles::delete_profile(&dir, &name)
+}
+
+/// Deletes the persisted config for `mode` (and its version sidecar) so the
+/// next load starts from the defaults, and returns those defaults. The
+/// deleted config is kept as a backup unless `clear_backups` removes the
+/// backups too.
+#[tauri::command]
+async fn reset_config_to_defaults(
+    app_handle: AppHandle,
+    mode: RunMode,
+    clear_backups: Option<bool>,
+) -> Result<Value, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let resolver = app_handle.path_resolver();
+        if let Some(path) = config_storage_path(mode, &resolver) {
+            if clear_backups.unwrap_or(false) {
+                backups::clear_backups(&path)?;
+            } else {
+                backups::back_up_before_removal(&path)?;
+            }
+            for file in [persisted_meta_path(&path), path] {
+                match fs::remove_file(&file) {
+                    Ok(()) => {}
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(err) => return Err(format!("{}: {err}", file.display())),
+                }
+            }
+        }
+        resolve_defaults(mode, &resolver).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn save_config(path: String, config: Value) -> Result<(), String> {
+    let pretty = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+    atomic::write_atomic(Path::new(&path), pretty).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn run_pioneer(
+    window: Window,
+    app_handle: AppHandle,
+    request: RunRequest,
+) -> Result<RunStartedPayload, String> {
+    let pioneer_path = pioneer_binary_for(request.binary_path.as_deref())?;
+    let log_template = request
+        .log_filename_template
+        .as_deref()
+        .unwrap_or(runs::DEFAULT_LOG_FILENAME_TEMPLATE);
+    runs::check_log_template(log_template)?;
+    let log_dir = request.log_dir.as_deref().map(PathBuf::from);
+    if let Some(dir) = &log_dir {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Cannot create log directory {}: {e}", dir.display()))?;
+    }
+    if request.split_stream_logs && request.combined_output {
+        return Err(
+            "split_stream_logs needs separate stdout/stderr pipes; turn off combined_output".into(),
+        );
+    }
+    let resume_plan = if request.resume {
+        resume::check_resumable(&pioneer_path, request.mode)?;
+        let runs_root = workspace::runs_root(&app_handle.path_resolver())
+            .ok_or_else(|| "App data directory is unavailable".to_string())?;
+        let registry = app_handle.state::<RunRegistry>();
+        Some(resume::plan_resume(
+            &runs_root,
+            &request.config,
+            |run_id| registry.is_active(run_id),
+        )?)
+    } else {
+        None
+    };
+    let resume_stage = resume_plan.as_ref().and_then(|plan| {
+        request
+            .mode
+            .stage_sequence()
+            .iter()
+            .position(|stage| stage.key == plan.resume_stage)
+    });
+    let command_wrapper = binary::command_wrapper(&app_handle.path_resolver());
+    if let Some(wrapper) = &command_wrapper {
+        binary::check_command_wrapper(wrapper)?;
+    }
+    invocation::check_extra_args(&request.extra_args)?;
+    invocation::check_env(&request.env)?;
+    let post_run_command = request
+        .post_run_command
+        .clone()
+        .filter(|command| !command.is_empty());
+    if let Some(command) = &post_run_command {
+        hooks::check_hook(command)?;
+    }
+    if let Some(policy) = policy::load_policy(&app_handle.path_resolver())? {
+        if policy.enforce {
+            let violations = policy::check_policy(request.mode, &request.config, &policy);
+            if let Some(problems) = policy::describe_violations(&violations) {
+                return Err(problems);
+            }
+        }
+    }
+    let binary_version = read_pioneer_version(&pioneer_path).ok();
+    if !request.skip_preflight {
+        let formats = library_formats(&app_handle, &pioneer_path);
+        let issues = preflight::check_inputs(request.mode, &request.config, formats);
+        if let Some(problems) = preflight::describe_input_issues(&issues) {
+            return Err(problems);
+        }
+    }
+    let temp_dir = app_tempdir().map_err(|e| e.to_string())?;
+    let timestamp = runs::unix_now();
+    let stage_weights = app_handle.state::<StageWeightCache>().get(
+        request.mode,
+        workspace::runs_root(&app_handle.path_resolver()).as_deref(),
+    );
+
+    // Runs live in their own directory under app data so logs and config
+    // snapshots survive the session; the tempdir only holds staged inputs.
+    let run_dir = workspace::runs_root(&app_handle.path_resolver())
+        .and_then(|root| runs::create_run_dir(&root, request.mode, timestamp).ok());
+    let recording = run_dir
+        .as_deref()
+        .map(|dir| Recording::start(&app_handle, &runs::run_id_of(dir), request.mode, dir));
+    let output_dir = run_dir.as_deref().unwrap_or(temp_dir.path()).to_path_buf();
+    let config_path = output_dir.join(request.mode.config_filename());
+
+    let mut run_config = request.config.clone();
+    let max_files = match (request.mode, request.max_files) {
+        (RunMode::SearchDia, Some(max_files)) => Some(staging::scope_search_inputs(
+            &mut run_config,
+            temp_dir.path(),
+            max_files,
+            |current, total| {
+                send_prepare_update(
+                    &window,
+                    request.mode,
+                    &format!("Staging {total} MS files: {current}/{total}"),
+                    Some((current, total)),
+                )
+            },
+        )?),
+        _ => None,
+    };
+
+    if !request.skip_preflight {
+        let writable = preflight::check_output_writable(
+            request.mode,
+            &run_config,
+            app_handle.path_resolver().app_config_dir().as_deref(),
+        );
+        if let Some(problems) = preflight::describe_unwritable(&writable) {
+            return Err(problems);
+        }
+        let subdirs = preflight::load_output_subdirs(&app_handle.path_resolver());
+        let subdir_checks = preflight::check_output_subdirs(
+            request.mode,
+            &run_config,
+            subdirs.for_mode(request.mode),
+        );
+        if let Some(problems) = preflight::describe_unwritable(&subdir_checks) {
+            return Err(problems);
+        }
+    }
+
+    let storage_warnings = if request.check_storage {
+        send_prepare_update(&window, request.mode, "Checking storage speed", None);
+        let mut dirs: Vec<PathBuf> = preflight::output_directories(request.mode, &run_config)
+            .into_iter()
+            .map(|(_, dir)| dir)
+            .collect();
+        dirs.push(temp_dir.path().to_path_buf());
+        dirs.iter()
+            .flat_map(|dir| match storage::check_storage_speed(dir) {
+                Ok(speed) => speed.warnings,
+                Err(err) => vec![format!("Could not benchmark {}: {err}", dir.display())],
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let expected_precursors = if request.mode == RunMode::BuildSpecLib {
+        send_prepare_update(&window, request.mode, "Estimating precursor count", None);
+        estimate::estimate_build_precursors(&run_config)
+            .ok()
+            .map(|estimate| estimate.precursors)
+    } else {
+        None
+    };
+
+    let progress_file = if request.progress_source.uses_file() {
+        Some(
+            progressfile::progress_file_path(request.mode, &run_config).ok_or_else(|| {
+                "Reading progress from Pioneer's progress file needs an output directory in the config"
+                    .to_string()
+            })?,
+        )
+    } else {
+        None
+    };
+
+    let output_dirs: Vec<PathBuf> = preflight::output_directories(request.mode, &run_config)
+        .into_iter()
+        .map(|(_, dir)| dir)
+        .collect();
+
+    let input_hashes = request.record_input_hashes.then(|| {
+        send_prepare_update(&window, request.mode, "Hashing inputs", None);
+        checksums::hash_inputs(request.mode, &run_config, request.hash_full_content)
+    });
+
+    send_prepare_update(&window, request.mode, "Writing run configuration", None);
+    let config_str = serde_json::to_string_pretty(&run_config).map_err(|e| e.to_string())?;
+    fs::write(&config_path, config_str).map_err(|e| e.to_string())?;
+
+    let persisted_path = persist_config(
+        &app_handle,
+        request.mode,
+        &request.config,
+        binary_version.as_deref(),
+    )?;
+
+    let persisted_path_string = persisted_path.map(|p| p.to_string_lossy().to_string());
+
+    let run_id = run_dir.as_deref().map(runs::run_id_of);
+    let run_key = run_id
+        .clone()
+        .unwrap_or_else(|| format!("{}_{timestamp}", request.mode.as_str()));
+    let log_name = runs::log_filename(log_template, request.mode, timestamp, &run_key)?;
+    let log_path = log_dir.as_deref().unwrap_or(&output_dir).join(log_name);
+    FileCreator::create_empty(&log_path).map_err(|e| e.to_string())?;
+    let stream_logs = if request.split_stream_logs {
+        let paths = (
+            output_dir.join(STDOUT_LOG_FILENAME),
+            output_dir.join(STDERR_LOG_FILENAME),
+        );
+        FileCreator::create_empty(&paths.0).map_err(|e| e.to_string())?;
+        FileCreator::create_empty(&paths.1).map_err(|e| e.to_string())?;
+        Some(paths)
+    } else {
+        None
+    };
+
+    if let (Some(dir), Some(id)) = (&run_dir, &run_id) {
+        runs::write_metadata(
+            dir,
+            &RunMetadata {
+                run_id: id.clone(),
+                mode: request.mode,
+                label: None,
+                started_at: timestamp,
+                finished_at: None,
+                success: None,
+                exit_code: None,
+                config_path: config_path.to_string_lossy().to_string(),
+                log_path: log_path.to_string_lossy().to_string(),
+                stage_durations: Default::default(),
+                input_hashes,
+                environment: Some(environment::capture_environment(
+                    &pioneer_path,
+                    binary_version.as_deref(),
+                )),
+                resumed_from: resume_plan
+                    .as_ref()
+                    .map(|plan| plan.previous_run_id.clone()),
+            },
+        )?;
+    }
+
+    let payload = RunStartedPayload {
+        mode: request.mode,
+        run_id,
+        log_path: log_path.to_string_lossy().to_string(),
+        config_path: config_path.to_string_lossy().to_string(),
+        persisted_path: persisted_path_string.clone(),
+        max_files,
+        stdout_log_path: stream_logs
+            .as_ref()
+            .map(|(stdout, _)| stdout.to_string_lossy().to_string()),
+        stderr_log_path: stream_logs
+            .as_ref()
+            .map(|(_, stderr)| stderr.to_string_lossy().to_string()),
+        storage_warnings,
+        resume: resume_plan,
+    };
+
+    app_handle.state::<CompletionCache>().clear(request.mode);
+    let registry = app_handle.state::<RunRegistry>();
+    if let Some(run_id) = &payload.run_id {
+        registry.reserve(run_id, request.mode, temp_dir.path());
+    }
+    if let Err(err) = events::emit(&window, events::RUN_STARTED, &payload) {
+        if let Some(run_id) = &payload.run_id {
+            registry.unregister(run_id);
+        }
+        return Err(err.to_string());
+    }
+
+    let thread_window = window.clone();
+    let thread_run_id = payload.run_id.clone();
+    let thread_app_handle = app_handle.clone();
+    let reserved_run_id = payload.run_id.clone();
+    let end_token = terminal::end_token(&run_key);
+    let end_marked = Arc::new(AtomicBool::new(false));
+    let auto_close_terminal = terminal::load_settings(&app_handle.path_resolver()).auto_close();
+    let classifier = loghealth::load_classifier(&app_handle.path_resolver());
+    let history =
+        history::history_path(&app_handle.path_resolver()).map(|history_file| PendingRecord {
+            history_file,
+            run_id: payload.run_id.clone(),
+            started_at: timestamp,
+            config_path: config_path.clone(),
+            log_path: log_path.clone(),
+        });
+    std::thread::spawn(move || {
+        let _temp_dir = temp_dir;
+        let _recording = recording;
+        let result = run_process(
+            thread_window,
+            pioneer_path,
+            request.mode,
+            config_path,
+            log_path.clone(),
+            RunOptions {
+                run_dir: run_dir.clone(),
+                run_id: thread_run_id,
+                expected_precursors,
+                combined_output: request.combined_output,
+                eta: EtaEstimator::start(stage_weights.progress_at(resume_stage.unwrap_or(0))),
+                stage_weights,
+                max_line_bytes: request
+                    .max_line_bytes
+                    .filter(|max| *max > 0)
+                    .unwrap_or(DEFAULT_MAX_LINE_BYTES),
+                stage_debug: request.stage_debug,
+                output_dirs,
+                run_key: run_key.clone(),
+                batch_id: request.batch_id.clone(),
+                end_token: end_token.clone(),
+                log_path: log_path.clone(),
+                end_marked: end_marked.clone(),
+                external_terminal: request.external_terminal.unwrap_or(true),
+                auto_close_terminal,
+                classifier,
+                stream_logs,
+                resume_stage,
+                progress_source: request.progress_source,
+                stage_matching: request.stage_matching.clone(),
+                progress_file,
+                command_wrapper,
+                extra_args: request.extra_args.clone(),
+                env: request.env.clone(),
+                timeout: request
+                    .timeout_secs
+                    .filter(|secs| *secs > 0)
+                    .map(Duration::from_secs),
+                post_run: post_run_command.map(|command| PostRunHook {
+                    command,
+                    always: request.post_run_always,
+                    log_path: log_path.clone(),
+                    timeout: request
+                        .post_run_timeout_secs
+                        .filter(|secs| *secs > 0)
+                        .map_or(hooks::DEFAULT_HOOK_TIMEOUT, Duration::from_secs),
+                }),
+                pipeline: request.pipeline,
+                history,
+            },
+        );
+        // Normally already written by complete_run; this covers runs that
+        // failed before Pioneer started.
+        if !end_marked.swap(true, Ordering::SeqCst) {
+            let summary = match &result {
+                Ok(()) => "run finished".to_string(),
+                Err(err) => format!("run failed: {err}"),
+            };
+            if let Err(err) = terminal::write_end_marker(&log_path, &end_token, &summary) {
+                eprintln!("Failed to write the end-of-run marker: {err}");
+            }
+        }
+        if let Err(err) = result {
+            eprintln!("Failed to run Pioneer: {err}");
+            if let Some(dir) = &run_dir {
+                let _ = runs::finish_run(dir, false, None);
+            }
+        }
+        // Also clears the reservation when run_process bailed out before
+        // registering the process.
+        if let (Some(registry), Some(run_id)) = (
+            thread_app_handle.try_state::<RunRegistry>(),
+            &reserved_run_id,
+        ) {
+            registry.unregister(run_id);
+        }
+    });
+
+    Ok(payload)
+}
+
+/// Checks a request the way `run_pioneer` does and returns the command line
+/// and config it would use, without creating a run directory, persisting the
+/// config, or starting Pioneer. Inputs are not staged, so a `max_files` run
+/// shows the config as given rather than the scoped copy.
+#[tauri::command]
+async fn preview_command(
+    app_handle: AppHandle,
+    request: RunRequest,
+) -> Result<CommandPreview, String> {
+    let pioneer_path = pioneer_binary_for(request.binary_path.as_deref())?;
+    if request.resume {
+        resume::check_resumable(&pioneer_path, request.mode)?;
+    }
+    let command_wrapper = binary::command_wrapper(&app_handle.path_resolver());
+    if let Some(wrapper) = &command_wrapper {
+        binary::check_command_wrapper(wrapper)?;
+    }
+    invocation::check_extra_args(&request.extra_args)?;
+    invocation::check_env(&request.env)?;
+
+    let config_path = PathBuf::from(request.mode.config_filename());
+    let command = pioneer_command(
+        &pioneer_path,
+        request.mode,
+        &config_path,
+        command_wrapper.as_deref(),
+        request.resume,
+        &request.extra_args,
+        &request.env,
+    );
+    Ok(CommandPreview {
+        mode: request.mode,
+        command_line: describe_command(&command),
+        program: command.get_program().to_string_lossy().to_string(),
+        args: command
+            .get_args()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect(),
+        env: request.env.into_iter().collect(),
+        config_path: config_path.to_string_lossy().to_string(),
+        config: serde_json::to_string_pretty(&request.config).map_err(|e| e.to_string())?,
+    })
+}
+
+/// Runs a BuildSpecLib and then, if it succeeds, a SearchDIA against the
+/// library it wrote (`paths.library` in the search config is replaced).
+/// Returns once the build has started; `pioneer-pipeline-progress` spans both
+/// phases and `pioneer-pipeline-complete` reports the outcome. Cancelling the
+/// build's run aborts the pipeline.
+#[tauri::command]
+async fn run_pipeline(
+    window: Window,
+    app_handle: AppHandle,
+    mut build: RunRequest,
+    mut search: RunRequest,
+) -> Result<PipelineStartedPayload, String> {
+    if build.mode != RunMode::BuildSpecLib || search.mode != RunMode::SearchDia {
+        return Err("run_pipeline needs a BuildSpecLib request and a SearchDIA request".into());
+    }
+    if lookup(&build.config, "lib_name")
+        .and_then(Value::as_str)
+        .is_none()
+    {
+        return Err("The build config does not name an output library (`lib_name`)".into());
+    }
+    let pipeline_id = format!("pipeline_{}", runs::unix_now());
+    let (build_tx, build_done) = mpsc::channel();
+    let (search_tx, search_done) = mpsc::channel();
+    build.pipeline = Some(PipelineLink {
+        pipeline_id: pipeline_id.clone(),
+        phase: RunMode::BuildSpecLib,
+        done: build_tx,
+    });
+    search.pipeline = Some(PipelineLink {
+        pipeline_id: pipeline_id.clone(),
+        phase: RunMode::SearchDia,
+        done: search_tx,
+    });
+    let build_config = build.config.clone();
+
+    let started = run_pioneer(window.clone(), app_handle.clone(), build).await?;
+    let payload = PipelineStartedPayload {
+        pipeline_id: pipeline_id.clone(),
+        build_run_id: started.run_id,
+        stages: pipeline::pipeline_stages(),
+    };
+
+    std::thread::spawn(move || {
+        let complete = |failed_phase: Option<RunMode>,
+                        library: Option<&Path>,
+                        search_run_id: Option<String>,
+                        message: Option<String>| {
+            let _ = events::emit(
+                &window,
+                events::PIPELINE_COMPLETE,
+                &PipelineCompletePayload {
+                    pipeline_id: pipeline_id.clone(),
+                    success: failed_phase.is_none(),
+                    failed_phase,
+                    library_path: library.map(|path| path.to_string_lossy().to_string()),
+                    search_run_id,
+                    message,
+                },
+            );
+        };
+        // `Err` when the run's thread ended without a completion event.
+        let failure =
+            |phase: RunMode, outcome: &Result<RunCompletePayload, mpsc::RecvError>| match outcome {
+                Ok(done) if done.success => None,
+                Ok(done) if done.cancelled => Some(format!("{} was cancelled", phase.label())),
+                Ok(done) => Some(
+                    done.message
+                        .clone()
+                        .unwrap_or_else(|| format!("{} failed", phase.label())),
+                ),
+                Err(_) => Some(format!(
+                    "{} ended without reporting an outcome",
+                    phase.label()
+                )),
+            };
+
+        let build_outcome = build_done.recv();
+        if let Some(message) = failure(RunMode::BuildSpecLib, &build_outcome) {
+            return complete(Some(RunMode::BuildSpecLib), None, None, Some(message));
+        }
+        let outputs = build_outcome.ok().and_then(|done| done.outputs);
+        let library = match pipeline::resolve_built_library(&build_config, outputs.as_ref()) {
+            Ok(library) => library,
+            Err(err) => return complete(Some(RunMode::BuildSpecLib), None, None, Some(err)),
+        };
+        let Some(paths) = search
+            .config
+            .get_mut("paths")
+            .and_then(Value::as_object_mut)
+        else {
+            return complete(
+                Some(RunMode::SearchDia),
+                Some(&library),
+                None,
+                Some("Search config is missing the `paths` section".into()),
+            );
+        };
+        paths.insert(
+            "library".into(),
+            Value::String(library.to_string_lossy().to_string()),
+        );
+
+        let search_run_id =
+            match tauri::async_runtime::block_on(run_pioneer(window.clone(), app_handle, search)) {
+                Ok(started) => started.run_id,
+                Err(err) => {
+                    return complete(Some(RunMode::SearchDia), Some(&library), None, Some(err))
+                }
+            };
+        let message = failure(RunMode::SearchDia, &search_done.recv());
+        complete(
+            message.is_some().then_some(RunMode::SearchDia),
+            Some(&library),
+            search_run_id,
+            message,
+        );
+    });
+
+    Ok(payload)
+}
+
+fn persist_config(
+    app_handle: &AppHandle,
+    mode: RunMode,
+    config: &Value,
+    binary_version: Option<&str>,
+) -> Result<Option<PathBuf>, String> {
+    let resolver = app_handle.path_resolver();
+    let Some(path) = config_storage_path(mode, &resolver) else {
+        return Ok(None);
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let pretty = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+    backups::back_up(&path, &pretty)?;
+    atomic::write_atomic(&path, pretty).map_err(|e| e.to_string())?;
+
+    let meta = PersistedMeta {
+        binary_version: binary_version.map(str::to_string),
+    };
+    let meta_pretty = serde_json::to_string_pretty(&meta).map_err(|e| e.to_string())?;
+    atomic::write_atomic(&persisted_meta_path(&path), meta_pretty).map_err(|e| e.to_string())?;
+    Ok(Some(path))
+}
+
+fn persisted_meta_path(config_path: &Path) -> PathBuf {
+    config_path.with_extension("meta.json")
+}
+
+fn read_persisted_meta(config_path: &Path) -> Option<PersistedMeta> {
+    let contents = fs::read_to_string(persisted_meta_path(config_path)).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// Compares the version recorded with a persisted config against the current
+/// binary. Configs saved before versions were recorded (or while the version
+/// could not be read) have nothing to compare against and produce no warning.
+fn config_version_warning(
+    mode: RunMode,
+    config_path: Option<&Path>,
+    current_version: Option<&str>,
+) -> Option<ConfigVersionWarning> {
+    let config_path = config_path.filter(|path| path.is_file())?;
+    let current_version = current_version?;
+    let persisted_version = read_persisted_meta(config_path)?.binary_version?;
+    if persisted_version == current_version {
+        return None;
+    }
+    Some(ConfigVersionWarning {
+        mode,
+        message: format!(
+            "The saved {} parameters were last used with Pioneer {persisted_version}, but Pioneer {current_version} is now installed. Review the parameters before running.",
+            mode.label()
+        ),
+        persisted_version,
+        current_version: current_version.to_string(),
+    })
+}
+fn run_process(
+    window: Window,
+    pioneer: PathBuf,
+    mode: RunMode,
+    config_path: PathBuf,
+    log_path: PathBuf,
+    options: RunOptions,
+) -> Result<(), String> {
+    if options.external_terminal {
+        let end_token = options
+            .auto_close_terminal
+            .then_some(options.end_token.as_str());
+        if let Err(err) = open_terminal_tail(&log_path, end_token) {
+            let _ = events::emit(
+                &window,
+                events::TERMINAL_WARNING,
+                &format!("Could not launch external terminal: {err}"),
+            );
+        }
+    }
+
+    let registry = window.try_state::<RunRegistry>();
+    let cancel_requested = || match (&registry, &options.run_id) {
+        (Some(registry), Some(run_id)) => registry.cancel_requested(run_id),
+        _ => false,
+    };
+    if cancel_requested() {
+        complete_run(&window, &options, cancelled_payload(mode, None, None));
+        return Ok(());
+    }
+
+    // Allow for filesystems with coarse modification times when deciding
+    // which files this run produced.
+    let started_at = SystemTime::now() - OUTPUT_MTIME_SLACK;
+    let mut command = pioneer_command(
+        &pioneer,
+        mode,
+        &config_path,
+        options.command_wrapper.as_deref(),
+        options.resume_stage.is_some(),
+        &options.extra_args,
+        &options.env,
+    );
 
-    let mut stage_index = 0usize;
-    let stages = mode.stage_sequence();
     let mut log_file = OpenOptions::new()
         .create(true)
         .append(true)
         .open(&log_path)
         .map_err(|e| e.to_string())?;
+    writeln!(
+        log_file,
+        "=== Pioneer GUI: command: {} ===",
+        describe_command(&command)
+    )
+    .ok();
+    let mut env_names: Vec<&String> = options.env.keys().collect();
+    env_names.sort();
+    for name in env_names {
+        let value = environment::redact_value(name, options.env[name].clone());
+        writeln!(log_file, "=== Pioneer GUI: env: {name}={value} ===").ok();
+    }
 
-    send_stage_update(&window, mode, stages, stage_index);
+    let (tx, rx) = mpsc::channel::<(&'static str, String)>();
 
-    while let Ok((stream, line)) = rx.recv() {
-        writeln!(log_file, "{stream}: {line}").ok();
-        let _ = window.emit(
-            "pioneer-log",
-            &LogPayload {
-                mode,
-                stream,
-                line: line.clone(),
-            },
-        );
+    let mut child = if options.combined_output {
+        // One OS pipe shared by both handles keeps Pioneer's own interleaving;
+        // the command must be dropped after spawning so the reader sees EOF.
+        let (reader, writer) = std::io::pipe().map_err(|e| e.to_string())?;
+        let stderr_writer = writer.try_clone().map_err(|e| e.to_string())?;
+        command.stdout(writer).stderr(stderr_writer);
+        let child = command.spawn().map_err(|e| e.to_string())?;
+        drop(command);
+        spawn_reader(reader, tx.clone(), "combined", options.max_line_bytes);
+        child
+    } else {
+        command.stdout(Stdio::piped()).stderr(Stdio::piped());
+        let mut child = command.spawn().map_err(|e| e.to_string())?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| "Missing stdout pipe".to_string())?;
+        let stderr = child
+            .stderr
+            .take()
+            .ok_or_else(|| "Missing stderr pipe".to_string())?;
+        spawn_reader(stdout, tx.clone(), "stdout", options.max_line_bytes);
+        spawn_reader(stderr, tx.clone(), "stderr", options.max_line_bytes);
+        child
+    };
+    let progress_tail_stop = options.progress_file.as_ref().map(|path| {
+        let stop = Arc::new(AtomicBool::new(false));
+        progressfile::spawn_progress_tail(path.clone(), tx.clone(), Arc::clone(&stop));
+        stop
+    });
+    drop(tx);
+
+    let mut stage_index = options.resume_stage.unwrap_or(0);
+    let stages = mode.stage_sequence();
+
+    let mut stream_logs = match &options.stream_logs {
+        Some((stdout, stderr)) => Some((
+            OpenOptions::new()
+                .append(true)
+                .open(stdout)
+                .map_err(|e| e.to_string())?,
+            OpenOptions::new()
+                .append(true)
+                .open(stderr)
+                .map_err(|e| e.to_string())?,
+        )),
+        None => None,
+    };
+    let weights = &options.stage_weights;
+    if let (Some(registry), Some(run_id)) = (&registry, &options.run_id) {
+        registry.register(run_id, mode, child.id());
+    }
 
-        if let Some(next_index) = match_stage(&line, stage_index, stages) {
-            if next_index > stage_index {
-                stage_index = next_index;
-                send_stage_update(&window, mode, stages, stage_index);
+    send_stage_update(&window, mode, stages, &options, stage_index);
+    let mut timeline = StageTimeline::start(&stages[stage_index]);
+
+    let predicted_count = estimate::predicted_count_regex();
+    let progress_hints = ProgressHints::new();
+    // How far into the current stage's band the bar has been moved, so hints
+    // never move it backwards.
+    let mut stage_fraction = 0.0_f32;
+    let mut stage_matcher = StageMatcher::new(options.stage_matching.clone(), stages);
+    let mut stderr_tail = VecDeque::with_capacity(STDERR_TAIL_LINES);
+    let mut cancelled = false;
+    let deadline = options.timeout.map(|timeout| Instant::now() + timeout);
+    let mut timed_out = false;
+    let mut log_health = LogHealth::default();
+    loop {
+        // Checked before the first read too, so a cancel that landed between
+        // spawning and registering still kills the process.
+        if !cancelled && cancel_requested() {
+            cancelled = true;
+            registry::kill_run(&mut child);
+        }
+        // Killing only closes the pipes once the process is gone, so output
+        // keeps being logged until then.
+        if !cancelled && !timed_out && deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            timed_out = true;
+            writeln!(
+                log_file,
+                "=== Pioneer GUI: time limit of {}s reached, stopping Pioneer ===",
+                options.timeout.unwrap_or_default().as_secs()
+            )
+            .ok();
+            registry::kill_run(&mut child);
+        }
+        let (stream, line) = match rx.recv_timeout(CANCEL_POLL_INTERVAL) {
+            Ok(message) => message,
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                // The progress tail holds a sender too, so the channel only
+                // disconnects once it is told the process has exited.
+                if let Some(stop) = &progress_tail_stop {
+                    if matches!(child.try_wait(), Ok(Some(_))) {
+                        stop.store(true, Ordering::SeqCst);
+                    }
+                }
+                continue;
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        };
+        let line = if stream == PROGRESS_STREAM {
+            progressfile::progress_text(&line)
+        } else {
+            writeln!(log_file, "{stream}: {line}").ok();
+            if let Some((stdout_log, stderr_log)) = &mut stream_logs {
+                let target = if stream == "stdout" {
+                    stdout_log
+                } else {
+                    stderr_log
+                };
+                writeln!(target, "{line}").ok();
+            }
+            if let (Some(registry), Some(run_id)) = (&registry, &options.run_id) {
+                registry.record_line(run_id, &line);
+            }
+            if stream != "stdout" {
+                if stderr_tail.len() == STDERR_TAIL_LINES {
+                    stderr_tail.pop_front();
+                }
+                stderr_tail.push_back(line.clone());
+            }
+            let severity = options.classifier.classify(&line);
+            let _ = events::emit(
+                &window,
+                events::LOG,
+                &LogPayload {
+                    mode,
+                    stream,
+                    line: line.clone(),
+                    severity,
+                },
+            );
+            if let Some(severity) = severity {
+                log_health.record(severity, &line);
+                let _ = events::emit(
+                    &window,
+                    events::LOG_LEVEL,
+                    &LogLevelPayload {
+                        mode,
+                        run_id: options.run_id.clone(),
+                        stream,
+                        severity,
+                        line: line.clone(),
+                        error_count: log_health.errors,
+                        warning_count: log_health.warnings,
+                    },
+                );
             }
+            line
+        };
+        if !options.progress_source.drives_stages(stream) {
+            continue;
+        }
+
+        let matched = stage_matcher.observe(&line, stage_index, stages);
+        if let Some(next_index) = matched {
+            stage_index = next_index;
+            stage_fraction = 0.0;
+            let (left, elapsed) = timeline.enter(&stages[stage_index]);
+            let _ = events::emit(
+                &window,
+                events::STAGE_TIMING,
+                &StageTimingPayload {
+                    mode,
+                    run_id: options.run_id.clone(),
+                    stage_key: left.to_string(),
+                    elapsed_ms: elapsed.as_millis() as u64,
+                    next_stage_key: stages[stage_index].key.to_string(),
+                },
+            );
+            send_stage_update(&window, mode, stages, &options, stage_index);
+        }
+        if options.stage_debug {
+            let _ = events::emit(
+                &window,
+                events::STAGE_DEBUG,
+                &StageDebugPayload {
+                    mode,
+                    line: line.clone(),
+                    matched_stage: matched.map(|idx| stages[idx].key.to_string()),
+                    stage_index,
+                    stage_key: stages[stage_index].key.to_string(),
+                },
+            );
+        }
+
+        // The predict stage dominates a build, so when the precursor total is
+        // known, interpolate within its band instead of sitting on one value.
+        // Otherwise explicit percentages and x/y counts in the output do the
+        // same for whichever stage is running.
+        let precursor_fraction = match (options.expected_precursors, stages[stage_index].key) {
+            (Some(total), "predict") => estimate::parse_predicted_count(&line, &predicted_count)
+                .filter(|count| total > 0 && *count <= total)
+                .map(|count| count as f32 / total as f32),
+            _ => None,
+        };
+        let fraction = precursor_fraction.or_else(|| progress_hints.fraction(&line));
+        if let Some(fraction) = fraction.filter(|fraction| *fraction > stage_fraction) {
+            stage_fraction = fraction.min(1.0);
+            let start = weights.progress_at(stage_index);
+            let end = weights.progress_at((stage_index + 1).min(stages.len() - 1));
+            emit_progress(
+                &window,
+                mode,
+                &stages[stage_index],
+                (start + (end - start) * stage_fraction).clamp(0.0, 100.0),
+                &options,
+            );
         }
     }
 
-    let status = child.wait().map_err(|e| e.to_string())?;
-    if status.success() {
+    // The loop only ends once every reader has hit EOF, killed or not, so
+    // the log holds everything the process wrote.
+    let _ = log_file.flush();
+    let status = child.wait();
+    if let (Some(registry), Some(run_id)) = (&registry, &options.run_id) {
+        registry.unregister(run_id);
+    }
+    let status = status.map_err(|e| e.to_string())?;
+    let finished = Instant::now();
+    let summary = timeline.summary(finished);
+    let _ = events::emit(
+        &window,
+        events::STAGE_SUMMARY,
+        &StageSummaryPayload {
+            mode,
+            run_id: options.run_id.clone(),
+            total_ms: summary.iter().map(|stage| stage.elapsed_ms).sum(),
+            stages: summary,
+        },
+    );
+    if cancelled {
+        complete_run(
+            &window,
+            &options,
+            cancelled_payload(mode, status.code(), Some(log_health)),
+        );
+    } else if timed_out && !status.success() {
+        let message = format!(
+            "Pioneer was stopped after exceeding the {}s time limit",
+            options.timeout.unwrap_or_default().as_secs()
+        );
+        complete_run(
+            &window,
+            &options,
+            RunCompletePayload {
+                mode,
+                success: false,
+                exit_code: status.code(),
+                message: Some(message.clone()),
+                failure_category: Some(FailureCategory::TimedOut),
+                outputs: None,
+                cancelled: false,
+                error_count: log_health.errors,
+                warning_count: log_health.warnings,
+                log_health: Some(log_health),
+            },
+        );
+        return Err(message);
+    } else if status.success() {
+        // A resumed run only timed its later stages, which would skew the
+        // learned stage weights.
+        if let (Some(dir), None) = (&options.run_dir, options.resume_stage) {
+            if let Err(err) = runs::record_stage_durations(dir, timeline.durations(finished)) {
+                eprintln!("Failed to record stage durations: {err}");
+            }
+            if let Some(cache) = window.try_state::<StageWeightCache>() {
+                cache.invalidate(mode);
+            }
+        }
         stage_index = stages.len() - 1;
-        send_stage_update(&window, mode, stages, stage_index);
-        let _ = window.emit(
-            "pioneer-run-complete",
-            &RunCompletePayload {
+        send_stage_update(&window, mode, stages, &options, stage_index);
+        complete_run(
+            &window,
+            &options,
+            RunCompletePayload {
                 mode,
                 success: true,
                 exit_code: status.code(),
                 message: None,
+                failure_category: None,
+                outputs: Some(outputs::collect_outputs(&options.output_dirs, started_at)),
+                cancelled: false,
+                error_count: log_health.errors,
+                warning_count: log_health.warnings,
+                log_health: Some(log_health),
             },
         );
     } else {
-        let message = format!(
-            "Pioneer exited with status {:?}",
-            status.code().or(Some(-1))
+        let message = describe_exit(&status);
+        let category = classify_failure(
+            stderr_tail.make_contiguous(),
+            status.code(),
+            exit_signal(&status),
         );
-        let _ = window.emit(
-            "pioneer-run-complete",
-            &RunCompletePayload {
+        complete_run(
+            &window,
+            &options,
+            RunCompletePayload {
                 mode,
                 success: false,
                 exit_code: status.code(),
                 message: Some(message.clone()),
+                failure_category: Some(category),
+                outputs: None,
+                cancelled: false,
+                error_count: log_health.errors,
+                warning_count: log_health.warnings,
+                log_health: Some(log_health),
             },
         );
         return Err(message);
@@ -521,14 +3230,157 @@ fn run_process(
     Ok(())
 }
 
+fn cancelled_payload(
+    mode: RunMode,
+    exit_code: Option<i32>,
+    log_health: Option<LogHealth>,
+) -> RunCompletePayload {
+    RunCompletePayload {
+        mode,
+        success: false,
+        exit_code,
+        message: Some("Cancelled by user".into()),
+        failure_category: None,
+        outputs: None,
+        cancelled: true,
+        error_count: log_health.as_ref().map_or(0, |health| health.errors),
+        warning_count: log_health.as_ref().map_or(0, |health| health.warnings),
+        log_health,
+    }
+}
+
+fn complete_run(window: &Window, options: &RunOptions, payload: RunCompletePayload) {
+    if let Some(dir) = &options.run_dir {
+        if let Err(err) = runs::finish_run(dir, payload.success, payload.exit_code) {
+            eprintln!("Failed to record run outcome: {err}");
+        }
+    }
+    if let Some(cache) = window.try_state::<CompletionCache>() {
+        cache.record(options.run_id.as_deref(), &payload);
+    }
+    if let Some(pending) = &options.history {
+        history::record_completion(pending, &payload);
+    }
+    let _ = events::emit(window, events::RUN_COMPLETE, &payload);
+    if let Some(link) = &options.pipeline {
+        let _ = link.done.send(payload.clone());
+    }
+    report_batch_progress(window, options, &payload);
+    if !options.end_marked.swap(true, Ordering::SeqCst) {
+        let summary = if payload.cancelled {
+            "run cancelled".to_string()
+        } else if payload.success {
+            "run finished".to_string()
+        } else {
+            format!(
+                "run failed: {}",
+                payload.message.as_deref().unwrap_or("unknown error")
+            )
+        };
+        if let Err(err) =
+            terminal::write_end_marker(&options.log_path, &options.end_token, &summary)
+        {
+            eprintln!("Failed to write the end-of-run marker: {err}");
+        }
+    }
+    // Last, and on its own thread, so the hook sees the run fully recorded
+    // and cannot hold anything up.
+    if let Some(hook) = &options.post_run {
+        let outcome = RunOutcome {
+            mode: payload.mode,
+            run_id: options.run_id.clone(),
+            run_dir: options.run_dir.clone(),
+            output_dir: options.output_dirs.first().cloned(),
+            success: payload.success,
+            cancelled: payload.cancelled,
+            exit_code: payload.exit_code,
+        };
+        if hooks::should_run(hook, &outcome) {
+            hooks::spawn_post_hook(window, hook, outcome);
+        }
+    }
+}
+
+/// Counts the run towards its batch and updates the taskbar to match.
+fn report_batch_progress(window: &Window, options: &RunOptions, payload: &RunCompletePayload) {
+    if let (Some(batch_id), Some(tracker)) = (&options.batch_id, window.try_state::<BatchTracker>())
+    {
+        if let Some(batch) = tracker.finish(batch_id, &options.run_key, payload.success) {
+            let _ = events::emit(window, events::BATCH_PROGRESS, &batch);
+            if !batch.done {
+                taskbar::report_progress(window, payload.mode, batch.progress);
+                return;
+            }
+        }
+    }
+    taskbar::clear(window, payload.mode);
+}
+
+/// Pioneer's command line for one run, shared by `run_process` and
+/// `preview_command` so a preview shows exactly what would be launched.
+fn pioneer_command(
+    pioneer: &Path,
+    mode: RunMode,
+    config_path: &Path,
+    wrapper: Option<&[String]>,
+    resume: bool,
+    extra_args: &[String],
+    env: &HashMap<String, String>,
+) -> StdCommand {
+    let mut command = match wrapper {
+        Some(wrapper) => {
+            let mut command = StdCommand::new(&wrapper[0]);
+            command.args(&wrapper[1..]).arg(pioneer);
+            command
+        }
+        None => StdCommand::new(pioneer),
+    };
+    command.arg(mode.subcommand());
+    if resume {
+        command.arg(resume::RESUME_FLAG);
+    }
+    command.arg(config_path);
+    command.args(extra_args);
+    command.envs(env);
+    // Its own process group, so pause, cancel and the timeout can signal
+    // everything under a wrapper, not just the wrapper.
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    command
+}
+
+/// The command line as a user would type it, quoting arguments with spaces.
+fn describe_command(command: &StdCommand) -> String {
+    std::iter::once(command.get_program())
+        .chain(command.get_args())
+        .map(|part| {
+            let part = part.to_string_lossy();
+            if part.contains(char::is_whitespace) {
+                format!("\"{part}\"")
+            } else {
+                part.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 fn spawn_reader<R: std::io::Read + Send + 'static>(
     reader: R,
     tx: mpsc::Sender<(&'static str, String)>,
     label: &'static str,
+    max_line_bytes: usize,
 ) {
     std::thread::spawn(move || {
-        let buf_reader = BufReader::new(reader);
-        for line in buf_reader.lines().flatten() {
+        let mut buf_reader = BufReader::new(reader);
+        while let Ok(Some((mut line, dropped))) = read_capped_line(&mut buf_reader, max_line_bytes)
+        {
+            if dropped > 0 {
+                line.push_str(&format!(" … [truncated {dropped} bytes]"));
+            }
             if tx.send((label, line)).is_err() {
                 break;
             }
@@ -536,34 +3388,101 @@ fn spawn_reader<R: std::io::Read + Send + 'static>(
     });
 }
 
-fn match_stage(line: &str, current_index: usize, stages: &[StageInfo]) -> Option<usize> {
-    let normalized = line.to_lowercase();
-    for (idx, stage) in stages.iter().enumerate().skip(current_index + 1) {
-        if stage
-            .keywords
-            .iter()
-            .any(|keyword| keyword.is_empty() || normalized.contains(keyword))
-        {
-            return Some(idx);
+/// Reads one line, keeping at most `max_bytes` of it and discarding the rest
+/// without buffering it. Returns the line and the number of bytes dropped, or
+/// `None` at end of input. Invalid UTF-8 is replaced rather than ending the
+/// stream.
+fn read_capped_line<R: BufRead>(
+    reader: &mut R,
+    max_bytes: usize,
+) -> std::io::Result<Option<(String, usize)>> {
+    let mut line = Vec::new();
+    let mut dropped = 0usize;
+    let mut read_any = false;
+    loop {
+        let available = reader.fill_buf()?;
+        if available.is_empty() {
+            break;
+        }
+        read_any = true;
+        let newline = available.iter().position(|byte| *byte == b'\n');
+        let mut chunk = &available[..newline.unwrap_or(available.len())];
+        if newline.is_some() {
+            chunk = chunk.strip_suffix(b"\r").unwrap_or(chunk);
         }
+        let keep = chunk.len().min(max_bytes.saturating_sub(line.len()));
+        line.extend_from_slice(&chunk[..keep]);
+        dropped += chunk.len() - keep;
+        let consumed = newline.map(|idx| idx + 1).unwrap_or(available.len());
+        reader.consume(consumed);
+        if newline.is_some() {
+            break;
+        }
+    }
+    if !read_any {
+        return Ok(None);
+    }
+    // A `\r` split from its `\n` by a buffer boundary ends up kept here.
+    if line.last() == Some(&b'\r') {
+        line.pop();
     }
-    None
+    Ok(Some((String::from_utf8_lossy(&line).into_owned(), dropped)))
 }
 
-fn send_stage_update(window: &Window, mode: RunMode, stages: &[StageInfo], index: usize) {
-    let stage = &stages[index];
-    let progress = if stages.len() <= 1 {
-        100.0
-    } else {
-        (index as f32 / (stages.len() - 1) as f32) * 100.0
-    };
-    let _ = window.emit(
-        "pioneer-progress",
+fn send_stage_update(
+    window: &Window,
+    mode: RunMode,
+    stages: &[StageInfo],
+    options: &RunOptions,
+    index: usize,
+) {
+    let progress = options.stage_weights.progress_at(index);
+    emit_progress(window, mode, &stages[index], progress, options);
+}
+
+fn emit_progress(
+    window: &Window,
+    mode: RunMode,
+    stage: &StageInfo,
+    progress: f32,
+    options: &RunOptions,
+) {
+    let _ = events::emit(
+        window,
+        events::PROGRESS,
         &ProgressPayload {
             mode,
             stage_key: stage.key.to_string(),
             stage_label: stage.label.to_string(),
             progress,
+            eta_seconds: options.eta.eta_seconds(progress),
+        },
+    );
+    let mut overall = progress;
+    if let (Some(batch_id), Some(tracker)) = (&options.batch_id, window.try_state::<BatchTracker>())
+    {
+        if let Some(payload) = tracker.update(batch_id, &options.run_key, progress) {
+            let _ = events::emit(window, events::BATCH_PROGRESS, &payload);
+            overall = payload.progress;
+        }
+    }
+    if let Some(link) = &options.pipeline {
+        let payload = link.progress_payload(stage, progress);
+        overall = payload.progress;
+        let _ = events::emit(window, events::PIPELINE_PROGRESS, &payload);
+    }
+    taskbar::report_progress(window, mode, overall);
+}
+
+fn send_prepare_update(window: &Window, mode: RunMode, step: &str, counts: Option<(usize, usize)>) {
+    let _ = events::emit(
+        window,
+        events::PREPARE_PROGRESS,
+        &PreparePayload {
+            mode,
+            step: step.to_string(),
+            current: counts.map(|(current, _)| current),
+            total: counts.map(|(_, total)| total),
         },
     );
 }
@@ -581,14 +3500,24 @@ impl FileCreator {
     }
 }
 
-fn open_terminal_tail(log_path: &Path) -> Result<(), String> {
+/// Opens a terminal following `log_path`. With `end_token`, the tail exits
+/// (and the window can close) once the run's end marker is written;
+/// otherwise it follows the log until the user closes it.
+fn open_terminal_tail(log_path: &Path, end_token: Option<&str>) -> Result<(), String> {
     #[cfg(target_os = "windows")]
     {
         use std::process::Command;
-        let command = format!(
-            "Start-Process powershell -ArgumentList '-NoExit','-Command','Get-Content -Path \"{}\" -Wait'",
-            log_path.display()
-        );
+        let command = match end_token {
+            Some(token) => format!(
+                "Start-Process powershell -ArgumentList '-Command','Get-Content -Path \"{}\" -Wait | ForEach-Object {{ $_; if ($_.Contains(\"{}\")) {{ exit }} }}'",
+                log_path.display(),
+                token
+            ),
+            None => format!(
+                "Start-Process powershell -ArgumentList '-NoExit','-Command','Get-Content -Path \"{}\" -Wait'",
+                log_path.display()
+            ),
+        };
         Command::new("powershell")
             .args(["-NoProfile", "-Command", &command])
             .spawn()
@@ -599,11 +3528,20 @@ fn open_terminal_tail(log_path: &Path) -> Result<(), String> {
     #[cfg(target_os = "macos")]
     {
         use std::process::Command;
-        let escaped = log_path.display().to_string().replace('"', "\\\"");
-        let script = format!(
-            "tell application \"Terminal\" to do script \"tail -n +1 -f {}\"",
-            escaped
-        );
+        let shell_command = match end_token {
+            Some(token) => {
+                let script = terminal::write_tail_script(log_path).map_err(|e| e.to_string())?;
+                format!(
+                    "bash {} {} {}; exit",
+                    terminal::shell_quote(&script.to_string_lossy()),
+                    terminal::shell_quote(&log_path.to_string_lossy()),
+                    terminal::shell_quote(token)
+                )
+            }
+            None => format!("tail -n +1 -f {}", log_path.display()),
+        };
+        let escaped = shell_command.replace('\\', "\\\\").replace('"', "\\\"");
+        let script = format!("tell application \"Terminal\" to do script \"{}\"", escaped);
         Command::new("osascript")
             .args(["-e", &script])
             .spawn()
@@ -614,26 +3552,28 @@ fn open_terminal_tail(log_path: &Path) -> Result<(), String> {
     #[cfg(target_os = "linux")]
     {
         use std::process::Command;
-        let terminals = [
-            "x-terminal-emulator",
-            "gnome-terminal",
-            "konsole",
-            "xfce4-terminal",
-            "mate-terminal",
-            "xterm",
-        ];
-        let mut selected = None;
-        for term in terminals.iter() {
-            if which(term).is_ok() {
-                selected = Some(*term);
-                break;
-            }
-        }
-
-        let Some(term) = selected else {
+        let Some(term) = terminal::linux_terminal() else {
             return Err("No compatible terminal found".into());
         };
 
+        if let Some(token) = end_token {
+            let script = terminal::write_tail_script(log_path).map_err(|e| e.to_string())?;
+            let mut command = Command::new(term);
+            match term {
+                "gnome-terminal" | "mate-terminal" => command.arg("--"),
+                "xfce4-terminal" => command.arg("-x"),
+                _ => command.arg("-e"),
+            };
+            command
+                .arg("bash")
+                .arg(&script)
+                .arg(log_path)
+                .arg(token)
+                .spawn()
+                .map_err(|e| e.to_string())?;
+            return Ok(());
+        }
+
         let tail_command = format!(
             "tail -n +1 -f '{}' ; read -p \"Press Enter to close...\" _",
             log_path.display()
@@ -665,42 +3605,35 @@ fn open_terminal_tail(log_path: &Path) -> Result<(), String> {
     Err("Unsupported platform".into())
 }
 
+/// Name the persisted config and the profile directory for `mode` share.
+fn config_storage_stem(mode: RunMode) -> &'static str {
+    match mode {
+        RunMode::BuildSpecLib => "buildspeclib",
+        RunMode::SearchDia => "searchdia",
+    }
+}
+
 fn config_storage_path(mode: RunMode, resolver: &PathResolver) -> Option<PathBuf> {
     let mut path = resolver.app_config_dir()?;
-    let filename = match mode {
-        RunMode::BuildSpecLib => "buildspeclib.json",
-        RunMode::SearchDia => "searchdia.json",
-    };
-    path.push(filename);
+    path.push(format!("{}.json", config_storage_stem(mode)));
+    Some(path)
+}
+
+fn profiles_dir(mode: RunMode, resolver: &PathResolver) -> Option<PathBuf> {
+    let mut path = resolver.app_config_dir()?;
+    path.push(profiles::PROFILES_DIR);
+    path.push(config_storage_stem(mode));
     Some(path)
 }
 
 fn load_persisted_config(path: Option<&Path>, defaults: &Value) -> Option<Value> {
     let path = path?;
     let contents = fs::read_to_string(path).ok()?;
-    let persisted: Value = serde_json::from_str(&contents).ok()?;
-    Some(deep_merge(defaults, &persisted))
-}
-
-fn deep_merge(base: &Value, override_val: &Value) -> Value {
-    match (base, override_val) {
-        (Value::Object(base_map), Value::Object(override_map)) => {
-            let mut merged = base_map.clone();
-            for (key, value) in override_map.iter() {
-                let next = if let Some(existing) = merged.get(key) {
-                    deep_merge(existing, value)
-                } else {
-                    value.clone()
-                };
-                merged.insert(key.clone(), next);
-            }
-            Value::Object(merged)
-        }
-        _ => override_val.clone(),
-    }
+    let persisted: Value = serde_json::from_str(encoding::strip_bom(&contents)).ok()?;
+    Some(merge::merge_saved(defaults, &persisted))
 }
 
-fn env_pioneer_candidates() -> Vec<PathBuf> {
+fn env_pioneer_candidates() -> Vec<BinaryCandidate> {
     const ENV_VARS: &[&str] = &["PIONEER_BINARY", "PIONEER_PATH", "PIONEER_EXE", "PIONEER"];
 
     let mut results = Vec::new();
@@ -709,44 +3642,304 @@ fn env_pioneer_candidates() -> Vec<PathBuf> {
             if raw.is_empty() {
                 continue;
             }
+            let mut push = |path: PathBuf| {
+                results.push(BinaryCandidate {
+                    path,
+                    source: BinarySource::EnvVar {
+                        name: key.to_string(),
+                    },
+                })
+            };
             let path = PathBuf::from(&raw);
-            if path.is_file() {
-                results.push(path);
-            } else if path.is_dir() {
+            if path.is_dir() {
                 for candidate in ["pioneer", "Pioneer", "pioneer.exe", "Pioneer.exe"] {
-                    results.push(path.join(candidate));
+                    push(path.join(candidate));
                 }
             } else {
-                results.push(path);
+                push(path);
             }
         }
     }
     results
 }
 
+/// Raw `pioneer --version` output, bounded by `PROBE_TIMEOUT`.
+fn read_version_output(pioneer: &Path) -> Result<Output, ConfigLoadError> {
+    let mut command = StdCommand::new(pioneer);
+    command.arg("--version");
+    output_within(command, &FetchControl::probe())
+}
+
+/// The trimmed version string, if the command succeeded and printed one.
+fn version_from_output(output: &Output) -> Option<String> {
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !version.is_empty()).then_some(version)
+}
+
+fn read_pioneer_version(pioneer: &Path) -> Result<String, ConfigLoadError> {
+    let output = read_version_output(pioneer)?;
+    if !output.status.success() {
+        return Err(ConfigLoadError::NonZeroExit(output.status.code()));
+    }
+    version_from_output(&output)
+        .ok_or_else(|| ConfigLoadError::Other("Pioneer printed no version information".into()))
+}
+
+/// Library formats `pioneer` declares, through the session's capability
+/// cache so repeated checks run `--help` once.
+fn library_formats(app_handle: &AppHandle, pioneer: &Path) -> Option<FormatRange> {
+    let cache = app_handle.try_state::<CapabilityCache>();
+    if let Some(capabilities) = cache.as_ref().and_then(|cache| cache.get(pioneer)) {
+        return capabilities.library_formats;
+    }
+    let capabilities = capabilities::discover_capabilities(pioneer).ok()?;
+    if let Some(cache) = &cache {
+        cache.insert(pioneer, capabilities.clone());
+    }
+    capabilities.library_formats
+}
+
+fn current_library_formats(app_handle: &AppHandle) -> Option<FormatRange> {
+    library_formats(app_handle, &locate_pioneer_binary().ok()?)
+}
+
+fn current_binary_version() -> Option<String> {
+    let pioneer = locate_pioneer_binary().ok()?;
+    read_pioneer_version(&pioneer).ok()
+}
+
+/// `binary_path` when a request names one, otherwise the usual lookup.
+fn pioneer_binary_for(binary_path: Option<&str>) -> Result<PathBuf, String> {
+    match binary_path {
+        Some(path) => binary::check_override(Path::new(path)),
+        None => locate_pioneer_binary().map_err(|e| e.to_string()),
+    }
+}
+
 fn locate_pioneer_binary() -> Result<PathBuf, ConfigLoadError> {
-    for candidate in env_pioneer_candidates() {
-        if candidate.is_file() {
-            return Ok(candidate);
-        }
+    locate_pioneer_candidate().map(|candidate| candidate.path)
+}
+
+/// For the config load that starts a session, when an explicit path may sit
+/// on a network mount that is not up yet.
+fn locate_pioneer_binary_at_startup() -> Result<PathBuf, ConfigLoadError> {
+    find_pioneer_candidate(true).map(|candidate| candidate.path)
+}
+
+/// The binary together with where it was found.
+fn locate_pioneer_candidate() -> Result<BinaryCandidate, ConfigLoadError> {
+    find_pioneer_candidate(false)
+}
+
+/// With `retry`, explicit paths are re-checked per `lookup_retry` before
+/// falling back to PATH, which is never retried.
+fn find_pioneer_candidate(retry: bool) -> Result<BinaryCandidate, ConfigLoadError> {
+    let explicit: Vec<BinaryCandidate> = binary::configured_path()
+        .map(|path| BinaryCandidate {
+            path,
+            source: BinarySource::Configured,
+        })
+        .into_iter()
+        .chain(env_pioneer_candidates())
+        .collect();
+    if let Some(found) = binary::first_existing(&explicit, retry) {
+        return Ok(found);
     }
 
     const CANDIDATES: &[&str] = &["pioneer", "Pioneer", "pioneer.exe", "Pioneer.exe"];
     for candidate in CANDIDATES {
         if let Ok(path) = which(candidate) {
-            return Ok(path);
+            return Ok(BinaryCandidate {
+                path,
+                source: BinarySource::Path,
+            });
         }
     }
     Err(ConfigLoadError::MissingBinary)
 }
 fn main() {
     tauri::Builder::default()
+        .manage(DefaultsFetchState::default())
+        .manage(StageWeightCache::default())
+        .manage(RunRegistry::default())
+        .manage(EventBridge::default())
+        .manage(EventRecorder::default())
+        .manage(BatchTracker::default())
+        .manage(TaskbarTracker::default())
+        .manage(CompletionCache::default())
+        .manage(CapabilityCache::default())
+        .setup(|app| {
+            binary::load_configured_binary(&app.path_resolver());
+            if binary::warm_on_startup(&app.path_resolver()) {
+                std::thread::spawn(|| {
+                    if let Ok(pioneer) = locate_pioneer_binary() {
+                        if let Err(err) = binary::warm_binary(&pioneer) {
+                            eprintln!("Failed to warm {}: {err}", pioneer.display());
+                        }
+                    }
+                });
+            }
+            let bridge_settings = bridge::load_settings(&app.path_resolver());
+            if bridge_settings.enabled {
+                if let Err(err) = app.state::<EventBridge>().start(bridge_settings.port) {
+                    eprintln!("Failed to start event bridge: {err}");
+                }
+            }
+            let app_handle = app.handle();
+            std::thread::spawn(move || prune_runs_on_startup(&app_handle));
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             load_configs,
+            load_build_defaults,
+            load_search_defaults,
+            cancel_defaults_fetch,
+            fallback_drift,
+            diff_binary_defaults,
+            config_diff,
+            minimize_config,
+            preview_merged_config,
+            describe_events,
+            clean_workspace,
+            get_retention_policy,
+            terminal_available,
+            get_terminal_settings,
+            get_log_patterns,
+            set_log_patterns,
+            test_log_patterns,
+            set_terminal_settings,
+            get_output_subdirectories,
+            set_output_subdirectories,
+            get_disk_space_settings,
+            set_disk_space_settings,
+            set_retention_policy,
+            estimate_precursors,
+            hash_inputs,
+            validate_config,
+            validate_param_ranges,
+            validate_config_external,
+            sanity_check_statistics,
+            check_policy,
+            check_parameter_rules,
+            merge_configs,
+            check_output_locks,
+            clear_output_locks,
+            validate_build_inputs,
+            validate_search_inputs,
+            check_auxiliary_databases,
+            check_storage_speed,
+            check_library_version,
+            normalize_config_paths,
+            redact_config,
+            validate_pipeline,
+            check_range_consistency,
+            detect_filename_collisions,
+            check_staging_strategy,
+            scan_ms_data,
+            effective_parallelism,
+            check_memory_budget,
+            pause_run,
+            get_last_completion,
+            resume_run,
+            cancel_run,
+            start_batch,
+            get_run_status,
+            peek_active_log,
+            list_runs,
+            load_run_history,
+            clear_run_history,
+            label_run,
+            export_run_events,
+            get_run_environment,
+            copy_log,
+            export_reproducer,
+            stage_weights,
+            start_event_bridge,
+            stop_event_bridge,
+            event_bridge_status,
+            set_binary_path,
+            warm_binary,
+            list_models,
+            set_warm_on_startup,
+            get_command_wrapper,
+            check_binary,
+            discover_binary_capabilities,
+            get_pioneer_version,
+            get_binary_lookup_retry,
+            set_binary_lookup_retry,
+            set_command_wrapper,
+            install_binary,
+            check_output_writable,
+            preflight_run,
+            split_config,
+            assemble_config,
             read_config,
+            normalize_config_file,
             save_config,
-            run_pioneer
+            canonicalize_config,
+            canonicalize_persisted_config,
+            list_config_backups,
+            restore_config_backup,
+            reset_config_to_defaults,
+            save_profile,
+            list_profiles,
+            load_profile,
+            delete_profile,
+            run_pioneer,
+            preview_command,
+            run_pipeline
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{read_capped_line, DEFAULT_MAX_LINE_BYTES};
+    use std::io::{BufReader, Cursor};
+
+    fn read_all(input: &[u8], capacity: usize, max_bytes: usize) -> Vec<(String, usize)> {
+        let mut reader = BufReader::with_capacity(capacity, Cursor::new(input.to_vec()));
+        std::iter::from_fn(|| read_capped_line(&mut reader, max_bytes).expect("read")).collect()
+    }
+
+    #[test]
+    fn caps_long_lines_and_counts_what_was_dropped() {
+        let lines = read_all(b"short\n0123456789abcdef\nlast", 4, 8);
+        assert_eq!(
+            lines,
+            [
+                ("short".to_string(), 0),
+                ("01234567".to_string(), 8),
+                ("last".to_string(), 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn a_multi_megabyte_line_is_capped_without_losing_the_next() {
+        let long = 8 * 1024 * 1024;
+        let mut input = vec![b'x'; long];
+        input.extend_from_slice(b"\nnext\n");
+        let mut reader = BufReader::new(Cursor::new(input));
+        let (line, dropped) = read_capped_line(&mut reader, DEFAULT_MAX_LINE_BYTES)
+            .expect("read")
+            .expect("a line");
+        assert_eq!(line.len(), DEFAULT_MAX_LINE_BYTES);
+        assert_eq!(dropped, long - DEFAULT_MAX_LINE_BYTES);
+        let next = read_capped_line(&mut reader, DEFAULT_MAX_LINE_BYTES).expect("read");
+        assert_eq!(next, Some(("next".to_string(), 0)));
+    }
+
+    #[test]
+    fn strips_crlf_split_across_buffer_boundaries() {
+        let lines = read_all(b"abc\r\ndef\r\n", 4, 64);
+        assert_eq!(lines, [("abc".to_string(), 0), ("def".to_string(), 0)]);
+    }
+
+    #[test]
+    fn keeps_empty_lines_and_replaces_invalid_utf8() {
+        let lines = read_all(b"\n\xffok\n", 8, 64);
+        assert_eq!(lines, [(String::new(), 0), ("\u{fffd}ok".to_string(), 0)]);
+    }
+}
@@ -0,0 +1,174 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+
+use crate::diff::lookup;
+use crate::estimate::estimate_build_precursors;
+use crate::parallelism::effective_parallelism;
+use crate::workspace::disk_usage;
+use crate::RunMode;
+
+const GIB: u64 = 1024 * 1024 * 1024;
+/// Julia runtime plus Pioneer's compiled code, before any data is loaded.
+const BASE_BYTES: u64 = 2 * GIB;
+/// Working buffers each search thread holds while scoring a file.
+const SEARCH_BYTES_PER_THREAD: u64 = 3 * GIB / 2;
+const BUILD_BYTES_PER_THREAD: u64 = GIB / 2;
+/// The whole library is loaded, and indexed copies roughly add half again.
+const LIBRARY_EXPANSION: f64 = 1.5;
+/// Predicted fragments held per precursor before they are written out.
+const BUILD_BYTES_PER_PRECURSOR: u64 = 1024;
+/// Share of total RAM a run can use before the OS and other programs are
+/// likely to push it into swap or the OOM killer.
+const USABLE_FRACTION: f64 = 0.8;
+
+/// A rough peak-memory estimate for a run. The per-thread and per-precursor
+/// figures are ballpark values, not measurements of a particular Pioneer
+/// release, so the estimate can be off by a factor of two either way; it is
+/// meant to flag clearly unsafe thread counts, not to size a machine.
+#[derive(Serialize, Debug)]
+pub struct MemoryBudget {
+    pub mode: RunMode,
+    pub threads: usize,
+    /// No thread count was configured, so one thread per logical core was
+    /// assumed.
+    pub threads_assumed: bool,
+    /// Library size on disk (SearchDIA) or FASTA size (BuildSpecLib).
+    pub input_bytes: u64,
+    pub precursors: Option<u64>,
+    pub estimated_peak_bytes: u64,
+    pub total_memory_bytes: Option<u64>,
+    /// `USABLE_FRACTION` of total memory; estimates above it are flagged.
+    pub threshold_bytes: Option<u64>,
+    pub likely_to_exceed: bool,
+    /// The largest thread count whose estimate stays under the threshold.
+    pub suggested_threads: Option<usize>,
+    pub warnings: Vec<String>,
+}
+
+pub fn check_memory_budget(mode: RunMode, config: &Value) -> MemoryBudget {
+    let parallelism = effective_parallelism(mode, config);
+    let (threads, threads_assumed) = match parallelism.effective_threads {
+        Some(threads) => (threads.max(1), false),
+        None => (parallelism.logical_cores.unwrap_or(1), true),
+    };
+    let mut warnings = Vec::new();
+
+    let (input_bytes, precursors, data_bytes, per_thread) = match mode {
+        RunMode::SearchDia => {
+            let library = lookup(config, "paths.library")
+                .and_then(Value::as_str)
+                .map(|path| disk_usage(Path::new(path)))
+                .unwrap_or(0);
+            if library == 0 {
+                warnings.push("Library size unknown; the estimate excludes it".into());
+            }
+            let data = (library as f64 * LIBRARY_EXPANSION) as u64;
+            (library, None, data, SEARCH_BYTES_PER_THREAD)
+        }
+        RunMode::BuildSpecLib => {
+            let fasta: u64 = config
+                .get("fasta_paths")
+                .and_then(Value::as_array)
+                .map(|paths| {
+                    paths
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .map(|path| disk_usage(Path::new(path)))
+                        .sum()
+                })
+                .unwrap_or(0);
+            let precursors = match estimate_build_precursors(config) {
+                Ok(estimate) => Some(estimate.precursors),
+                Err(err) => {
+                    warnings.push(format!("Precursor count unknown ({err})"));
+                    None
+                }
+            };
+            let data = fasta + precursors.unwrap_or(0) * BUILD_BYTES_PER_PRECURSOR;
+            (fasta, precursors, data, BUILD_BYTES_PER_THREAD)
+        }
+    };
+    let estimate_for = |threads: usize| BASE_BYTES + data_bytes + per_thread * threads as u64;
+    let estimated_peak_bytes = estimate_for(threads);
+
+    let total_memory_bytes = total_memory();
+    let threshold_bytes = total_memory_bytes.map(|total| (total as f64 * USABLE_FRACTION) as u64);
+    let likely_to_exceed =
+        threshold_bytes.is_some_and(|threshold| estimated_peak_bytes > threshold);
+    let suggested_threads = threshold_bytes.and_then(|threshold| {
+        (1..=threads)
+            .rev()
+            .find(|&candidate| estimate_for(candidate) <= threshold)
+    });
+
+    match (threshold_bytes, likely_to_exceed) {
+        (None, _) => warnings.push("Could not determine total system memory".into()),
+        (Some(threshold), true) => warnings.push(match suggested_threads {
+            Some(suggested) => format!(
+                "{threads} threads need roughly {} but only about {} is usable; try {suggested} threads or fewer",
+                format_gib(estimated_peak_bytes),
+                format_gib(threshold)
+            ),
+            None => format!(
+                "This run needs roughly {} even on one thread, more than the {} usable on this machine",
+                format_gib(estimate_for(1)),
+                format_gib(threshold)
+            ),
+        }),
+        (Some(_), false) => {}
+    }
+
+    MemoryBudget {
+        mode,
+        threads,
+        threads_assumed,
+        input_bytes,
+        precursors,
+        estimated_peak_bytes,
+        total_memory_bytes,
+        threshold_bytes,
+        likely_to_exceed,
+        suggested_threads,
+        warnings,
+    }
+}
+
+fn format_gib(bytes: u64) -> String {
+    format!("{:.1} GiB", bytes as f64 / GIB as f64)
+}
+
+#[cfg(target_os = "linux")]
+pub fn total_memory() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let line = meminfo.lines().find(|line| line.starts_with("MemTotal:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+#[cfg(target_os = "macos")]
+pub fn total_memory() -> Option<u64> {
+    let output = std::process::Command::new("sysctl")
+        .args(["-n", "hw.memsize"])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+#[cfg(windows)]
+pub fn total_memory() -> Option<u64> {
+    let output = std::process::Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-Command",
+            "(Get-CimInstance Win32_ComputerSystem).TotalPhysicalMemory",
+        ])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+pub fn total_memory() -> Option<u64> {
+    None
+}
@@ -0,0 +1,290 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::diff::join_path;
+
+/// Per-round or per-rank settings, where a newer Pioneer adding an entry
+/// should reach configs saved before it. Every other array is the user's own
+/// list (FASTA files, modifications, regexes) and is kept as saved.
+const PER_POSITION_PATHS: &[&str] = &[
+    "library_params.rank_to_score",
+    "global.isotope_settings.err_bounds_first_pass",
+    "global.isotope_settings.err_bounds_quant_search",
+    "parameter_tuning.fragment_settings.min_score",
+    "parameter_tuning.fragment_settings.min_top_n",
+    "first_search.fragment_settings.min_top_n",
+    "quant_search.fragment_settings.min_top_n",
+];
+
+/// How `merge_with` combines two arrays at the same key. Numbers, strings, and
+/// mismatched types are always replaced by the later layer.
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ArrayMerge {
+    /// The later layer's array wins outright.
+    #[default]
+    Replace,
+    /// The later layer's items are appended to the earlier ones.
+    Concat,
+    /// Like `Concat`, but items already present are not repeated.
+    Union,
+    /// Items are merged position by position (objects key by key, anything
+    /// else replaced), and items past the end of the shorter array are kept
+    /// from whichever layer has them. A persisted `[a]` over a default that
+    /// grew to `[a, b]` therefore keeps `b` instead of truncating it; the
+    /// flip side is that a later layer cannot drop items by being shorter.
+    MergeByIndex,
+}
+
+/// The array rule for each dotted config path, falling back to `default`.
+/// Array items share their array's path, so a rule for `variable_mods.name`
+/// also covers arrays nested inside that array.
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ArrayPolicy {
+    pub default: ArrayMerge,
+    pub by_path: BTreeMap<String, ArrayMerge>,
+}
+
+impl ArrayPolicy {
+    /// How a saved config is laid over the current defaults on load.
+    pub fn persisted_config() -> Self {
+        ArrayPolicy {
+            default: ArrayMerge::Replace,
+            by_path: PER_POSITION_PATHS
+                .iter()
+                .map(|path| (path.to_string(), ArrayMerge::MergeByIndex))
+                .collect(),
+        }
+    }
+
+    fn at(&self, path: &str) -> ArrayMerge {
+        self.by_path.get(path).copied().unwrap_or(self.default)
+    }
+}
+
+/// Objects are merged key by key; arrays follow `policy`, and anything else
+/// in `override_val` replaces what is in `base`.
+pub fn merge_with_policy(base: &Value, override_val: &Value, policy: &ArrayPolicy) -> Value {
+    merge_at(base, override_val, "", policy)
+}
+
+/// A saved config (persisted, restored from a backup, or a profile) laid
+/// over the current defaults.
+pub fn merge_saved(defaults: &Value, saved: &Value) -> Value {
+    merge_with_policy(defaults, saved, &ArrayPolicy::persisted_config())
+}
+
+fn merge_at(base: &Value, override_val: &Value, path: &str, policy: &ArrayPolicy) -> Value {
+    match (base, override_val) {
+        (Value::Object(base_map), Value::Object(override_map)) => {
+            let mut merged = base_map.clone();
+            for (key, value) in override_map.iter() {
+                let next = if let Some(existing) = merged.get(key) {
+                    merge_at(existing, value, &join_path(path, key), policy)
+                } else {
+                    value.clone()
+                };
+                merged.insert(key.clone(), next);
+            }
+            Value::Object(merged)
+        }
+        (Value::Array(base_items), Value::Array(override_items)) => match policy.at(path) {
+            ArrayMerge::Replace => override_val.clone(),
+            ArrayMerge::MergeByIndex => {
+                let mut merged: Vec<Value> = base_items
+                    .iter()
+                    .zip(override_items)
+                    .map(|(base, over)| merge_at(base, over, path, policy))
+                    .collect();
+                let shorter = merged.len();
+                merged.extend(base_items.iter().skip(shorter).cloned());
+                merged.extend(override_items.iter().skip(shorter).cloned());
+                Value::Array(merged)
+            }
+            arrays => {
+                let mut merged = base_items.clone();
+                for item in override_items {
+                    if arrays == ArrayMerge::Concat || !merged.contains(item) {
+                        merged.push(item.clone());
+                    }
+                }
+                Value::Array(merged)
+            }
+        },
+        _ => override_val.clone(),
+    }
+}
+
+/// Folds `layers` from first to last, so later layers win (lab defaults, then
+/// project overrides, then the user's own tweaks). Layers that are not
+/// objects are rejected rather than silently replacing everything before them.
+pub fn merge_configs(layers: &[Value], arrays: &ArrayPolicy) -> Result<Value, String> {
+    if let Some(index) = layers.iter().position(|layer| !layer.is_object()) {
+        return Err(format!("Config layer {} is not a JSON object", index + 1));
+    }
+    Ok(layers
+        .iter()
+        .fold(Value::Object(Default::default()), |merged, layer| {
+            merge_with_policy(&merged, layer, arrays)
+        }))
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum LeafSource {
+    /// Taken from the defaults; the persisted config does not set it.
+    Default,
+    /// Set by the persisted config, replacing the default.
+    Overridden,
+    /// Only in the persisted config: the defaults have no such key, which
+    /// usually means it was renamed or removed in this Pioneer version.
+    PersistedOnly,
+}
+
+#[derive(Serialize, Debug)]
+pub struct LeafOrigin {
+    pub path: String,
+    pub source: LeafSource,
+    /// For `Overridden` leaves, whether the persisted value equals the
+    /// default anyway.
+    pub same_as_default: bool,
+}
+
+#[derive(Serialize, Debug)]
+pub struct MergePreview {
+    pub merged: Value,
+    /// Every leaf of `merged` (arrays count as leaves), in merge order.
+    pub leaves: Vec<LeafOrigin>,
+}
+
+/// What `merge_saved(defaults, persisted)` produces, and where each leaf of
+/// the result came from.
+pub fn preview_merge(defaults: &Value, persisted: &Value) -> MergePreview {
+    let mut leaves = Vec::new();
+    annotate(Some(defaults), Some(persisted), "", &mut leaves);
+    MergePreview {
+        merged: merge_saved(defaults, persisted),
+        leaves,
+    }
+}
+
+fn annotate(base: Option<&Value>, over: Option<&Value>, path: &str, out: &mut Vec<LeafOrigin>) {
+    match (base, over) {
+        (Some(Value::Object(base_map)), Some(Value::Object(over_map))) => {
+            for (key, value) in base_map {
+                annotate(Some(value), over_map.get(key), &join_path(path, key), out);
+            }
+            for (key, value) in over_map {
+                if !base_map.contains_key(key) {
+                    annotate(None, Some(value), &join_path(path, key), out);
+                }
+            }
+        }
+        (Some(Value::Object(map)), None) | (None, Some(Value::Object(map))) if !map.is_empty() => {
+            for (key, value) in map {
+                let child = Some(value);
+                let (base, over) = if base.is_some() {
+                    (child, None)
+                } else {
+                    (None, child)
+                };
+                annotate(base, over, &join_path(path, key), out);
+            }
+        }
+        (base, over) => {
+            let source = match (base, over) {
+                (_, None) => LeafSource::Default,
+                (Some(_), Some(_)) => LeafSource::Overridden,
+                (None, Some(_)) => LeafSource::PersistedOnly,
+            };
+            out.push(LeafOrigin {
+                path: path.to_string(),
+                source,
+                same_as_default: source == LeafSource::Overridden && base == over,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{merge_configs, merge_saved, preview_merge, ArrayMerge, ArrayPolicy, LeafSource};
+    use serde_json::json;
+
+    #[test]
+    fn persisted_config_inherits_new_default_entries() {
+        let defaults = json!({
+            "fasta_paths": ["/path/to/fasta/file.fasta"],
+            "parameter_tuning": {"fragment_settings": {"min_score": [22, 17, 15]}}
+        });
+        let persisted = json!({
+            "fasta_paths": ["/data/human.fasta"],
+            "parameter_tuning": {"fragment_settings": {"min_score": [20, 17]}}
+        });
+        assert_eq!(
+            merge_saved(&defaults, &persisted),
+            json!({
+                "fasta_paths": ["/data/human.fasta"],
+                "parameter_tuning": {"fragment_settings": {"min_score": [20, 17, 15]}}
+            })
+        );
+    }
+
+    #[test]
+    fn later_layers_win_key_by_key() {
+        let layers = [
+            json!({"a": 1, "b": {"c": 1, "d": 1}, "list": [1, 2]}),
+            json!({"b": {"c": 2}, "list": [3]}),
+            json!({"b": {"d": 3}, "e": true}),
+        ];
+        assert_eq!(
+            merge_configs(&layers, &ArrayPolicy::default()).expect("merge"),
+            json!({"a": 1, "b": {"c": 2, "d": 3}, "list": [3], "e": true})
+        );
+    }
+
+    #[test]
+    fn rejects_a_layer_that_is_not_an_object() {
+        let layers = [json!({"a": 1}), json!([1])];
+        let err = merge_configs(&layers, &ArrayPolicy::default()).expect_err("array layer");
+        assert!(err.contains("layer 2"), "{err}");
+    }
+
+    #[test]
+    fn array_rules_apply_by_path() {
+        let policy = ArrayPolicy {
+            default: ArrayMerge::Concat,
+            by_path: [("mods.fixed".to_string(), ArrayMerge::Union)].into(),
+        };
+        let layers = [
+            json!({"mods": {"fixed": ["C"], "variable": ["M"]}}),
+            json!({"mods": {"fixed": ["C", "K"], "variable": ["M"]}}),
+        ];
+        assert_eq!(
+            merge_configs(&layers, &policy).expect("merge"),
+            json!({"mods": {"fixed": ["C", "K"], "variable": ["M", "M"]}})
+        );
+    }
+
+    #[test]
+    fn preview_labels_each_leaf() {
+        let defaults = json!({"a": 1, "b": 2, "c": {"d": 3}});
+        let persisted = json!({"a": 1, "b": 5, "x": 0});
+        let leaves: Vec<(String, LeafSource, bool)> = preview_merge(&defaults, &persisted)
+            .leaves
+            .into_iter()
+            .map(|leaf| (leaf.path, leaf.source, leaf.same_as_default))
+            .collect();
+        assert_eq!(
+            leaves,
+            [
+                ("a".to_string(), LeafSource::Overridden, true),
+                ("b".to_string(), LeafSource::Overridden, false),
+                ("c.d".to_string(), LeafSource::Default, false),
+                ("x".to_string(), LeafSource::PersistedOnly, false),
+            ]
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::PathResolver;
+
+use crate::workspace::list_dir;
+
+pub const MODELS_CACHE_FILENAME: &str = "models.json";
+/// Extra directories to scan, separated like `PATH`.
+const MODELS_DIR_ENV: &str = "PIONEER_MODELS_DIR";
+/// Model directories as laid out by the Pioneer installers, relative to the
+/// directory holding the binary.
+const BINARY_RELATIVE_DIRS: &[&str] = &[
+    "models",
+    "../models",
+    "../share/pioneer/models",
+    "../data/models",
+];
+/// Single-file weights formats; anything else in a models directory is ignored.
+const WEIGHT_EXTENSIONS: &[&str] = &["onnx", "pt", "bson", "jld2", "safetensors"];
+const VERSION_FILENAMES: &[&str] = &["VERSION", "version.txt", "version"];
+const INSTALL_DOCS: &str = "https://github.com/nwamsley1/Pioneer.jl";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ModelInfo {
+    pub name: String,
+    pub version: Option<String>,
+    pub path: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ModelList {
+    pub binary_version: Option<String>,
+    pub models: Vec<ModelInfo>,
+    pub searched_dirs: Vec<String>,
+    /// Whether the list came from the cache rather than a fresh scan.
+    #[serde(default)]
+    pub cached: bool,
+    /// Explains an empty list.
+    pub message: Option<String>,
+}
+
+fn cache_path(resolver: &PathResolver) -> Option<PathBuf> {
+    Some(resolver.app_cache_dir()?.join(MODELS_CACHE_FILENAME))
+}
+
+/// The models available to `pioneer`, scanned once per binary version. A
+/// list cached for another version (or with no version) is rescanned, and an
+/// empty result is never cached so newly installed models show up at once.
+pub fn list_models(
+    resolver: &PathResolver,
+    pioneer: &Path,
+    binary_version: Option<&str>,
+) -> ModelList {
+    let cache = cache_path(resolver);
+    if let (Some(path), Some(version)) = (&cache, binary_version) {
+        let cached = fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<ModelList>(&contents).ok())
+            .filter(|list| list.binary_version.as_deref() == Some(version));
+        if let Some(mut list) = cached {
+            list.cached = true;
+            return list;
+        }
+    }
+
+    let list = scan_models(pioneer, binary_version);
+    if let (Some(path), Some(_), false) = (&cache, binary_version, list.models.is_empty()) {
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(pretty) = serde_json::to_string_pretty(&list) {
+            let _ = fs::write(path, pretty);
+        }
+    }
+    list
+}
+
+fn model_dirs(pioneer: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = env::var_os(MODELS_DIR_ENV)
+        .map(|raw| env::split_paths(&raw).collect())
+        .unwrap_or_default();
+    let binary = fs::canonicalize(pioneer).unwrap_or_else(|_| pioneer.to_path_buf());
+    if let Some(bin_dir) = binary.parent() {
+        dirs.extend(BINARY_RELATIVE_DIRS.iter().map(|rel| bin_dir.join(rel)));
+    }
+    dirs
+}
+
+fn scan_models(pioneer: &Path, binary_version: Option<&str>) -> ModelList {
+    let dirs = model_dirs(pioneer);
+    // Keyed by name so a model found in several directories is listed once,
+    // from the first directory searched.
+    let mut models: BTreeMap<String, ModelInfo> = BTreeMap::new();
+    for dir in dirs.iter().filter(|dir| dir.is_dir()) {
+        for entry in list_dir(dir) {
+            let Some(model) = model_at(&entry) else {
+                continue;
+            };
+            models.entry(model.name.clone()).or_insert(model);
+        }
+    }
+
+    let message = models.is_empty().then(|| {
+        format!(
+            "No prediction models were found. Install the Pioneer models as described at {INSTALL_DOCS}, or point `{MODELS_DIR_ENV}` at the directory holding them."
+        )
+    });
+    ModelList {
+        binary_version: binary_version.map(str::to_string),
+        models: models.into_values().collect(),
+        searched_dirs: dirs
+            .iter()
+            .map(|dir| dir.to_string_lossy().to_string())
+            .collect(),
+        cached: false,
+        message,
+    }
+}
+
+/// A model is either a directory (its version read from a `VERSION` file
+/// inside) or a single weights file.
+fn model_at(path: &Path) -> Option<ModelInfo> {
+    let file_name = path.file_name()?.to_string_lossy().to_string();
+    if file_name.starts_with('.') {
+        return None;
+    }
+    let (name, version) = if path.is_dir() {
+        let version = VERSION_FILENAMES
+            .iter()
+            .find_map(|name| fs::read_to_string(path.join(name)).ok())
+            .map(|raw| raw.trim().to_string())
+            .filter(|version| !version.is_empty());
+        (file_name, version)
+    } else {
+        let extension = path.extension()?.to_string_lossy().to_ascii_lowercase();
+        if !WEIGHT_EXTENSIONS.contains(&extension.as_str()) {
+            return None;
+        }
+        (path.file_stem()?.to_string_lossy().to_string(), None)
+    };
+    Some(ModelInfo {
+        name,
+        version,
+        path: path.to_string_lossy().to_string(),
+    })
+}
@@ -0,0 +1,88 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Stops a scan of a huge results tree from stalling the completion event.
+const MAX_OUTPUT_FILES: usize = 1000;
+const MAX_SCAN_DEPTH: usize = 6;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct OutputFile {
+    pub path: String,
+    pub size: u64,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct RunOutputs {
+    pub files: Vec<OutputFile>,
+    /// Set when the listing is incomplete because `MAX_OUTPUT_FILES` was hit.
+    pub truncated: bool,
+    /// Why no listing could be produced, e.g. the config names no output
+    /// directory or it does not exist.
+    pub error: Option<String>,
+}
+
+/// Files under `dirs` modified at or after `since`, sorted by path.
+pub fn collect_outputs(dirs: &[PathBuf], since: SystemTime) -> RunOutputs {
+    let mut outputs = RunOutputs::default();
+    let existing: Vec<&PathBuf> = dirs.iter().filter(|dir| dir.is_dir()).collect();
+    if existing.is_empty() {
+        outputs.error = Some(if dirs.is_empty() {
+            "The config does not name an output directory".to_string()
+        } else {
+            format!(
+                "Output directory not found: {}",
+                dirs.iter()
+                    .map(|dir| dir.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        });
+        return outputs;
+    }
+
+    let mut seen = Vec::new();
+    for dir in existing {
+        scan(dir, since, 0, &mut seen, &mut outputs);
+    }
+    outputs.files.sort_by(|a, b| a.path.cmp(&b.path));
+    outputs
+}
+
+fn scan(
+    dir: &Path,
+    since: SystemTime,
+    depth: usize,
+    seen: &mut Vec<PathBuf>,
+    outputs: &mut RunOutputs,
+) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            if depth < MAX_SCAN_DEPTH {
+                scan(&path, since, depth + 1, seen, outputs);
+            }
+            continue;
+        }
+        let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+        if modified < since || seen.contains(&path) {
+            continue;
+        }
+        if outputs.files.len() == MAX_OUTPUT_FILES {
+            outputs.truncated = true;
+            return;
+        }
+        seen.push(path.clone());
+        outputs.files.push(OutputFile {
+            path: path.to_string_lossy().to_string(),
+            size: metadata.len(),
+        });
+    }
+}
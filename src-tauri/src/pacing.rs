@@ -0,0 +1,297 @@
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::runs;
+use crate::{RunMode, StageInfo};
+
+/// Progress (in points of 0–100) a run must make before it gets an ETA;
+/// projections from the first few percent swing too much to be useful.
+const MIN_ETA_PROGRESS: f32 = 5.0;
+
+/// Successful runs with recorded stage durations required before learned
+/// weights replace the uniform ones.
+pub const MIN_HISTORY_RUNS: usize = 3;
+/// Only the most recent runs are averaged so the weights follow changes in
+/// the user's workloads.
+const HISTORY_WINDOW: usize = 20;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct StageWeight {
+    pub key: String,
+    pub label: String,
+    pub weight: f32,
+    pub mean_seconds: Option<f64>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct StageWeights {
+    pub mode: RunMode,
+    pub learned: bool,
+    pub runs_used: usize,
+    pub stages: Vec<StageWeight>,
+}
+
+impl StageWeights {
+    /// Equal width for every stage but the last, which only marks completion.
+    /// This reproduces the original `index / (len - 1)` pacing.
+    pub fn uniform(mode: RunMode, stages: &[StageInfo], runs_used: usize) -> Self {
+        let last = stages.len().saturating_sub(1);
+        StageWeights {
+            mode,
+            learned: false,
+            runs_used,
+            stages: stages
+                .iter()
+                .enumerate()
+                .map(|(idx, stage)| StageWeight {
+                    key: stage.key.to_string(),
+                    label: stage.label.to_string(),
+                    weight: if idx < last { 1.0 } else { 0.0 },
+                    mean_seconds: None,
+                })
+                .collect(),
+        }
+    }
+
+    /// Percentage reached when the stage at `index` is entered.
+    pub fn progress_at(&self, index: usize) -> f32 {
+        let total: f32 = self.stages.iter().map(|stage| stage.weight).sum();
+        if index + 1 >= self.stages.len() || total <= 0.0 {
+            return 100.0;
+        }
+        let before: f32 = self.stages[..index].iter().map(|stage| stage.weight).sum();
+        before / total * 100.0
+    }
+}
+
+/// Averages the stage durations of recent successful runs in `root`. Stages a
+/// run never entered count as zero, matching how their time was absorbed by
+/// the preceding stage during that run.
+pub fn learn_weights(root: Option<&Path>, mode: RunMode, stages: &[StageInfo]) -> StageWeights {
+    let history: Vec<BTreeMap<String, f64>> = root
+        .map(runs::list_runs)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|run| run.mode == mode && run.success == Some(true))
+        .filter(|run| !run.stage_durations.is_empty())
+        .take(HISTORY_WINDOW)
+        .map(|run| run.stage_durations)
+        .collect();
+    if history.len() < MIN_HISTORY_RUNS {
+        return StageWeights::uniform(mode, stages, history.len());
+    }
+
+    let last = stages.len().saturating_sub(1);
+    let weights: Vec<StageWeight> = stages
+        .iter()
+        .enumerate()
+        .map(|(idx, stage)| {
+            let mean = history
+                .iter()
+                .map(|durations| durations.get(stage.key).copied().unwrap_or(0.0))
+                .sum::<f64>()
+                / history.len() as f64;
+            StageWeight {
+                key: stage.key.to_string(),
+                label: stage.label.to_string(),
+                weight: if idx < last { mean as f32 } else { 0.0 },
+                mean_seconds: Some(mean),
+            }
+        })
+        .collect();
+    if weights.iter().all(|stage| stage.weight <= 0.0) {
+        return StageWeights::uniform(mode, stages, history.len());
+    }
+    StageWeights {
+        mode,
+        learned: true,
+        runs_used: history.len(),
+        stages: weights,
+    }
+}
+
+/// Learned weights per mode, computed on first use and dropped whenever a new
+/// successful run adds history.
+#[derive(Default)]
+pub struct StageWeightCache {
+    build: Mutex<Option<StageWeights>>,
+    search: Mutex<Option<StageWeights>>,
+}
+
+impl StageWeightCache {
+    fn slot(&self, mode: RunMode) -> &Mutex<Option<StageWeights>> {
+        match mode {
+            RunMode::BuildSpecLib => &self.build,
+            RunMode::SearchDia => &self.search,
+        }
+    }
+
+    pub fn get(&self, mode: RunMode, root: Option<&Path>) -> StageWeights {
+        let stages = mode.stage_sequence();
+        let Ok(mut slot) = self.slot(mode).lock() else {
+            return learn_weights(root, mode, stages);
+        };
+        slot.get_or_insert_with(|| learn_weights(root, mode, stages))
+            .clone()
+    }
+
+    pub fn invalidate(&self, mode: RunMode) {
+        if let Ok(mut slot) = self.slot(mode).lock() {
+            *slot = None;
+        }
+    }
+}
+
+/// Projects the time remaining from the progress made since the run started,
+/// assuming the rest goes at the same average pace. Learned stage weights
+/// make that assumption hold better; time spent paused counts as elapsed.
+pub struct EtaEstimator {
+    started: Instant,
+    /// Progress the run started at: non-zero for resumed runs, which would
+    /// otherwise look faster than they are.
+    baseline: f32,
+}
+
+impl EtaEstimator {
+    pub fn start(baseline: f32) -> Self {
+        EtaEstimator {
+            started: Instant::now(),
+            baseline,
+        }
+    }
+
+    pub fn eta_seconds(&self, progress: f32) -> Option<u32> {
+        let made = progress - self.baseline;
+        if made < MIN_ETA_PROGRESS {
+            return None;
+        }
+        let remaining = (100.0 - progress).max(0.0);
+        let elapsed = self.started.elapsed().as_secs_f32();
+        Some((elapsed * remaining / made).round() as u32)
+    }
+}
+
+/// Sent when the run leaves a stage.
+#[derive(Serialize, Clone, Debug)]
+pub struct StageTimingPayload {
+    pub mode: RunMode,
+    pub run_id: Option<String>,
+    pub stage_key: String,
+    /// Time spent in `stage_key` on this visit.
+    pub elapsed_ms: u64,
+    pub next_stage_key: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct StageDuration {
+    pub stage_key: String,
+    pub elapsed_ms: u64,
+}
+
+/// Sent once Pioneer exits, whatever the outcome.
+#[derive(Serialize, Clone, Debug)]
+pub struct StageSummaryPayload {
+    pub mode: RunMode,
+    pub run_id: Option<String>,
+    /// In the order the stages were first entered; a stage visited more than
+    /// once reports its total.
+    pub stages: Vec<StageDuration>,
+    pub total_ms: u64,
+}
+
+/// When each stage was entered during a run.
+pub struct StageTimeline {
+    entries: Vec<(&'static str, Instant)>,
+}
+
+impl StageTimeline {
+    pub fn start(stage: &StageInfo) -> Self {
+        StageTimeline {
+            entries: vec![(stage.key, Instant::now())],
+        }
+    }
+
+    /// Returns the stage being left and how long this visit to it lasted.
+    pub fn enter(&mut self, stage: &StageInfo) -> (&'static str, Duration) {
+        let now = Instant::now();
+        let (previous, started) = self.entries[self.entries.len() - 1];
+        self.entries.push((stage.key, now));
+        (previous, now.saturating_duration_since(started))
+    }
+
+    /// Milliseconds per stage, the last one running until `end`.
+    pub fn summary(&self, end: Instant) -> Vec<StageDuration> {
+        let mut stages: Vec<StageDuration> = Vec::new();
+        for (idx, (key, started)) in self.entries.iter().enumerate() {
+            let finished = self
+                .entries
+                .get(idx + 1)
+                .map(|(_, next)| *next)
+                .unwrap_or(end);
+            let elapsed_ms = finished.saturating_duration_since(*started).as_millis() as u64;
+            match stages.iter_mut().find(|stage| stage.stage_key == *key) {
+                Some(stage) => stage.elapsed_ms += elapsed_ms,
+                None => stages.push(StageDuration {
+                    stage_key: key.to_string(),
+                    elapsed_ms,
+                }),
+            }
+        }
+        stages
+    }
+
+    /// Seconds spent in each entered stage, the last one running until `end`.
+    pub fn durations(&self, end: Instant) -> BTreeMap<String, f64> {
+        let mut durations = BTreeMap::new();
+        for (idx, (key, started)) in self.entries.iter().enumerate() {
+            let finished = self
+                .entries
+                .get(idx + 1)
+                .map(|(_, next)| *next)
+                .unwrap_or(end);
+            *durations.entry(key.to_string()).or_insert(0.0) +=
+                finished.saturating_duration_since(*started).as_secs_f64();
+        }
+        durations
+    }
+}
+
+/// Explicit completion figures in a line of output, such as "Progress: 47%"
+/// or "Processed 120/500 files". Ratios next to a slash, dot, or word
+/// character are ignored so paths and dates do not register.
+pub struct ProgressHints {
+    percent: Regex,
+    ratio: Regex,
+}
+
+impl Default for ProgressHints {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProgressHints {
+    pub fn new() -> Self {
+        ProgressHints {
+            percent: Regex::new(r"(\d{1,3}(?:\.\d+)?)\s*%").expect("valid percent regex"),
+            ratio: Regex::new(r"(?:^|[^\w/.])(\d+)\s*/\s*(\d+)(?:$|[^\w/.])")
+                .expect("valid ratio regex"),
+        }
+    }
+
+    /// Fraction complete, in `[0, 1]`, when the line carries one.
+    pub fn fraction(&self, line: &str) -> Option<f32> {
+        if let Some(captures) = self.percent.captures(line) {
+            let percent: f32 = captures[1].parse().ok()?;
+            return (percent <= 100.0).then_some(percent / 100.0);
+        }
+        let captures = self.ratio.captures(line)?;
+        let done: u64 = captures[1].parse().ok()?;
+        let total: u64 = captures[2].parse().ok()?;
+        (total > 0 && done <= total).then(|| done as f32 / total as f32)
+    }
+}
@@ -0,0 +1,129 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::env;
+
+use crate::diff::join_path;
+use crate::RunMode;
+
+/// Environment variables that change how many threads Pioneer (Julia and the
+/// native libraries it loads) will start.
+const THREAD_ENV_VARS: &[&str] = &[
+    "JULIA_NUM_THREADS",
+    "JULIA_CPU_THREADS",
+    "OMP_NUM_THREADS",
+    "OPENBLAS_NUM_THREADS",
+    "MKL_NUM_THREADS",
+];
+
+#[derive(Serialize, Debug)]
+pub struct ConfiguredThreads {
+    pub path: String,
+    pub value: Value,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ThreadEnvVar {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ParallelismReport {
+    pub mode: RunMode,
+    pub logical_cores: Option<usize>,
+    pub configured: Vec<ConfiguredThreads>,
+    pub env_overrides: Vec<ThreadEnvVar>,
+    /// Best guess at Pioneer's thread count: the config, else
+    /// `JULIA_NUM_THREADS`. `None` when neither sets it.
+    pub effective_threads: Option<usize>,
+    pub warnings: Vec<String>,
+}
+
+pub fn effective_parallelism(mode: RunMode, config: &Value) -> ParallelismReport {
+    let logical_cores = std::thread::available_parallelism()
+        .ok()
+        .map(|cores| cores.get());
+
+    let mut configured = Vec::new();
+    collect_thread_keys(config, "", &mut configured);
+
+    let env_overrides: Vec<ThreadEnvVar> = THREAD_ENV_VARS
+        .iter()
+        .filter_map(|name| {
+            env::var(name)
+                .ok()
+                .filter(|value| !value.trim().is_empty())
+                .map(|value| ThreadEnvVar {
+                    name: name.to_string(),
+                    value,
+                })
+        })
+        .collect();
+
+    let julia_threads = env_overrides
+        .iter()
+        .find(|var| var.name == "JULIA_NUM_THREADS")
+        .and_then(|var| parse_thread_count(&var.value, logical_cores));
+    let effective_threads = configured
+        .iter()
+        .find_map(|entry| entry.value.as_u64().map(|n| n as usize))
+        .or(julia_threads);
+
+    let mut warnings = Vec::new();
+    if let Some(cores) = logical_cores {
+        for entry in &configured {
+            if let Some(threads) = entry.value.as_u64().filter(|n| *n as usize > cores) {
+                warnings.push(format!(
+                    "`{}` requests {threads} threads but only {cores} logical cores are available",
+                    entry.path
+                ));
+            }
+        }
+        for var in &env_overrides {
+            if let Some(threads) =
+                parse_thread_count(&var.value, Some(cores)).filter(|n| *n > cores)
+            {
+                warnings.push(format!(
+                    "{} requests {threads} threads but only {cores} logical cores are available",
+                    var.name
+                ));
+            }
+        }
+    }
+
+    ParallelismReport {
+        mode,
+        logical_cores,
+        configured,
+        env_overrides,
+        effective_threads,
+        warnings,
+    }
+}
+
+fn collect_thread_keys(value: &Value, prefix: &str, out: &mut Vec<ConfiguredThreads>) {
+    let Value::Object(map) = value else {
+        return;
+    };
+    for (key, child) in map {
+        let path = join_path(prefix, key);
+        if child.is_object() {
+            collect_thread_keys(child, &path, out);
+        } else if key.to_ascii_lowercase().contains("thread") {
+            out.push(ConfiguredThreads {
+                path,
+                value: child.clone(),
+            });
+        }
+    }
+}
+
+/// Understands plain counts, Julia's `auto`, and its `N,M` pool syntax (the
+/// first number is the default pool that does the work).
+fn parse_thread_count(raw: &str, logical_cores: Option<usize>) -> Option<usize> {
+    let first = raw.split(',').next()?.trim();
+    if first.eq_ignore_ascii_case("auto") {
+        return logical_cores;
+    }
+    first.parse().ok()
+}
@@ -0,0 +1,102 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::RunMode;
+
+/// Config keys known to hold filesystem paths (or arrays of them). Only these
+/// are rewritten; other strings may legitimately contain slashes.
+const BUILD_PATH_KEYS: &[&str] = &[
+    "fasta_paths",
+    "out_dir",
+    "lib_name",
+    "new_lib_name",
+    "library_params.calibration_raw_file",
+];
+const SEARCH_PATH_KEYS: &[&str] = &["paths.library", "paths.ms_data", "paths.results"];
+
+#[derive(Serialize, Debug)]
+pub struct PathChange {
+    pub path: String,
+    pub old_value: String,
+    pub new_value: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct NormalizedPaths {
+    pub config: Value,
+    pub changes: Vec<PathChange>,
+    pub warnings: Vec<String>,
+}
+
+pub fn path_keys(mode: RunMode) -> &'static [&'static str] {
+    match mode {
+        RunMode::BuildSpecLib => BUILD_PATH_KEYS,
+        RunMode::SearchDia => SEARCH_PATH_KEYS,
+    }
+}
+
+/// Rewrites the separators of every recognised path key to the host's, and
+/// flags Windows-only paths (drive letters, UNC shares) on other hosts since
+/// no separator change can make those resolve.
+pub fn normalize_config_paths(mode: RunMode, config: &Value) -> NormalizedPaths {
+    let mut normalized = config.clone();
+    let mut changes = Vec::new();
+    let mut warnings = Vec::new();
+
+    for key in path_keys(mode) {
+        let Some(value) = lookup_mut(&mut normalized, key) else {
+            continue;
+        };
+        let targets: Vec<&mut Value> = match value {
+            Value::Array(items) => items.iter_mut().collect(),
+            other => vec![other],
+        };
+        for target in targets {
+            let Some(raw) = target.as_str().map(str::to_string) else {
+                continue;
+            };
+            if !cfg!(windows) && is_windows_only(&raw) {
+                warnings.push(format!(
+                    "`{key}` is a Windows path ({raw}) and will not resolve on this machine"
+                ));
+            }
+            let converted = to_host_separators(&raw);
+            if converted != raw {
+                *target = Value::String(converted.clone());
+                changes.push(PathChange {
+                    path: key.to_string(),
+                    old_value: raw,
+                    new_value: converted,
+                });
+            }
+        }
+    }
+
+    NormalizedPaths {
+        config: normalized,
+        changes,
+        warnings,
+    }
+}
+
+fn lookup_mut<'a>(value: &'a mut Value, path: &str) -> Option<&'a mut Value> {
+    path.split('.')
+        .try_fold(value, |current, key| current.get_mut(key))
+}
+
+fn to_host_separators(raw: &str) -> String {
+    if raw.contains("://") {
+        return raw.to_string();
+    }
+    if cfg!(windows) {
+        raw.replace('/', "\\")
+    } else {
+        raw.replace('\\', "/")
+    }
+}
+
+fn is_windows_only(raw: &str) -> bool {
+    let bytes = raw.as_bytes();
+    let drive = bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':';
+    drive || raw.starts_with("\\\\")
+}
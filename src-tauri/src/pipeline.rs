@@ -0,0 +1,412 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+use crate::diff::lookup;
+use crate::inputs::validate_build_inputs;
+use crate::outputs::RunOutputs;
+use crate::validation::{validate_param_ranges, RangeRule, ValidationIssue};
+use crate::{RunCompletePayload, RunMode, StageInfo};
+
+const LIBRARY_EXTENSION: &str = "poin";
+const NCE_TOLERANCE: f64 = 1e-6;
+
+/// Validates a build-then-search pipeline. Each config gets its own checks,
+/// reported under a `build.` or `search.` prefix, followed by the checks that
+/// only make sense across the pair. The search library is not required to
+/// exist yet since the build will produce it.
+pub fn validate_pipeline(
+    build: &Value,
+    search: &Value,
+    build_rules: &[RangeRule],
+    search_rules: &[RangeRule],
+) -> Vec<ValidationIssue> {
+    let mut issues: Vec<ValidationIssue> = validate_build_inputs(build)
+        .into_iter()
+        .chain(validate_param_ranges(build, build_rules))
+        .map(|issue| prefixed("build", issue))
+        .collect();
+    issues.extend(
+        validate_param_ranges(search, search_rules)
+            .into_iter()
+            .map(|issue| prefixed("search", issue)),
+    );
+
+    check_library_path(build, search, &mut issues);
+    check_nce(build, search, &mut issues);
+    issues
+}
+
+fn prefixed(prefix: &str, issue: ValidationIssue) -> ValidationIssue {
+    ValidationIssue::new(format!("{prefix}.{}", issue.path), issue.message)
+}
+
+/// The library Pioneer writes for `lib_name`, which gains a `.poin`
+/// extension unless it already has one.
+pub fn built_library_path(lib_name: &str) -> PathBuf {
+    let path = PathBuf::from(lib_name.trim_end_matches(['/', '\\']));
+    if path.extension().is_some_and(|ext| ext == LIBRARY_EXTENSION) {
+        path
+    } else {
+        let mut with_ext = path.into_os_string();
+        with_ext.push(format!(".{LIBRARY_EXTENSION}"));
+        PathBuf::from(with_ext)
+    }
+}
+
+fn normalized(path: &str) -> PathBuf {
+    let trimmed = Path::new(path.trim_end_matches(['/', '\\']));
+    trimmed.components().collect()
+}
+
+fn check_library_path(build: &Value, search: &Value, issues: &mut Vec<ValidationIssue>) {
+    let Some(lib_name) = lookup(build, "lib_name").and_then(Value::as_str) else {
+        issues.push(ValidationIssue::new(
+            "build.lib_name",
+            "Build config does not name an output library",
+        ));
+        return;
+    };
+    let Some(library) = lookup(search, "paths.library").and_then(Value::as_str) else {
+        issues.push(ValidationIssue::new(
+            "search.paths.library",
+            "Search config does not name an input library",
+        ));
+        return;
+    };
+
+    let expected = built_library_path(lib_name);
+    if normalized(library) != normalized(&expected.to_string_lossy()) {
+        issues.push(ValidationIssue::new(
+            "search.paths.library",
+            format!(
+                "Search reads {library}, but the build writes {}",
+                expected.display()
+            ),
+        ));
+    }
+}
+
+fn check_nce(build: &Value, search: &Value, issues: &mut Vec<ValidationIssue>) {
+    let build_nce = lookup(build, "nce_params.nce").and_then(Value::as_f64);
+    let search_nce = lookup(search, "acquisition.nce").and_then(Value::as_f64);
+    let dynamic = lookup(build, "nce_params.dynamic_nce")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    if let (Some(build_nce), Some(search_nce)) = (build_nce, search_nce) {
+        if !dynamic && (build_nce - search_nce).abs() > NCE_TOLERANCE {
+            issues.push(ValidationIssue::new(
+                "search.acquisition.nce",
+                format!(
+                    "Search NCE {search_nce} differs from the {build_nce} the library is predicted at"
+                ),
+            ));
+        }
+    }
+}
+
+/// Where a config stores a `[min, max]` range.
+enum RangeSource {
+    Pair(&'static str, &'static str),
+}
+
+struct RangeSpec {
+    field: &'static str,
+    unit: &'static str,
+    build: RangeSource,
+    /// Search keys holding the same range, checked in order. SearchDIA
+    /// configs have none so far (the search takes these ranges from the
+    /// library), so each field is reported as unchecked until a release adds
+    /// one here.
+    search: &'static [RangeSource],
+}
+
+const RANGE_SPECS: &[RangeSpec] = &[
+    RangeSpec {
+        field: "precursor charge",
+        unit: "",
+        build: RangeSource::Pair(
+            "fasta_digest_params.min_charge",
+            "fasta_digest_params.max_charge",
+        ),
+        search: &[],
+    },
+    RangeSpec {
+        field: "precursor m/z",
+        unit: " m/z",
+        build: RangeSource::Pair("library_params.prec_mz_min", "library_params.prec_mz_max"),
+        search: &[],
+    },
+    RangeSpec {
+        field: "fragment m/z",
+        unit: " m/z",
+        build: RangeSource::Pair("library_params.frag_mz_min", "library_params.frag_mz_max"),
+        search: &[],
+    },
+];
+
+/// What `check_range_consistency` found, including what it could not check,
+/// so an empty `mismatches` is never mistaken for a pass.
+#[derive(Serialize, Debug)]
+pub struct RangeConsistency {
+    pub mismatches: Vec<RangeMismatch>,
+    pub unchecked: Vec<UncheckedRange>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct UncheckedRange {
+    pub field: String,
+    pub reason: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct RangeMismatch {
+    pub field: String,
+    pub build_path: String,
+    pub search_path: String,
+    pub build_range: [f64; 2],
+    pub search_range: [f64; 2],
+    /// True when the search expects values the library cannot contain, as
+    /// opposed to a library that is merely wider than needed.
+    pub search_exceeds_build: bool,
+    pub message: String,
+}
+
+impl RangeSource {
+    fn read(&self, config: &Value) -> Option<([f64; 2], String)> {
+        match self {
+            RangeSource::Pair(min_key, max_key) => {
+                let min = lookup(config, min_key).and_then(Value::as_f64)?;
+                let max = lookup(config, max_key).and_then(Value::as_f64)?;
+                Some(([min, max], format!("{min_key}/{max_key}")))
+            }
+        }
+    }
+}
+
+fn format_range(range: [f64; 2], unit: &str) -> String {
+    format!("{}–{}{unit}", range[0], range[1])
+}
+
+/// Compares the charge and m/z ranges a library is built for against those
+/// the search expects, straight from the two configs so it works before the
+/// library exists.
+pub fn check_range_consistency(build: &Value, search: &Value) -> RangeConsistency {
+    let mut mismatches = Vec::new();
+    let mut unchecked = Vec::new();
+    let mut skip = |field: &str, reason: String| {
+        unchecked.push(UncheckedRange {
+            field: field.to_string(),
+            reason,
+        })
+    };
+    for spec in RANGE_SPECS {
+        let Some((build_range, build_path)) = spec.build.read(build) else {
+            skip(
+                spec.field,
+                format!("The build config has no {} range", spec.field),
+            );
+            continue;
+        };
+        let Some((search_range, search_path)) =
+            spec.search.iter().find_map(|source| source.read(search))
+        else {
+            skip(
+                spec.field,
+                format!(
+                    "The search config has no comparable {} range; SearchDIA takes it from the library",
+                    spec.field
+                ),
+            );
+            continue;
+        };
+        if build_range == search_range {
+            continue;
+        }
+
+        let exceeds = search_range[0] < build_range[0] || search_range[1] > build_range[1];
+        let build_text = format_range(build_range, spec.unit);
+        let search_text = format_range(search_range, spec.unit);
+        let message = if exceeds {
+            format!(
+                "The search expects {} {search_text} but the library only covers {build_text}; widen `{build_path}` to include {search_text} or the missing precursors will never be matched",
+                spec.field
+            )
+        } else {
+            format!(
+                "The library covers {} {build_text} but the search only uses {search_text}; narrowing `{build_path}` to {search_text} would shorten the build",
+                spec.field
+            )
+        };
+        mismatches.push(RangeMismatch {
+            field: spec.field.to_string(),
+            build_path,
+            search_path,
+            build_range,
+            search_range,
+            search_exceeds_build: exceeds,
+            message,
+        });
+    }
+    RangeConsistency {
+        mismatches,
+        unchecked,
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PipelineStage {
+    pub phase: RunMode,
+    pub key: String,
+    pub label: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PipelineStartedPayload {
+    pub pipeline_id: String,
+    pub build_run_id: Option<String>,
+    /// The build's stages followed by the search's, in the order
+    /// `pioneer-pipeline-progress` moves through them.
+    pub stages: Vec<PipelineStage>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PipelineProgressPayload {
+    pub pipeline_id: String,
+    pub phase: RunMode,
+    /// Index into the pipeline's `stages`.
+    pub stage_index: usize,
+    pub stage_key: String,
+    pub stage_label: String,
+    /// 0–100 across both phases.
+    pub progress: f32,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PipelineCompletePayload {
+    pub pipeline_id: String,
+    pub success: bool,
+    /// The phase that failed or was cancelled; the search never starts when
+    /// this is the build.
+    pub failed_phase: Option<RunMode>,
+    pub library_path: Option<String>,
+    pub search_run_id: Option<String>,
+    pub message: Option<String>,
+}
+
+/// Ties one phase's run to its pipeline: progress is rescaled into the
+/// phase's share of the bar, and the completion payload is handed back to the
+/// thread driving the pipeline. The sender is dropped with the run's options,
+/// so a run that ends without completing still wakes that thread.
+pub struct PipelineLink {
+    pub pipeline_id: String,
+    pub phase: RunMode,
+    pub done: mpsc::Sender<RunCompletePayload>,
+}
+
+impl PipelineLink {
+    /// Each phase gets a share of the bar proportional to its stage count, so
+    /// stages advance the combined bar at an even pace.
+    fn band(&self) -> (f32, f32, usize) {
+        let build = RunMode::BuildSpecLib.stage_sequence().len();
+        let search = RunMode::SearchDia.stage_sequence().len();
+        let split = build as f32 / (build + search) as f32 * 100.0;
+        match self.phase {
+            RunMode::BuildSpecLib => (0.0, split, 0),
+            RunMode::SearchDia => (split, 100.0, build),
+        }
+    }
+
+    pub fn overall(&self, progress: f32) -> f32 {
+        let (start, end, _) = self.band();
+        start + (end - start) * progress.clamp(0.0, 100.0) / 100.0
+    }
+
+    pub fn progress_payload(&self, stage: &StageInfo, progress: f32) -> PipelineProgressPayload {
+        let (_, _, offset) = self.band();
+        let local = self
+            .phase
+            .stage_sequence()
+            .iter()
+            .position(|candidate| candidate.key == stage.key)
+            .unwrap_or(0);
+        PipelineProgressPayload {
+            pipeline_id: self.pipeline_id.clone(),
+            phase: self.phase,
+            stage_index: offset + local,
+            stage_key: stage.key.to_string(),
+            stage_label: stage.label.to_string(),
+            progress: self.overall(progress),
+        }
+    }
+}
+
+pub fn pipeline_stages() -> Vec<PipelineStage> {
+    [RunMode::BuildSpecLib, RunMode::SearchDia]
+        .into_iter()
+        .flat_map(|phase| {
+            phase
+                .stage_sequence()
+                .iter()
+                .map(move |stage| PipelineStage {
+                    phase,
+                    key: stage.key.to_string(),
+                    label: stage.label.to_string(),
+                })
+        })
+        .collect()
+}
+
+/// The library a finished build wrote: the `.poin` its `lib_name` implies,
+/// or else one found among the files the run produced.
+pub fn resolve_built_library(
+    build: &Value,
+    outputs: Option<&RunOutputs>,
+) -> Result<PathBuf, String> {
+    let expected = lookup(build, "lib_name")
+        .and_then(Value::as_str)
+        .map(built_library_path);
+    if let Some(expected) = expected.as_ref().filter(|path| path.exists()) {
+        return Ok(expected.clone());
+    }
+    let produced = outputs.and_then(|outputs| {
+        outputs.files.iter().find_map(|file| {
+            Path::new(&file.path)
+                .ancestors()
+                .find(|ancestor| {
+                    ancestor
+                        .extension()
+                        .is_some_and(|ext| ext == LIBRARY_EXTENSION)
+                })
+                .map(Path::to_path_buf)
+        })
+    });
+    produced.ok_or_else(|| match expected {
+        Some(expected) => format!(
+            "The build finished but {} was not found",
+            expected.display()
+        ),
+        None => "The build config does not name an output library".to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_range_consistency;
+    use crate::{FALLBACK_BUILD, FALLBACK_SEARCH};
+
+    #[test]
+    fn default_configs_report_every_range_as_unchecked() {
+        let build = serde_json::from_str(FALLBACK_BUILD).unwrap();
+        let search = serde_json::from_str(FALLBACK_SEARCH).unwrap();
+        let report = check_range_consistency(&build, &search);
+        assert!(report.mismatches.is_empty());
+        let fields: Vec<&str> = report.unchecked.iter().map(|r| r.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            ["precursor charge", "precursor m/z", "fragment m/z"]
+        );
+        assert!(report.unchecked[0].reason.starts_with("The search config"));
+    }
+}
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::PathBuf;
+use tauri::PathResolver;
+
+use crate::diff::lookup;
+use crate::encoding::strip_bom;
+use crate::RunMode;
+
+pub const POLICY_FILENAME: &str = "parameter_policy.json";
+
+/// Locked parameters per workflow, keyed by dotted config path, read from
+/// `parameter_policy.json`. The file is maintained by whoever owns the lab's
+/// SOPs; the GUI never writes it.
+#[derive(Deserialize, Default, Debug)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ParameterPolicy {
+    /// Refuse to start runs that violate the policy rather than only
+    /// reporting violations.
+    pub enforce: bool,
+    pub build_spec_lib: Map<String, Value>,
+    pub search_dia: Map<String, Value>,
+}
+
+impl ParameterPolicy {
+    fn locked(&self, mode: RunMode) -> &Map<String, Value> {
+        match mode {
+            RunMode::BuildSpecLib => &self.build_spec_lib,
+            RunMode::SearchDia => &self.search_dia,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct PolicyViolation {
+    pub path: String,
+    pub expected: Value,
+    /// `None` when the config does not set the key at all.
+    pub actual: Option<Value>,
+    pub message: String,
+}
+
+fn policy_path(resolver: &PathResolver) -> Option<PathBuf> {
+    Some(resolver.app_config_dir()?.join(POLICY_FILENAME))
+}
+
+/// `Ok(None)` when no policy is installed. A policy file that cannot be
+/// parsed is an error rather than "no policy", so a typo never quietly lifts
+/// the locks.
+pub fn load_policy(resolver: &PathResolver) -> Result<Option<ParameterPolicy>, String> {
+    let Some(path) = policy_path(resolver).filter(|path| path.is_file()) else {
+        return Ok(None);
+    };
+    let contents = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(strip_bom(&contents))
+        .map(Some)
+        .map_err(|e| format!("Invalid {}: {e}", path.display()))
+}
+
+pub fn check_policy(
+    mode: RunMode,
+    config: &Value,
+    policy: &ParameterPolicy,
+) -> Vec<PolicyViolation> {
+    policy
+        .locked(mode)
+        .iter()
+        .filter_map(|(path, expected)| {
+            let actual = lookup(config, path);
+            if actual.is_some_and(|actual| values_match(actual, expected)) {
+                return None;
+            }
+            let message = match actual {
+                Some(actual) => format!("`{path}` must be {expected}, found {actual}"),
+                None => format!("`{path}` must be set to {expected}"),
+            };
+            Some(PolicyViolation {
+                path: path.clone(),
+                expected: expected.clone(),
+                actual: actual.cloned(),
+                message,
+            })
+        })
+        .collect()
+}
+
+/// Numbers compare by value, so a policy's `1` accepts a config's `1.0`.
+fn values_match(actual: &Value, expected: &Value) -> bool {
+    match (actual.as_f64(), expected.as_f64()) {
+        (Some(actual), Some(expected)) => actual == expected,
+        _ => actual == expected,
+    }
+}
+
+pub fn describe_violations(violations: &[PolicyViolation]) -> Option<String> {
+    if violations.is_empty() {
+        return None;
+    }
+    let lines: Vec<&str> = violations
+        .iter()
+        .map(|violation| violation.message.as_str())
+        .collect();
+    Some(format!(
+        "This run violates the approved parameter policy:\n{}",
+        lines.join("\n")
+    ))
+}
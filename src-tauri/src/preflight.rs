@@ -0,0 +1,363 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{self, OpenOptions};
+use std::path::{Component, Path, PathBuf};
+use tauri::PathResolver;
+
+use crate::capabilities::FormatRange;
+use crate::diff::lookup;
+use crate::diskspace::DiskSpaceCheck;
+use crate::inputs::{validate_build_inputs, validate_search_inputs};
+use crate::pipeline::built_library_path;
+use crate::validation::ValidationIssue;
+use crate::RunMode;
+
+const PROBE_FILENAME: &str = ".pioneer-gui-write-probe";
+pub const OUTPUT_SUBDIRS_FILENAME: &str = "output_subdirs.json";
+/// Subdirectories Pioneer creates under the results directory during a search.
+const DEFAULT_SEARCH_SUBDIRS: &[&str] = &["temp_data", "qc_plots"];
+/// Extensions of the tables and reports a search leaves in its results
+/// directory. Only top-level entries are looked at.
+const SEARCH_RESULT_EXTENSIONS: &[&str] = &["arrow", "tsv", "csv", "parquet", "pdf"];
+/// Entries listed in an `ExistingResults` warning; `count` has the total.
+const MAX_LISTED_RESULTS: usize = 10;
+
+#[derive(Serialize, Debug)]
+pub struct WritableCheck {
+    pub key: String,
+    pub path: String,
+    /// The directory actually probed: `path` itself, or its nearest existing
+    /// ancestor when Pioneer would create `path` during the run.
+    pub probed_path: Option<String>,
+    pub writable: bool,
+    pub error: Option<String>,
+}
+
+/// Config keys naming directories Pioneer writes into. `lib_name` is a path
+/// prefix for the library files, so its parent is the directory that matters.
+pub fn output_directories(mode: RunMode, config: &Value) -> Vec<(String, PathBuf)> {
+    let string_at = |key: &str| {
+        lookup(config, key)
+            .and_then(Value::as_str)
+            .map(PathBuf::from)
+    };
+    let mut dirs = Vec::new();
+    match mode {
+        RunMode::BuildSpecLib => {
+            if let Some(out_dir) = string_at("out_dir") {
+                dirs.push(("out_dir".to_string(), out_dir));
+            }
+            if let Some(parent) =
+                string_at("lib_name").and_then(|p| p.parent().map(Path::to_path_buf))
+            {
+                if !parent.as_os_str().is_empty() {
+                    dirs.push(("lib_name".to_string(), parent));
+                }
+            }
+        }
+        RunMode::SearchDia => {
+            if let Some(results) = string_at("paths.results") {
+                dirs.push(("paths.results".to_string(), results));
+            }
+        }
+    }
+    dirs
+}
+
+pub fn check_writable(key: &str, dir: &Path) -> WritableCheck {
+    let mut check = WritableCheck {
+        key: key.to_string(),
+        path: dir.to_string_lossy().to_string(),
+        probed_path: None,
+        writable: false,
+        error: None,
+    };
+
+    let Some(existing) = dir.ancestors().find(|ancestor| ancestor.is_dir()) else {
+        check.error = Some("No existing parent directory".into());
+        return check;
+    };
+    check.probed_path = Some(existing.to_string_lossy().to_string());
+
+    let probe = existing.join(PROBE_FILENAME);
+    match OpenOptions::new().write(true).create_new(true).open(&probe) {
+        Ok(file) => {
+            drop(file);
+            let _ = fs::remove_file(&probe);
+            check.writable = true;
+        }
+        Err(err) => check.error = Some(err.to_string()),
+    }
+    check
+}
+
+pub fn check_output_writable(
+    mode: RunMode,
+    config: &Value,
+    config_dir: Option<&Path>,
+) -> Vec<WritableCheck> {
+    let mut checks: Vec<WritableCheck> = output_directories(mode, config)
+        .iter()
+        .map(|(key, dir)| check_writable(key, dir))
+        .collect();
+    if let Some(dir) = config_dir {
+        checks.push(check_writable("app_config_dir", dir));
+    }
+    checks
+}
+
+/// Everything `run_pioneer` checks before launching, for a frontend that
+/// wants to block the Run button rather than wait for the error.
+#[derive(Serialize, Debug)]
+pub struct PreflightReport {
+    /// Inputs that are missing, unreadable, or otherwise unusable.
+    pub inputs: Vec<ValidationIssue>,
+    /// Output directories (and required subdirectories) that cannot be
+    /// written; writable ones are left out.
+    pub outputs: Vec<WritableCheck>,
+    /// Previous results the run would overwrite. These do not block the
+    /// run; the UI is expected to ask before continuing.
+    pub existing_results: Vec<ExistingResults>,
+    /// Free space under each output directory. A low reading is a warning
+    /// and does not affect `clear`.
+    pub disk_space: Vec<DiskSpaceCheck>,
+    /// Nothing would stop the run.
+    pub clear: bool,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ExistingResults {
+    pub key: String,
+    pub path: String,
+    /// Up to `MAX_LISTED_RESULTS` of the entries found.
+    pub entries: Vec<String>,
+    pub count: usize,
+    pub message: String,
+}
+
+/// Output from an earlier run where this one would write: result tables and
+/// Pioneer's working subdirectories in a search's `paths.results`, or the
+/// `.poin` library a build's `lib_name` names.
+pub fn check_existing_results(mode: RunMode, config: &Value) -> Vec<ExistingResults> {
+    let mut found = Vec::new();
+    match mode {
+        RunMode::SearchDia => {
+            let Some(results) = lookup(config, "paths.results").and_then(Value::as_str) else {
+                return found;
+            };
+            let Ok(entries) = fs::read_dir(results) else {
+                return found;
+            };
+            let mut matches: Vec<String> = entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| is_search_result(path))
+                .filter_map(|path| {
+                    path.file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                })
+                .collect();
+            if matches.is_empty() {
+                return found;
+            }
+            matches.sort();
+            let count = matches.len();
+            matches.truncate(MAX_LISTED_RESULTS);
+            found.push(ExistingResults {
+                key: "paths.results".into(),
+                path: results.to_string(),
+                entries: matches,
+                count,
+                message: format!(
+                    "{results} already holds results from an earlier search ({count} entries); this run may overwrite them"
+                ),
+            });
+        }
+        RunMode::BuildSpecLib => {
+            let Some(lib_name) = lookup(config, "lib_name").and_then(Value::as_str) else {
+                return found;
+            };
+            let library = built_library_path(lib_name);
+            if library.exists() {
+                let display = library.to_string_lossy().to_string();
+                found.push(ExistingResults {
+                    key: "lib_name".into(),
+                    path: display.clone(),
+                    entries: vec![display.clone()],
+                    count: 1,
+                    message: format!(
+                        "The library {display} already exists; this build would overwrite it"
+                    ),
+                });
+            }
+        }
+    }
+    found
+}
+
+fn is_search_result(path: &Path) -> bool {
+    if path.is_dir() {
+        return path
+            .file_name()
+            .is_some_and(|name| DEFAULT_SEARCH_SUBDIRS.iter().any(|dir| name == *dir));
+    }
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+        .is_some_and(|ext| SEARCH_RESULT_EXTENSIONS.contains(&ext.as_str()))
+}
+
+/// The input files and directories the workflow reads: FASTA files and
+/// models for a build, the library, MS data, and auxiliary databases for a
+/// search.
+pub fn check_inputs(
+    mode: RunMode,
+    config: &Value,
+    library_formats: Option<FormatRange>,
+) -> Vec<ValidationIssue> {
+    match mode {
+        RunMode::BuildSpecLib => validate_build_inputs(config),
+        RunMode::SearchDia => validate_search_inputs(config, library_formats),
+    }
+}
+
+pub fn describe_input_issues(issues: &[ValidationIssue]) -> Option<String> {
+    if issues.is_empty() {
+        return None;
+    }
+    let lines: Vec<String> = issues
+        .iter()
+        .map(|issue| format!("{}: {}", issue.path, issue.message))
+        .collect();
+    Some(format!(
+        "Some inputs are not usable (pass skip_preflight to run anyway):\n{}",
+        lines.join("\n")
+    ))
+}
+
+pub fn describe_unwritable(checks: &[WritableCheck]) -> Option<String> {
+    let problems: Vec<String> = checks
+        .iter()
+        .filter(|check| !check.writable)
+        .map(|check| {
+            format!(
+                "{} ({}) is not writable: {}",
+                check.key,
+                check.path,
+                check.error.as_deref().unwrap_or("unknown error")
+            )
+        })
+        .collect();
+    if problems.is_empty() {
+        None
+    } else {
+        Some(problems.join("\n"))
+    }
+}
+
+/// Subdirectories, relative to the run's primary output directory, that must
+/// be creatable before a run starts. Persisted in `output_subdirs.json`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OutputSubdirs {
+    pub build_spec_lib: Vec<String>,
+    pub search_dia: Vec<String>,
+}
+
+impl Default for OutputSubdirs {
+    fn default() -> Self {
+        Self {
+            build_spec_lib: Vec::new(),
+            search_dia: DEFAULT_SEARCH_SUBDIRS
+                .iter()
+                .map(|dir| dir.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl OutputSubdirs {
+    pub fn for_mode(&self, mode: RunMode) -> &[String] {
+        match mode {
+            RunMode::BuildSpecLib => &self.build_spec_lib,
+            RunMode::SearchDia => &self.search_dia,
+        }
+    }
+}
+
+fn subdirs_path(resolver: &PathResolver) -> Option<PathBuf> {
+    Some(resolver.app_config_dir()?.join(OUTPUT_SUBDIRS_FILENAME))
+}
+
+pub fn load_output_subdirs(resolver: &PathResolver) -> OutputSubdirs {
+    subdirs_path(resolver)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Only plain relative paths are accepted, so a probe can never reach outside
+/// the output directory.
+pub fn save_output_subdirs(resolver: &PathResolver, subdirs: &OutputSubdirs) -> Result<(), String> {
+    for dir in subdirs.build_spec_lib.iter().chain(&subdirs.search_dia) {
+        let relative = Path::new(dir);
+        if dir.trim().is_empty()
+            || !relative
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(format!("`{dir}` is not a plain relative directory"));
+        }
+    }
+    let path =
+        subdirs_path(resolver).ok_or_else(|| "App config directory is unavailable".to_string())?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let pretty = serde_json::to_string_pretty(subdirs).map_err(|e| e.to_string())?;
+    fs::write(path, pretty).map_err(|e| e.to_string())
+}
+
+/// Creates each subdirectory under the primary output directory to prove it
+/// can be, then removes whatever the probe created; directories that already
+/// existed are left alone and just probed for writability.
+pub fn check_output_subdirs(
+    mode: RunMode,
+    config: &Value,
+    subdirs: &[String],
+) -> Vec<WritableCheck> {
+    let Some((base_key, base)) = output_directories(mode, config).into_iter().next() else {
+        return Vec::new();
+    };
+    subdirs
+        .iter()
+        .map(|subdir| {
+            let key = format!("{base_key}/{subdir}");
+            let target = base.join(subdir);
+            if target.is_dir() {
+                return check_writable(&key, &target);
+            }
+            let mut check = WritableCheck {
+                key,
+                path: target.to_string_lossy().to_string(),
+                probed_path: Some(target.to_string_lossy().to_string()),
+                writable: false,
+                error: None,
+            };
+            let created: Vec<PathBuf> = target
+                .ancestors()
+                .take_while(|ancestor| !ancestor.exists())
+                .map(Path::to_path_buf)
+                .collect();
+            match fs::create_dir_all(&target) {
+                Ok(()) => check.writable = true,
+                Err(err) => check.error = Some(err.to_string()),
+            }
+            // Innermost first; `remove_dir` refuses anything Pioneer or the
+            // user has since put files into.
+            for dir in &created {
+                let _ = fs::remove_dir(dir);
+            }
+            check
+        })
+        .collect()
+}
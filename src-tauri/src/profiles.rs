@@ -0,0 +1,146 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::atomic::write_atomic;
+use crate::encoding::strip_bom;
+
+/// Under the app config directory; each workflow gets its own subdirectory.
+pub const PROFILES_DIR: &str = "profiles";
+
+#[derive(Serialize, Debug)]
+pub struct ConfigProfile {
+    pub name: String,
+    pub path: String,
+    pub bytes: u64,
+    /// Unix time the profile was last saved.
+    pub modified: Option<u64>,
+}
+
+/// Device names Windows reserves in every directory, whatever the extension.
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Profile names become file names, so they are kept to characters that are
+/// safe on every platform and can never name another directory or a device.
+fn check_profile_name(name: &str) -> Result<(), String> {
+    let valid = !name.trim().is_empty()
+        && name.trim() == name
+        && !name.starts_with('.')
+        && !name.ends_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, ' ' | '_' | '-' | '.'));
+    if !valid {
+        return Err(format!(
+            "Invalid profile name `{name}`: use letters, digits, spaces, `_`, `-`, and `.`"
+        ));
+    }
+    let stem = name.split('.').next().unwrap_or(name).trim_end();
+    if RESERVED_NAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(stem))
+    {
+        return Err(format!(
+            "Invalid profile name `{name}`: `{stem}` is reserved on Windows"
+        ));
+    }
+    Ok(())
+}
+
+fn profile_path(dir: &Path, name: &str) -> Result<PathBuf, String> {
+    check_profile_name(name)?;
+    Ok(dir.join(format!("{name}.json")))
+}
+
+/// Saves `config` as profile `name`, replacing any profile of that name.
+pub fn save_profile(dir: &Path, name: &str, config: &Value) -> Result<ConfigProfile, String> {
+    let path = profile_path(dir, name)?;
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let pretty = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+    write_atomic(&path, pretty).map_err(|e| e.to_string())?;
+    describe(name, &path).ok_or_else(|| format!("Saved profile {} is missing", path.display()))
+}
+
+/// Sorted by name. A workflow with no profiles yet has no directory. Files
+/// whose names could not have been saved as a profile are left out, so every
+/// listed profile can be loaded and deleted by name.
+pub fn list_profiles(dir: &Path) -> Result<Vec<ConfigProfile>, String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.to_string()),
+    };
+    let mut profiles: Vec<ConfigProfile> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                return None;
+            }
+            let name = path.file_stem()?.to_str()?.to_string();
+            check_profile_name(&name).ok()?;
+            describe(&name, &path)
+        })
+        .collect();
+    profiles.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(profiles)
+}
+
+pub fn load_profile(dir: &Path, name: &str) -> Result<Value, String> {
+    let path = profile_path(dir, name)?;
+    let contents =
+        fs::read_to_string(&path).map_err(|_| format!("There is no profile named `{name}`"))?;
+    serde_json::from_str(strip_bom(&contents))
+        .map_err(|e| format!("Profile {} is not valid JSON: {e}", path.display()))
+}
+
+pub fn delete_profile(dir: &Path, name: &str) -> Result<(), String> {
+    let path = profile_path(dir, name)?;
+    match fs::remove_file(&path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            Err(format!("There is no profile named `{name}`"))
+        }
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+fn describe(name: &str, path: &Path) -> Option<ConfigProfile> {
+    let metadata = fs::metadata(path).ok().filter(|m| m.is_file())?;
+    Some(ConfigProfile {
+        name: name.to_string(),
+        path: path.to_string_lossy().to_string(),
+        bytes: metadata.len(),
+        modified: metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|elapsed| elapsed.as_secs()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_profile_name;
+
+    #[test]
+    fn accepts_ordinary_names() {
+        for name in ["default", "DIA 2024", "lab_v1.2", "console", "com10"] {
+            assert!(check_profile_name(name).is_ok(), "{name}");
+        }
+    }
+
+    #[test]
+    fn rejects_unsafe_and_reserved_names() {
+        for name in [
+            "", " padded", ".hidden", "a/b", "..", "CON", "nul", "Aux.old", "com1", "LPT9",
+        ] {
+            assert!(check_profile_name(name).is_err(), "{name}");
+        }
+    }
+}
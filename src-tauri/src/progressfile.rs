@@ -0,0 +1,123 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+
+use crate::preflight::output_directories;
+use crate::RunMode;
+
+/// Written by Pioneer builds that report progress to disk, in the results
+/// directory (SearchDIA) or the library output directory (BuildSpecLib).
+pub const PROGRESS_FILENAME: &str = "progress.log";
+/// Stream label for lines read from the progress file, alongside `stdout`,
+/// `stderr`, and `combined`.
+pub const PROGRESS_STREAM: &str = "progress";
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// Fields of a JSON progress line whose text is matched against stage keywords.
+const TEXT_FIELDS: &[&str] = &["stage", "step", "message"];
+
+/// Which output drives stage and progress events.
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ProgressSource {
+    #[default]
+    Stdout,
+    File,
+    Both,
+}
+
+impl ProgressSource {
+    pub fn uses_file(self) -> bool {
+        matches!(self, ProgressSource::File | ProgressSource::Both)
+    }
+
+    /// Whether lines from `stream` should advance the stage.
+    pub fn drives_stages(self, stream: &str) -> bool {
+        match self {
+            ProgressSource::Stdout => stream != PROGRESS_STREAM,
+            ProgressSource::File => stream == PROGRESS_STREAM,
+            ProgressSource::Both => true,
+        }
+    }
+}
+
+pub fn progress_file_path(mode: RunMode, config: &Value) -> Option<PathBuf> {
+    output_directories(mode, config)
+        .into_iter()
+        .next()
+        .map(|(_, dir)| dir.join(PROGRESS_FILENAME))
+}
+
+/// A JSON line contributes its text fields; anything else is matched as-is.
+pub fn progress_text(line: &str) -> String {
+    match serde_json::from_str::<Value>(line) {
+        Ok(Value::Object(map)) => TEXT_FIELDS
+            .iter()
+            .filter_map(|field| map.get(*field).and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join(" "),
+        _ => line.to_string(),
+    }
+}
+
+/// Follows `path` by polling, sending each complete line labelled
+/// `PROGRESS_STREAM`. Content already in the file when the tail starts belongs
+/// to an earlier run and is skipped; a file that shrinks was rewritten and is
+/// read again from the start. Once `stop` is set the file is read one last
+/// time and the sender dropped.
+pub fn spawn_progress_tail(
+    path: PathBuf,
+    tx: mpsc::Sender<(&'static str, String)>,
+    stop: Arc<AtomicBool>,
+) {
+    std::thread::spawn(move || {
+        let mut offset = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        let mut pending = String::new();
+        loop {
+            let stopping = stop.load(Ordering::SeqCst);
+            if read_new_lines(&path, &mut offset, &mut pending, &tx).is_err() || stopping {
+                break;
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    });
+}
+
+/// `Err` only when the receiver has gone away.
+fn read_new_lines(
+    path: &Path,
+    offset: &mut u64,
+    pending: &mut String,
+    tx: &mpsc::Sender<(&'static str, String)>,
+) -> Result<(), ()> {
+    let Ok(mut file) = File::open(path) else {
+        return Ok(());
+    };
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    if len < *offset {
+        *offset = 0;
+        pending.clear();
+    }
+    if len == *offset || file.seek(SeekFrom::Start(*offset)).is_err() {
+        return Ok(());
+    }
+    let mut chunk = Vec::new();
+    if file.read_to_end(&mut chunk).is_err() {
+        return Ok(());
+    }
+    *offset += chunk.len() as u64;
+    pending.push_str(&String::from_utf8_lossy(&chunk));
+    while let Some(newline) = pending.find('\n') {
+        let line: String = pending.drain(..=newline).collect();
+        let line = line.trim_end_matches(['\n', '\r']);
+        if !line.trim().is_empty() {
+            tx.send((PROGRESS_STREAM, line.to_string()))
+                .map_err(|_| ())?;
+        }
+    }
+    Ok(())
+}
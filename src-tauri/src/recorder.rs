@@ -0,0 +1,131 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+use crate::RunMode;
+
+pub const EVENTS_FILENAME: &str = "events.jsonl";
+
+struct Sink {
+    run_id: String,
+    mode: RunMode,
+    file: File,
+}
+
+/// Appends every event emitted during a run to the run directory's
+/// `events.jsonl`, one `{"event", "payload", "at_ms"}` object per line, so
+/// the exact sequence the UI saw can be replayed later.
+#[derive(Default)]
+pub struct EventRecorder {
+    sinks: Mutex<Vec<Sink>>,
+}
+
+impl EventRecorder {
+    pub fn begin(&self, run_id: &str, mode: RunMode, run_dir: &Path) -> io::Result<()> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(run_dir.join(EVENTS_FILENAME))?;
+        if let Ok(mut sinks) = self.sinks.lock() {
+            sinks.push(Sink {
+                run_id: run_id.to_string(),
+                mode,
+                file,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn end(&self, run_id: &str) {
+        if let Ok(mut sinks) = self.sinks.lock() {
+            sinks.retain(|sink| sink.run_id != run_id);
+        }
+    }
+
+    /// Payloads carrying a `run_id` go to that run only; otherwise a payload's
+    /// `mode` picks the recording runs, and payloads with neither (terminal
+    /// warnings) go to every recording run.
+    pub fn record<S: Serialize>(&self, event: &str, payload: &S) {
+        let Ok(mut sinks) = self.sinks.lock() else {
+            return;
+        };
+        if sinks.is_empty() {
+            return;
+        }
+        let payload = serde_json::to_value(payload).unwrap_or(Value::Null);
+        let run_id = payload.get("run_id").and_then(Value::as_str);
+        let mode = payload
+            .get("mode")
+            .and_then(|mode| serde_json::from_value::<RunMode>(mode.clone()).ok());
+        let at_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or(0);
+        let mut line = json!({ "event": event, "payload": payload, "at_ms": at_ms }).to_string();
+        line.push('\n');
+
+        for sink in sinks.iter_mut() {
+            let matches = match (run_id, mode) {
+                (Some(run_id), _) => sink.run_id == run_id,
+                (None, Some(mode)) => sink.mode == mode,
+                (None, None) => true,
+            };
+            if matches {
+                let _ = sink.file.write_all(line.as_bytes());
+            }
+        }
+    }
+}
+
+/// Stops recording the run when dropped, so every early return from run
+/// setup closes the sink as well as the normal end of the run.
+pub struct Recording {
+    app_handle: AppHandle,
+    run_id: String,
+}
+
+impl Recording {
+    pub fn start(app_handle: &AppHandle, run_id: &str, mode: RunMode, run_dir: &Path) -> Self {
+        if let Err(err) = app_handle
+            .state::<EventRecorder>()
+            .begin(run_id, mode, run_dir)
+        {
+            eprintln!("Failed to record events for {run_id}: {err}");
+        }
+        Self {
+            app_handle: app_handle.clone(),
+            run_id: run_id.to_string(),
+        }
+    }
+}
+
+impl Drop for Recording {
+    fn drop(&mut self) {
+        if let Some(recorder) = self.app_handle.try_state::<EventRecorder>() {
+            recorder.end(&self.run_id);
+        }
+    }
+}
+
+/// Copies a run's recorded events to `dest`, returning how many were written.
+pub fn export_events(run_dir: &Path, dest: &Path) -> Result<usize, String> {
+    let source = run_dir.join(EVENTS_FILENAME);
+    if !source.is_file() {
+        return Err(format!("No events were recorded for {}", run_dir.display()));
+    }
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::copy(&source, dest).map_err(|e| e.to_string())?;
+    let file = File::open(dest).map_err(|e| e.to_string())?;
+    Ok(BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter(|line| !line.trim().is_empty())
+        .count())
+}
@@ -0,0 +1,177 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::diff::join_path;
+use crate::paths::path_keys;
+use crate::RunMode;
+
+/// Placeholders for the well-known path keys; any other path-looking string
+/// becomes `<PATH>`.
+const PLACEHOLDERS: &[(&str, &str)] = &[
+    ("fasta_paths", "FASTA"),
+    ("out_dir", "OUTPUT_DIR"),
+    ("lib_name", "LIBRARY_NAME"),
+    ("new_lib_name", "NEW_LIBRARY_NAME"),
+    ("library_params.calibration_raw_file", "CALIBRATION_FILE"),
+    ("paths.library", "LIBRARY"),
+    ("paths.ms_data", "MS_DATA_DIR"),
+    ("paths.results", "RESULTS_DIR"),
+];
+const GENERIC_PLACEHOLDER: &str = "PATH";
+
+#[derive(Serialize, Debug)]
+pub struct Redaction {
+    pub path: String,
+    pub placeholder: String,
+    pub original: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct RedactedConfig {
+    pub config: Value,
+    /// What each placeholder replaced. Keep this local; only `config` is
+    /// meant to be shared.
+    pub redactions: Vec<Redaction>,
+}
+
+/// Replaces path-valued strings with placeholders, leaving the structure and
+/// every non-path value untouched.
+pub fn redact_config(mode: RunMode, config: &Value) -> RedactedConfig {
+    let mut redacted = config.clone();
+    let mut redactions = Vec::new();
+    let known = path_keys(mode);
+    redact_value(&mut redacted, "", known, &mut redactions);
+    RedactedConfig {
+        config: redacted,
+        redactions,
+    }
+}
+
+fn redact_value(value: &mut Value, path: &str, known: &[&str], redactions: &mut Vec<Redaction>) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                redact_value(child, &join_path(path, key), known, redactions);
+            }
+        }
+        Value::Array(items) => {
+            let numbered = items.len() > 1;
+            for (idx, item) in items.iter_mut().enumerate() {
+                let Value::String(raw) = item else {
+                    redact_value(item, path, known, redactions);
+                    continue;
+                };
+                if let Some(base) = placeholder_base(path, raw, known) {
+                    let placeholder = if numbered {
+                        format!("<{base}_{}>", idx + 1)
+                    } else {
+                        format!("<{base}>")
+                    };
+                    redactions.push(redact(item, path, placeholder));
+                }
+            }
+        }
+        Value::String(raw) => {
+            if let Some(base) = placeholder_base(path, raw, known) {
+                redactions.push(redact(value, path, format!("<{base}>")));
+            }
+        }
+        _ => {}
+    }
+}
+
+fn redact(value: &mut Value, path: &str, placeholder: String) -> Redaction {
+    let original = value.as_str().unwrap_or_default().to_string();
+    *value = Value::String(placeholder.clone());
+    Redaction {
+        path: path.to_string(),
+        placeholder,
+        original,
+    }
+}
+
+fn placeholder_base(path: &str, raw: &str, known: &[&str]) -> Option<&'static str> {
+    if raw.is_empty() {
+        return None;
+    }
+    if known.contains(&path) {
+        return Some(
+            PLACEHOLDERS
+                .iter()
+                .find(|(key, _)| *key == path)
+                .map(|(_, placeholder)| *placeholder)
+                .unwrap_or(GENERIC_PLACEHOLDER),
+        );
+    }
+    looks_like_absolute_path(raw).then_some(GENERIC_PLACEHOLDER)
+}
+
+/// Regex-valued parameters contain slashes too, so only strings anchored like
+/// an absolute or home-relative path are treated as paths.
+fn looks_like_absolute_path(raw: &str) -> bool {
+    let bytes = raw.as_bytes();
+    let drive = bytes.len() >= 3
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && matches!(bytes[2], b'\\' | b'/');
+    let unix = raw.starts_with('/') && raw.len() > 1 && !raw.contains(char::is_whitespace);
+    drive || unix || raw.starts_with("~/") || raw.starts_with("\\\\")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{looks_like_absolute_path, redact_config};
+    use crate::RunMode;
+    use serde_json::json;
+
+    #[test]
+    fn known_keys_get_named_placeholders() {
+        let config = json!({
+            "fasta_paths": ["/data/a.fasta", "/data/b.fasta"],
+            "out_dir": "relative/out",
+            "lib_name": "",
+            "fasta_digest_params": {"cleavage_regex": "[KR][^_|$]"},
+        });
+        let redacted = redact_config(RunMode::BuildSpecLib, &config);
+        assert_eq!(
+            redacted.config,
+            json!({
+                "fasta_paths": ["<FASTA_1>", "<FASTA_2>"],
+                "out_dir": "<OUTPUT_DIR>",
+                "lib_name": "",
+                "fasta_digest_params": {"cleavage_regex": "[KR][^_|$]"},
+            })
+        );
+        let originals: Vec<&str> = redacted
+            .redactions
+            .iter()
+            .map(|r| r.original.as_str())
+            .collect();
+        assert_eq!(
+            originals,
+            ["/data/a.fasta", "/data/b.fasta", "relative/out"]
+        );
+    }
+
+    #[test]
+    fn other_absolute_paths_become_generic() {
+        let config = json!({
+            "paths": {"library": "/libs/x.poin"},
+            "notes": {"extra": "C:\\Users\\me\\run", "tag": "a/b"},
+        });
+        let redacted = redact_config(RunMode::SearchDia, &config);
+        assert_eq!(redacted.config["paths"]["library"], "<LIBRARY>");
+        assert_eq!(redacted.config["notes"]["extra"], "<PATH>");
+        assert_eq!(redacted.config["notes"]["tag"], "a/b");
+    }
+
+    #[test]
+    fn recognises_absolute_path_shapes() {
+        for raw in ["/tmp/x", "C:\\x", "d:/x", "~/x", "\\\\server\\share"] {
+            assert!(looks_like_absolute_path(raw), "{raw}");
+        }
+        for raw in ["/", "relative/x", "/one two", "[KR]/"] {
+            assert!(!looks_like_absolute_path(raw), "{raw}");
+        }
+    }
+}
@@ -0,0 +1,391 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::process::Child;
+#[cfg(windows)]
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::RunMode;
+
+/// Output lines kept per live run for `peek_active_log`: the opening lines,
+/// plus a ring buffer of the most recent ones. Both hold `MAX_PEEK_LINES`,
+/// the most a peek can ask for.
+pub const MAX_PEEK_LINES: usize = 500;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RunState {
+    Running,
+    Paused,
+    Succeeded,
+    Failed,
+    /// Started but never recorded an outcome, e.g. the app quit mid-run.
+    Unknown,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct RunStatus {
+    pub run_id: String,
+    pub mode: RunMode,
+    pub state: RunState,
+    pub pid: Option<u32>,
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct LogPeek {
+    /// `None` when no run is active.
+    pub run_id: Option<String>,
+    pub mode: Option<RunMode>,
+    pub head: Vec<String>,
+    pub tail: Vec<String>,
+    /// The line count used for `head` and `tail`.
+    pub lines: usize,
+    /// Set when more than `MAX_PEEK_LINES` were asked for and `lines` was
+    /// lowered to it.
+    pub clamped: bool,
+}
+
+struct ActiveRun {
+    mode: RunMode,
+    pid: u32,
+    paused: bool,
+    started: Instant,
+    head: Vec<String>,
+    ring: VecDeque<String>,
+}
+
+/// Runs whose Pioneer process is still alive, keyed by run id.
+#[derive(Default)]
+pub struct RunRegistry {
+    runs: Mutex<HashMap<String, ActiveRun>>,
+    /// Runs handed to the frontend whose process has not been spawned yet.
+    starting: Mutex<HashMap<String, RunMode>>,
+    /// The tempdir each run stages its inputs in, from reservation until the
+    /// run is unregistered.
+    temp_dirs: Mutex<HashMap<String, PathBuf>>,
+    cancel_requests: Mutex<HashSet<String>>,
+}
+
+impl RunRegistry {
+    /// Marks a run as starting so it can be cancelled before its process
+    /// exists, and records its tempdir so workspace cleanup leaves it alone.
+    pub fn reserve(&self, run_id: &str, mode: RunMode, temp_dir: &Path) {
+        if let Ok(mut starting) = self.starting.lock() {
+            starting.insert(run_id.to_string(), mode);
+        }
+        if let Ok(mut temp_dirs) = self.temp_dirs.lock() {
+            temp_dirs.insert(run_id.to_string(), temp_dir.to_path_buf());
+        }
+    }
+
+    pub fn register(&self, run_id: &str, mode: RunMode, pid: u32) {
+        if let Ok(mut runs) = self.runs.lock() {
+            runs.insert(
+                run_id.to_string(),
+                ActiveRun {
+                    mode,
+                    pid,
+                    paused: false,
+                    started: Instant::now(),
+                    head: Vec::new(),
+                    ring: VecDeque::with_capacity(MAX_PEEK_LINES),
+                },
+            );
+        }
+        // Removed only after the insert so a concurrent cancel always finds
+        // the run in one of the two sets.
+        if let Ok(mut starting) = self.starting.lock() {
+            starting.remove(run_id);
+        }
+    }
+
+    pub fn unregister(&self, run_id: &str) {
+        if let Ok(mut runs) = self.runs.lock() {
+            runs.remove(run_id);
+        }
+        if let Ok(mut starting) = self.starting.lock() {
+            starting.remove(run_id);
+        }
+        if let Ok(mut temp_dirs) = self.temp_dirs.lock() {
+            temp_dirs.remove(run_id);
+        }
+        if let Ok(mut requests) = self.cancel_requests.lock() {
+            requests.remove(run_id);
+        }
+    }
+
+    /// Records a cancellation for `run_process` to act on, whether or not the
+    /// process has been spawned yet.
+    pub fn request_cancel(&self, run_id: &str) -> Result<(), String> {
+        if !self.is_active(run_id) {
+            return Err(format!("Run `{run_id}` is not running"));
+        }
+        self.cancel_requests
+            .lock()
+            .map_err(|_| "Run registry lock poisoned".to_string())?
+            .insert(run_id.to_string());
+        Ok(())
+    }
+
+    /// Starting or running; either way its run directory is in use.
+    pub fn is_active(&self, run_id: &str) -> bool {
+        let starting = self
+            .starting
+            .lock()
+            .map(|starting| starting.contains_key(run_id))
+            .unwrap_or(false);
+        starting
+            || self
+                .runs
+                .lock()
+                .map(|runs| runs.contains_key(run_id))
+                .unwrap_or(false)
+    }
+
+    /// Whether `dir` is the tempdir of a starting or running run.
+    pub fn uses_temp_dir(&self, dir: &Path) -> bool {
+        self.temp_dirs
+            .lock()
+            .map(|temp_dirs| temp_dirs.values().any(|temp_dir| temp_dir == dir))
+            .unwrap_or(false)
+    }
+
+    pub fn any_active(&self) -> bool {
+        let starting = self
+            .starting
+            .lock()
+            .map(|starting| !starting.is_empty())
+            .unwrap_or(false);
+        starting
+            || self
+                .runs
+                .lock()
+                .map(|runs| !runs.is_empty())
+                .unwrap_or(false)
+    }
+
+    /// The active run of `mode`: the most recently started live one, else one
+    /// that is still starting.
+    pub fn active_run_for_mode(&self, mode: RunMode) -> Option<String> {
+        let running = self.runs.lock().ok().and_then(|runs| {
+            runs.iter()
+                .filter(|(_, run)| run.mode == mode)
+                .max_by_key(|(_, run)| run.started)
+                .map(|(run_id, _)| run_id.clone())
+        });
+        running.or_else(|| {
+            self.starting.lock().ok().and_then(|starting| {
+                starting
+                    .iter()
+                    .find(|(_, starting_mode)| **starting_mode == mode)
+                    .map(|(run_id, _)| run_id.clone())
+            })
+        })
+    }
+
+    pub fn cancel_requested(&self, run_id: &str) -> bool {
+        self.cancel_requests
+            .lock()
+            .map(|requests| requests.contains(run_id))
+            .unwrap_or(false)
+    }
+
+    pub fn record_line(&self, run_id: &str, line: &str) {
+        let Ok(mut runs) = self.runs.lock() else {
+            return;
+        };
+        let Some(run) = runs.get_mut(run_id) else {
+            return;
+        };
+        if run.head.len() < MAX_PEEK_LINES {
+            run.head.push(line.to_string());
+        }
+        if run.ring.len() == MAX_PEEK_LINES {
+            run.ring.pop_front();
+        }
+        run.ring.push_back(line.to_string());
+    }
+
+    /// First and last `n` lines (at most `MAX_PEEK_LINES`) of the most
+    /// recently started live run.
+    pub fn peek_active_log(&self, n: usize) -> LogPeek {
+        let lines = n.min(MAX_PEEK_LINES);
+        let empty = LogPeek {
+            lines,
+            clamped: lines < n,
+            ..LogPeek::default()
+        };
+        let Ok(runs) = self.runs.lock() else {
+            return empty;
+        };
+        let Some((run_id, run)) = runs.iter().max_by_key(|(_, run)| run.started) else {
+            return empty;
+        };
+        let skip = run.ring.len().saturating_sub(lines);
+        LogPeek {
+            run_id: Some(run_id.clone()),
+            mode: Some(run.mode),
+            head: run.head.iter().take(lines).cloned().collect(),
+            tail: run.ring.iter().skip(skip).cloned().collect(),
+            ..empty
+        }
+    }
+
+    pub fn status(&self, run_id: &str) -> Option<RunStatus> {
+        let runs = self.runs.lock().ok()?;
+        runs.get(run_id).map(|run| RunStatus {
+            run_id: run_id.to_string(),
+            mode: run.mode,
+            state: if run.paused {
+                RunState::Paused
+            } else {
+                RunState::Running
+            },
+            pid: Some(run.pid),
+        })
+    }
+
+    /// Suspends or resumes the run's process. The lock is held while
+    /// signalling so the process cannot be unregistered (and its pid reused)
+    /// in between.
+    pub fn set_paused(&self, run_id: &str, paused: bool) -> Result<RunStatus, String> {
+        let mut runs = self
+            .runs
+            .lock()
+            .map_err(|_| "Run registry lock poisoned".to_string())?;
+        let run = runs
+            .get_mut(run_id)
+            .ok_or_else(|| format!("Run `{run_id}` is not running"))?;
+        if run.paused != paused {
+            signal_process(run.pid, paused)?;
+            run.paused = paused;
+        }
+        Ok(RunStatus {
+            run_id: run_id.to_string(),
+            mode: run.mode,
+            state: if paused {
+                RunState::Paused
+            } else {
+                RunState::Running
+            },
+            pid: Some(run.pid),
+        })
+    }
+}
+
+/// Runs are spawned as the leader of their own process group (see
+/// `pioneer_command`), so signalling the group reaches Pioneer even when a
+/// command wrapper sits between it and the GUI.
+#[cfg(unix)]
+fn signal_process(pid: u32, pause: bool) -> Result<(), String> {
+    let signal = if pause { libc::SIGSTOP } else { libc::SIGCONT };
+    signal_group(pid, signal)
+}
+
+#[cfg(unix)]
+fn signal_group(pid: u32, signal: libc::c_int) -> Result<(), String> {
+    let pgid = libc::pid_t::try_from(pid).map_err(|e| e.to_string())?;
+    // SAFETY: kill has no memory-safety preconditions; it only sends a signal.
+    // A negative pid addresses the whole process group.
+    if unsafe { libc::kill(-pgid, signal) } == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error().to_string())
+    }
+}
+
+/// Kills a run's whole process tree, so a wrapped Pioneer is not orphaned
+/// still holding the output pipes open.
+#[cfg(unix)]
+pub fn kill_run(child: &mut Child) {
+    if signal_group(child.id(), libc::SIGKILL).is_err() {
+        let _ = child.kill();
+    }
+}
+
+#[cfg(windows)]
+pub fn kill_run(child: &mut Child) {
+    let killed_tree = std::process::Command::new("taskkill")
+        .args(["/T", "/F", "/PID", &child.id().to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success());
+    if !killed_tree {
+        let _ = child.kill();
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn kill_run(child: &mut Child) {
+    let _ = child.kill();
+}
+
+/// Windows has no supported per-process suspend; suspending every thread
+/// through the debug APIs is racy for a multithreaded Julia process, so
+/// pausing is reported as unsupported rather than attempted.
+#[cfg(not(unix))]
+fn signal_process(_pid: u32, _pause: bool) -> Result<(), String> {
+    Err("Pausing runs is not supported on this platform".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RunRegistry, MAX_PEEK_LINES};
+    use crate::RunMode;
+    use std::path::Path;
+
+    #[test]
+    fn cancel_before_spawn_survives_registration() {
+        let registry = RunRegistry::default();
+        assert!(registry.request_cancel("run-1").is_err());
+
+        registry.reserve("run-1", RunMode::SearchDia, Path::new("/tmp/pioneer-gui-1"));
+        assert!(registry.uses_temp_dir(Path::new("/tmp/pioneer-gui-1")));
+        assert!(registry.is_active("run-1"));
+        assert_eq!(
+            registry.active_run_for_mode(RunMode::SearchDia).as_deref(),
+            Some("run-1")
+        );
+        registry
+            .request_cancel("run-1")
+            .expect("cancel a starting run");
+
+        registry.register("run-1", RunMode::SearchDia, 1);
+        assert!(registry.cancel_requested("run-1"));
+        assert!(registry.is_active("run-1"));
+
+        registry.unregister("run-1");
+        assert!(!registry.is_active("run-1"));
+        assert!(!registry.cancel_requested("run-1"));
+        assert!(!registry.uses_temp_dir(Path::new("/tmp/pioneer-gui-1")));
+        assert!(!registry.any_active());
+    }
+
+    #[test]
+    fn peek_clamps_and_keeps_head_and_tail() {
+        let registry = RunRegistry::default();
+        registry.register("run-1", RunMode::BuildSpecLib, 1);
+        for index in 0..MAX_PEEK_LINES + 10 {
+            registry.record_line("run-1", &index.to_string());
+        }
+        let peek = registry.peek_active_log(2);
+        assert_eq!(peek.head, ["0", "1"]);
+        assert_eq!(
+            peek.tail,
+            [
+                (MAX_PEEK_LINES + 8).to_string(),
+                (MAX_PEEK_LINES + 9).to_string()
+            ]
+        );
+        assert!(!peek.clamped);
+
+        let peek = registry.peek_active_log(MAX_PEEK_LINES + 1);
+        assert!(peek.clamped);
+        assert_eq!(peek.lines, MAX_PEEK_LINES);
+        assert_eq!(peek.tail.len(), MAX_PEEK_LINES);
+        assert_eq!(peek.tail[0], "10");
+    }
+}
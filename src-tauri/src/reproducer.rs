@@ -0,0 +1,407 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Seek, Write};
+use std::path::Path;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::checksums::{hash_inputs, InputHashes};
+use crate::diff::lookup;
+use crate::redact::redact_config;
+use crate::resume::RESUME_FLAG;
+use crate::runs::{read_metadata, RunMetadata, METADATA_FILENAME};
+use crate::workspace::list_dir;
+use crate::RunMode;
+
+const LOG_DIR: &str = "logs";
+const INPUTS_DIR: &str = "inputs";
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(default)]
+pub struct ReproducerOptions {
+    /// Replace local paths in the config and metadata with placeholders.
+    pub redact_paths: bool,
+    /// Bundle the FASTA files a build read. They are usually small.
+    pub include_fasta: bool,
+    /// Bundle the spectral library and MS data too. Off by default: these run
+    /// to many gigabytes, and the bundle records their hashes either way.
+    pub include_large_inputs: bool,
+}
+
+impl Default for ReproducerOptions {
+    fn default() -> Self {
+        Self {
+            redact_paths: false,
+            include_fasta: true,
+            include_large_inputs: false,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct ReproducerReport {
+    pub path: String,
+    pub entries: Vec<String>,
+    pub bytes: u64,
+    pub redacted: bool,
+    /// Input paths that were requested for the bundle but could not be read.
+    pub missing_inputs: Vec<String>,
+}
+
+/// Writes a zip holding everything needed to rerun `run_dir`: the config
+/// snapshot, the run metadata (environment and input hashes included), the
+/// command line, the log, and a generated README. Files are streamed into the
+/// archive one at a time, so bundling inputs does not load them into memory.
+pub fn export_reproducer(
+    run_dir: &Path,
+    dest: &Path,
+    options: ReproducerOptions,
+) -> Result<ReproducerReport, String> {
+    let mut metadata = read_metadata(run_dir)?;
+    let contents = fs::read_to_string(&metadata.config_path)
+        .map_err(|e| format!("Cannot read the run's config snapshot: {e}"))?;
+    let config: Value = serde_json::from_str(&contents).map_err(|e| e.to_string())?;
+    let mode = metadata.mode;
+
+    // Runs that did not record hashes still get a names-and-sizes record.
+    let input_hashes = metadata
+        .input_hashes
+        .clone()
+        .unwrap_or_else(|| hash_inputs(mode, &config, false));
+
+    let config_name = mode.config_filename().to_string();
+    let log_name = Path::new(&metadata.log_path)
+        .file_name()
+        .map(|name| format!("{LOG_DIR}/{}", name.to_string_lossy()))
+        .unwrap_or_else(|| format!("{LOG_DIR}/pioneer.log"));
+    let log_source = metadata.log_path.clone();
+    let config_source = std::mem::replace(&mut metadata.config_path, config_name.clone());
+    metadata.log_path = log_name.clone();
+
+    let mut metadata_out = serde_json::to_value(&metadata).map_err(|e| e.to_string())?;
+    let mut hashes_out = serde_json::to_value(&input_hashes).map_err(|e| e.to_string())?;
+    // Substrings of the log to replace, since its lines mention paths inside
+    // longer text rather than as whole values.
+    let mut log_redactions: Vec<(String, String)> = Vec::new();
+    let config_out = if options.redact_paths {
+        let redacted = redact_config(mode, &config);
+        log_redactions.push((config_source, config_name.clone()));
+        for redaction in &redacted.redactions {
+            log_redactions.push((redaction.original.clone(), redaction.placeholder.clone()));
+            replace_strings(
+                &mut metadata_out,
+                &redaction.original,
+                &redaction.placeholder,
+            );
+            replace_strings(&mut hashes_out, &redaction.original, &redaction.placeholder);
+        }
+        if let Some(binary) = metadata_out.pointer_mut("/environment/pioneer_path") {
+            if let Value::String(original) = binary {
+                log_redactions.push((original.clone(), "<PIONEER_BINARY>".into()));
+            }
+            *binary = Value::String("<PIONEER_BINARY>".into());
+        }
+        // Longest first, so a path is never half-replaced by a placeholder
+        // for its parent directory.
+        log_redactions.retain(|(original, _)| !original.is_empty());
+        log_redactions.sort_by_key(|(original, _)| std::cmp::Reverse(original.len()));
+        redacted.config
+    } else {
+        config.clone()
+    };
+
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let file = File::create(dest).map_err(|e| e.to_string())?;
+    let mut bundle = Bundle::new(file);
+    let pretty = |value: &Value| serde_json::to_string_pretty(value).unwrap_or_default();
+
+    let command = command_line(mode, &config_name, metadata.resumed_from.is_some());
+    bundle.add_bytes(
+        "README.md",
+        readme(&metadata, &input_hashes, &command, options).as_bytes(),
+    )?;
+    bundle.add_bytes(&config_name, pretty(&config_out).as_bytes())?;
+    bundle.add_bytes(METADATA_FILENAME, pretty(&metadata_out).as_bytes())?;
+    bundle.add_bytes("input_hashes.json", pretty(&hashes_out).as_bytes())?;
+    bundle.add_bytes("command.txt", format!("{command}\n").as_bytes())?;
+    if Path::new(&log_source).is_file() {
+        if log_redactions.is_empty() {
+            bundle.add_file(&log_name, Path::new(&log_source))?;
+        } else {
+            bundle.add_redacted_file(&log_name, Path::new(&log_source), &log_redactions)?;
+        }
+    }
+
+    let mut missing_inputs = Vec::new();
+    let mut inputs: Vec<String> = Vec::new();
+    if options.include_fasta && mode == RunMode::BuildSpecLib {
+        if let Some(paths) = config.get("fasta_paths").and_then(Value::as_array) {
+            inputs.extend(paths.iter().filter_map(Value::as_str).map(str::to_string));
+        }
+    }
+    if options.include_large_inputs && mode == RunMode::SearchDia {
+        for key in ["paths.library", "paths.ms_data"] {
+            if let Some(path) = lookup(&config, key).and_then(Value::as_str) {
+                inputs.push(path.to_string());
+            }
+        }
+    }
+    // Numbered in config order, since inputs from different directories
+    // often share a file name.
+    for (index, input) in inputs.into_iter().enumerate() {
+        let path = Path::new(&input);
+        let Some(name) = path.file_name() else {
+            missing_inputs.push(input);
+            continue;
+        };
+        let entry = format!("{INPUTS_DIR}/{}_{}", index + 1, name.to_string_lossy());
+        if path.is_dir() {
+            bundle.add_dir(&entry, path)?;
+        } else if path.is_file() {
+            bundle.add_file(&entry, path)?;
+        } else {
+            missing_inputs.push(input);
+        }
+    }
+
+    let entries = bundle.finish()?;
+    let bytes = fs::metadata(dest).map(|m| m.len()).unwrap_or(0);
+    Ok(ReproducerReport {
+        path: dest.to_string_lossy().to_string(),
+        entries,
+        bytes,
+        redacted: options.redact_paths,
+        missing_inputs,
+    })
+}
+
+struct Bundle<W: Write + Seek> {
+    zip: ZipWriter<W>,
+    entries: Vec<String>,
+}
+
+impl<W: Write + Seek> Bundle<W> {
+    fn new(writer: W) -> Self {
+        Self {
+            zip: ZipWriter::new(writer),
+            entries: Vec::new(),
+        }
+    }
+
+    fn start(&mut self, name: &str, size: u64) -> Result<(), String> {
+        let options = FileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .large_file(size >= u32::MAX as u64);
+        self.zip
+            .start_file(name, options)
+            .map_err(|e| format!("{name}: {e}"))?;
+        self.entries.push(name.to_string());
+        Ok(())
+    }
+
+    fn add_bytes(&mut self, name: &str, bytes: &[u8]) -> Result<(), String> {
+        self.start(name, bytes.len() as u64)?;
+        self.zip
+            .write_all(bytes)
+            .map_err(|e| format!("{name}: {e}"))
+    }
+
+    fn add_file(&mut self, name: &str, path: &Path) -> Result<(), String> {
+        let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        let mut file = File::open(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        self.start(name, size)?;
+        io::copy(&mut file, &mut self.zip).map_err(|e| format!("{name}: {e}"))?;
+        Ok(())
+    }
+
+    /// Copies `path` line by line with each `(original, placeholder)` pair
+    /// substituted. Bytes that are not UTF-8 are replaced.
+    fn add_redacted_file(
+        &mut self,
+        name: &str,
+        path: &Path,
+        redactions: &[(String, String)],
+    ) -> Result<(), String> {
+        let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        let file = File::open(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        self.start(name, size)?;
+        let mut reader = BufReader::new(file);
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            let read = reader
+                .read_until(b'\n', &mut line)
+                .map_err(|e| format!("{}: {e}", path.display()))?;
+            if read == 0 {
+                return Ok(());
+            }
+            let redacted = redact_line(&String::from_utf8_lossy(&line), redactions);
+            self.zip
+                .write_all(redacted.as_bytes())
+                .map_err(|e| format!("{name}: {e}"))?;
+        }
+    }
+
+    /// Directories (Bruker `.d` runs, `.poin` libraries) are added file by
+    /// file under `name/`.
+    fn add_dir(&mut self, name: &str, dir: &Path) -> Result<(), String> {
+        let mut children = list_dir(dir);
+        children.sort();
+        for child in children {
+            let Some(child_name) = child.file_name() else {
+                continue;
+            };
+            let entry = format!("{name}/{}", child_name.to_string_lossy());
+            if child.is_dir() {
+                self.add_dir(&entry, &child)?;
+            } else {
+                self.add_file(&entry, &child)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<Vec<String>, String> {
+        let mut writer = self.zip.finish().map_err(|e| e.to_string())?;
+        writer.flush().map_err(|e| e.to_string())?;
+        Ok(self.entries)
+    }
+}
+
+fn replace_strings(value: &mut Value, original: &str, placeholder: &str) {
+    match value {
+        Value::String(raw) if raw == original => *raw = placeholder.to_string(),
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| replace_strings(item, original, placeholder)),
+        Value::Object(map) => map
+            .values_mut()
+            .for_each(|child| replace_strings(child, original, placeholder)),
+        _ => {}
+    }
+}
+
+fn redact_line(line: &str, redactions: &[(String, String)]) -> String {
+    redactions
+        .iter()
+        .fold(line.to_string(), |line, (original, placeholder)| {
+            line.replace(original, placeholder)
+        })
+}
+
+fn command_line(mode: RunMode, config_name: &str, resumed: bool) -> String {
+    let mut command = format!("pioneer {}", mode.subcommand());
+    if resumed {
+        command.push(' ');
+        command.push_str(RESUME_FLAG);
+    }
+    command.push(' ');
+    command.push_str(config_name);
+    command
+}
+
+fn readme(
+    metadata: &RunMetadata,
+    hashes: &InputHashes,
+    command: &str,
+    options: ReproducerOptions,
+) -> String {
+    let mut readme = format!(
+        "# Pioneer {} run {}\n\n",
+        metadata.mode.label(),
+        metadata.run_id
+    );
+    if let Some(label) = &metadata.label {
+        readme.push_str(&format!("Label: {label}\n\n"));
+    }
+    let outcome = match metadata.success {
+        Some(true) => "succeeded",
+        Some(false) => "failed",
+        None => "did not record an outcome",
+    };
+    readme.push_str(&format!(
+        "Started at Unix time {}; the run {outcome}.\n\n",
+        metadata.started_at
+    ));
+
+    if let Some(environment) = &metadata.environment {
+        readme.push_str("## Environment\n\n");
+        readme.push_str(&format!(
+            "- Pioneer: {}\n",
+            environment
+                .pioneer_version
+                .as_deref()
+                .unwrap_or("unknown version")
+        ));
+        readme.push_str(&format!("- Pioneer GUI: {}\n", environment.gui_version));
+        readme.push_str(&format!(
+            "- OS: {} {} ({})\n",
+            environment.os,
+            environment.os_version.as_deref().unwrap_or(""),
+            environment.arch
+        ));
+        if let Some(cpu) = &environment.cpu_model {
+            readme.push_str(&format!("- CPU: {cpu}\n"));
+        }
+        readme.push('\n');
+    }
+
+    readme.push_str("## Reproducing\n\n");
+    readme.push_str(&format!("```\n{command}\n```\n\n"));
+    if options.redact_paths {
+        readme.push_str("Paths in the config were replaced with placeholders such as `<FASTA>`; point them at your copies of the inputs first.\n\n");
+    } else {
+        readme.push_str(
+            "Adjust the paths in the config to where the inputs live on your machine.\n\n",
+        );
+    }
+
+    readme.push_str("## Inputs\n\n");
+    readme.push_str(
+        "Check your copies against these before running (`input_hashes.json` has the details):\n\n",
+    );
+    for input in &hashes.inputs {
+        let scope = if input.content_hashed {
+            "content"
+        } else {
+            "names and sizes"
+        };
+        readme.push_str(&format!(
+            "- `{}`: {} file(s), {} bytes, sha256 `{}` ({scope})\n",
+            input.key, input.files, input.bytes, input.sha256
+        ));
+    }
+    for error in &hashes.errors {
+        readme.push_str(&format!("- not hashed: {error}\n"));
+    }
+    readme.push_str(&format!(
+        "\nFiles under `{INPUTS_DIR}/` are bundled copies; inputs not listed there were left out of the bundle.\n"
+    ));
+    readme
+}
+
+#[cfg(test)]
+mod tests {
+    use super::redact_line;
+
+    #[test]
+    fn log_lines_get_the_config_placeholders() {
+        let redactions = [
+            (
+                "/data/fasta/human.fasta".to_string(),
+                "<FASTA_1>".to_string(),
+            ),
+            ("/data/fasta".to_string(), "<FASTA_DIR>".to_string()),
+        ];
+        assert_eq!(
+            redact_line(
+                "Reading /data/fasta/human.fasta from /data/fasta\n",
+                &redactions
+            ),
+            "Reading <FASTA_1> from <FASTA_DIR>\n"
+        );
+    }
+}
@@ -0,0 +1,152 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::Command as StdCommand;
+
+use crate::diff::lookup;
+use crate::events;
+use crate::recorder::EVENTS_FILENAME;
+use crate::runs::{list_runs, RunMetadata};
+use crate::workspace::list_dir;
+use crate::{output_within, FetchControl, RunMode};
+
+/// Flag Pioneer would accept to pick up a search from the intermediate files
+/// already in its results directory. Only passed when `search --help` lists it.
+pub const RESUME_FLAG: &str = "--resume";
+
+/// Where an interrupted search left off and what resuming it skips.
+#[derive(Serialize, Clone, Debug)]
+pub struct ResumePlan {
+    /// The interrupted run whose results directory is picked up.
+    pub previous_run_id: String,
+    pub results_dir: String,
+    /// Stage the previous run was in when it stopped; the resumed run starts
+    /// its progress there.
+    pub resume_stage: String,
+    /// Stages the previous run finished, which the resumed run will not repeat.
+    pub skipped_stages: Vec<String>,
+}
+
+/// Whether the installed Pioneer advertises a resume flag for `mode`. `Err`
+/// says why the help text could not be read at all, e.g. a timeout.
+fn supports_resume(pioneer: &Path, mode: RunMode) -> Result<bool, String> {
+    let mut command = StdCommand::new(pioneer);
+    command.arg(mode.subcommand()).arg("--help");
+    let output = output_within(command, &FetchControl::probe()).map_err(|e| e.to_string())?;
+    Ok(
+        String::from_utf8_lossy(&output.stdout).contains(RESUME_FLAG)
+            || String::from_utf8_lossy(&output.stderr).contains(RESUME_FLAG),
+    )
+}
+
+/// Why a run asking to resume cannot, if it cannot: only SearchDIA resumes,
+/// and only with a Pioneer build that supports it.
+pub fn check_resumable(pioneer: &Path, mode: RunMode) -> Result<(), String> {
+    if mode != RunMode::SearchDia {
+        return Err("Only SearchDIA runs can be resumed".into());
+    }
+    match supports_resume(pioneer, mode) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(format!(
+            "This Pioneer build does not support resuming (`{RESUME_FLAG}` is not listed in `pioneer search --help`)"
+        )),
+        Err(err) => Err(format!(
+            "Cannot tell whether this Pioneer build supports resuming, so the run was not resumed: `pioneer search --help` failed: {err}"
+        )),
+    }
+}
+
+/// Finds the newest unfinished or failed search that wrote to the same
+/// results directory as `config`, and works out from its recorded progress
+/// events which stages it completed. Fails with a reason when the results
+/// directory holds nothing to resume from.
+pub fn plan_resume(
+    runs_root: &Path,
+    config: &Value,
+    is_active: impl Fn(&str) -> bool,
+) -> Result<ResumePlan, String> {
+    let results = lookup(config, "paths.results")
+        .and_then(Value::as_str)
+        .ok_or_else(|| "Resuming needs `paths.results` to be set".to_string())?;
+    let results_dir = Path::new(results);
+    if !results_dir.is_dir() {
+        return Err(format!(
+            "Cannot resume: results directory {results} does not exist"
+        ));
+    }
+    if list_dir(results_dir).is_empty() {
+        return Err(format!(
+            "Cannot resume: results directory {results} holds no intermediate files"
+        ));
+    }
+
+    let previous = list_runs(runs_root)
+        .into_iter()
+        .filter(|run| run.mode == RunMode::SearchDia && run.success != Some(true))
+        .filter(|run| !is_active(&run.run_id))
+        .find(|run| results_dir_of(run).as_deref() == Some(results))
+        .ok_or_else(|| {
+            format!("Cannot resume: no interrupted or failed search wrote to {results}")
+        })?;
+
+    let last_stage = last_recorded_stage(&runs_root.join(&previous.run_id)).ok_or_else(|| {
+        format!(
+            "Cannot resume: run {} recorded no progress to resume from",
+            previous.run_id
+        )
+    })?;
+    let stages = RunMode::SearchDia.stage_sequence();
+    let resume_index = stages
+        .iter()
+        .position(|stage| stage.key == last_stage)
+        .unwrap_or(0);
+    // Nothing before the first search stage leaves intermediate results.
+    let first_search = stages
+        .iter()
+        .position(|stage| stage.key == "presearch")
+        .unwrap_or(0);
+    if resume_index <= first_search {
+        return Err(format!(
+            "Cannot resume: run {} stopped before any search stage completed",
+            previous.run_id
+        ));
+    }
+
+    Ok(ResumePlan {
+        previous_run_id: previous.run_id,
+        results_dir: results.to_string(),
+        resume_stage: stages[resume_index].key.to_string(),
+        skipped_stages: stages[first_search..resume_index]
+            .iter()
+            .map(|stage| stage.key.to_string())
+            .collect(),
+    })
+}
+
+fn results_dir_of(run: &RunMetadata) -> Option<String> {
+    let contents = fs::read_to_string(&run.config_path).ok()?;
+    let config: Value = serde_json::from_str(&contents).ok()?;
+    lookup(&config, "paths.results")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+/// The stage key of the last `pioneer-progress` event in the run's
+/// `events.jsonl`.
+fn last_recorded_stage(run_dir: &Path) -> Option<String> {
+    let file = File::open(run_dir.join(EVENTS_FILENAME)).ok()?;
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str::<Value>(&line).ok())
+        .filter(|entry| entry.get("event").and_then(Value::as_str) == Some(events::PROGRESS))
+        .filter_map(|entry| {
+            entry
+                .pointer("/payload/stage_key")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        })
+        .last()
+}
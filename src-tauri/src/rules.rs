@@ -0,0 +1,219 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use tauri::PathResolver;
+
+use crate::diff::lookup;
+use crate::encoding::strip_bom;
+use crate::RunMode;
+
+pub const RULES_FILENAME: &str = "parameter_rules.json";
+
+/// A key that is set, either to a specific value or (without `equals`) to
+/// anything other than `false`, `null`, `0`, `""`, or an empty list.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Condition {
+    pub path: String,
+    #[serde(default)]
+    pub equals: Option<Value>,
+}
+
+impl Condition {
+    fn holds(&self, config: &Value) -> bool {
+        let Some(value) = lookup(config, &self.path) else {
+            return false;
+        };
+        match &self.equals {
+            Some(expected) => values_equal(value, expected),
+            None => is_set(value),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match &self.equals {
+            Some(expected) => format!("`{}` is {expected}", self.path),
+            None => format!("`{}` is set", self.path),
+        }
+    }
+}
+
+/// When `if` holds, `requires` must hold too and `conflicts` must not.
+/// `min_version` and `max_version` (inclusive) limit the rule to the Pioneer
+/// releases it describes; it also applies when the version is unknown.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ParameterRule {
+    pub mode: RunMode,
+    #[serde(rename = "if")]
+    pub when: Condition,
+    #[serde(default)]
+    pub requires: Option<Condition>,
+    #[serde(default)]
+    pub conflicts: Option<Condition>,
+    /// Explanation shown with a violation, in place of the generated one.
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub min_version: Option<String>,
+    #[serde(default)]
+    pub max_version: Option<String>,
+}
+
+/// `parameter_rules.json`: rules added to (or, with `replaceDefaults`,
+/// replacing) the built-in set.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct RulesFile {
+    replace_defaults: bool,
+    rules: Vec<ParameterRule>,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RuleKind {
+    Requires,
+    Conflicts,
+}
+
+#[derive(Serialize, Debug)]
+pub struct RuleViolation {
+    pub kind: RuleKind,
+    pub path: String,
+    pub other_path: String,
+    pub message: String,
+}
+
+/// Constraints between Pioneer parameters that type and range checks cannot
+/// see. Kept to combinations where one setting silently does nothing (or
+/// cannot work) without the other.
+fn default_rules() -> Vec<ParameterRule> {
+    let rules = json!([
+        {
+            "mode": "searchDia",
+            "if": { "path": "global.huber_override.override_huber_delta_fit", "equals": true },
+            "requires": { "path": "global.huber_override.huber_delta" },
+            "message": "Overriding the Huber delta fit needs a non-zero `global.huber_override.huber_delta`"
+        },
+        {
+            "mode": "searchDia",
+            "if": { "path": "optimization.deconvolution.reg_type", "equals": "none" },
+            "conflicts": { "path": "optimization.deconvolution.lambda" },
+            "message": "`optimization.deconvolution.lambda` has no effect while `reg_type` is \"none\""
+        },
+        {
+            "mode": "buildSpecLib",
+            "if": { "path": "library_params.auto_detect_frag_bounds", "equals": true },
+            "requires": { "path": "library_params.calibration_raw_file" },
+            "message": "Detecting fragment bounds automatically needs `library_params.calibration_raw_file`"
+        }
+    ]);
+    serde_json::from_value(rules).expect("valid built-in parameter rules")
+}
+
+/// The built-in rules plus any from `parameter_rules.json`. A rules file that
+/// cannot be parsed is an error rather than being skipped.
+pub fn load_rules(resolver: &PathResolver) -> Result<Vec<ParameterRule>, String> {
+    let Some(path) = resolver
+        .app_config_dir()
+        .map(|dir| dir.join(RULES_FILENAME))
+        .filter(|path| path.is_file())
+    else {
+        return Ok(default_rules());
+    };
+    let contents = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let file: RulesFile = serde_json::from_str(strip_bom(&contents))
+        .map_err(|e| format!("Invalid {}: {e}", path.display()))?;
+    let mut rules = if file.replace_defaults {
+        Vec::new()
+    } else {
+        default_rules()
+    };
+    rules.extend(file.rules);
+    Ok(rules)
+}
+
+pub fn check_parameter_rules(
+    mode: RunMode,
+    config: &Value,
+    rules: &[ParameterRule],
+    binary_version: Option<&str>,
+) -> Vec<RuleViolation> {
+    let version = binary_version.and_then(parse_version);
+    let mut violations = Vec::new();
+    for rule in rules {
+        if rule.mode != mode || !applies_to(rule, version.as_deref()) || !rule.when.holds(config) {
+            continue;
+        }
+        let broken = [
+            (RuleKind::Requires, rule.requires.as_ref(), false),
+            (RuleKind::Conflicts, rule.conflicts.as_ref(), true),
+        ];
+        for (kind, other, violated_when_holds) in broken {
+            let Some(other) = other else {
+                continue;
+            };
+            if other.holds(config) != violated_when_holds {
+                continue;
+            }
+            let message = rule.message.clone().unwrap_or_else(|| match kind {
+                RuleKind::Requires => format!(
+                    "{} requires that {}",
+                    rule.when.describe(),
+                    other.describe()
+                ),
+                RuleKind::Conflicts => format!(
+                    "{} conflicts with {}",
+                    rule.when.describe(),
+                    other.describe()
+                ),
+            });
+            violations.push(RuleViolation {
+                kind,
+                path: rule.when.path.clone(),
+                other_path: other.path.clone(),
+                message,
+            });
+        }
+    }
+    violations
+}
+
+fn applies_to(rule: &ParameterRule, version: Option<&[u64]>) -> bool {
+    let Some(version) = version else {
+        return true;
+    };
+    let bound = |raw: &Option<String>| raw.as_deref().and_then(parse_version);
+    bound(&rule.min_version).is_none_or(|min| version >= min.as_slice())
+        && bound(&rule.max_version).is_none_or(|max| version <= max.as_slice())
+}
+
+/// Numeric components of the first `X.Y[.Z…]` in `raw`, so "Pioneer v0.3.1"
+/// parses too.
+fn parse_version(raw: &str) -> Option<Vec<u64>> {
+    let pattern = Regex::new(r"\d+(?:\.\d+)+").expect("valid version regex");
+    let found = pattern.find(raw)?;
+    found
+        .as_str()
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect()
+}
+
+fn is_set(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(set) => *set,
+        Value::Number(number) => number.as_f64().is_some_and(|n| n != 0.0),
+        Value::String(text) => !text.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+    }
+}
+
+fn values_equal(actual: &Value, expected: &Value) -> bool {
+    match (actual.as_f64(), expected.as_f64()) {
+        (Some(actual), Some(expected)) => actual == expected,
+        _ => actual == expected,
+    }
+}
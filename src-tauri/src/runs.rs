@@ -0,0 +1,358 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::checksums::InputHashes;
+use crate::environment::RunEnvironment;
+use crate::recorder::EVENTS_FILENAME;
+use crate::workspace::list_dir;
+use crate::{RunMode, STDERR_LOG_FILENAME, STDOUT_LOG_FILENAME};
+
+pub const METADATA_FILENAME: &str = "run.json";
+const MAX_LABEL_CHARS: usize = 120;
+/// Tokens are replaced with the run's mode, UTC start date (`YYYY-MM-DD`),
+/// UTC start time (`HH-MM-SS`), and run id.
+pub const DEFAULT_LOG_FILENAME_TEMPLATE: &str = "{mode}_{date}T{time}Z.log";
+const LOG_FILENAME_TOKENS: &[&str] = &["mode", "date", "time", "run_id"];
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RunMetadata {
+    pub run_id: String,
+    pub mode: RunMode,
+    #[serde(default)]
+    pub label: Option<String>,
+    pub started_at: u64,
+    #[serde(default)]
+    pub finished_at: Option<u64>,
+    #[serde(default)]
+    pub success: Option<bool>,
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+    pub config_path: String,
+    pub log_path: String,
+    /// Seconds spent in each progress stage, recorded for successful runs.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub stage_durations: BTreeMap<String, f64>,
+    /// Checksums of the run's inputs, when the run requested them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_hashes: Option<InputHashes>,
+    /// The machine and Pioneer build the run executed on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<RunEnvironment>,
+    /// The interrupted run this one resumed, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resumed_from: Option<String>,
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::from_secs(0))
+        .as_secs()
+}
+
+/// Splits a unix timestamp into UTC `YYYY-MM-DD` and `HH-MM-SS` (dashes keep
+/// the time usable in file names on Windows).
+pub fn utc_date_time(timestamp: u64) -> (String, String) {
+    let days = (timestamp / 86_400) as i64;
+    let seconds = timestamp % 86_400;
+    // Days-to-civil conversion from Howard Hinnant's date algorithms.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (
+        format!("{year:04}-{month:02}-{day:02}"),
+        format!(
+            "{:02}-{:02}-{:02}",
+            seconds / 3_600,
+            seconds % 3_600 / 60,
+            seconds % 60
+        ),
+    )
+}
+
+/// Rejects templates with unknown `{tokens}` or unbalanced braces before any
+/// run state is created.
+pub fn check_log_template(template: &str) -> Result<(), String> {
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        let Some(close) = rest[open..].find('}') else {
+            return Err(format!(
+                "Unclosed `{{` in log filename template `{template}`"
+            ));
+        };
+        let token = &rest[open + 1..open + close];
+        if !LOG_FILENAME_TOKENS.contains(&token) {
+            return Err(format!(
+                "Unknown token `{{{token}}}` in log filename template; expected one of {}",
+                LOG_FILENAME_TOKENS
+                    .iter()
+                    .map(|token| format!("{{{token}}}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        rest = &rest[open + close + 1..];
+    }
+    if rest.contains('}') {
+        return Err(format!(
+            "Unmatched `}}` in log filename template `{template}`"
+        ));
+    }
+    Ok(())
+}
+
+/// Expands the template and checks the result is a plain, portable file name
+/// that does not replace another file the run directory holds.
+pub fn log_filename(
+    template: &str,
+    mode: RunMode,
+    timestamp: u64,
+    run_id: &str,
+) -> Result<String, String> {
+    check_log_template(template)?;
+    let (date, time) = utc_date_time(timestamp);
+    let name = template
+        .replace("{mode}", mode.as_str())
+        .replace("{date}", &date)
+        .replace("{time}", &time)
+        .replace("{run_id}", run_id);
+
+    let trimmed = name.trim_end_matches(['.', ' ']);
+    if trimmed.is_empty() || name == ".." {
+        return Err(format!(
+            "Log filename template `{template}` produces an empty name"
+        ));
+    }
+    if let Some(bad) = name.chars().find(|c| {
+        c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|')
+    }) {
+        return Err(format!(
+            "Log filename `{name}` contains `{}`, which is not allowed in file names",
+            bad.escape_default()
+        ));
+    }
+    if trimmed.len() != name.len() {
+        return Err(format!(
+            "Log filename `{name}` must not end with a dot or space"
+        ));
+    }
+    // Case-insensitive, as on the default Windows and macOS filesystems.
+    let reserved = [
+        METADATA_FILENAME,
+        mode.config_filename(),
+        EVENTS_FILENAME,
+        STDOUT_LOG_FILENAME,
+        STDERR_LOG_FILENAME,
+    ];
+    if let Some(taken) = reserved
+        .iter()
+        .find(|taken| taken.eq_ignore_ascii_case(&name))
+    {
+        return Err(format!(
+            "Log filename `{name}` would overwrite the run's `{taken}`; choose a different template"
+        ));
+    }
+    Ok(name)
+}
+
+/// Creates `<root>/<mode>_<timestamp>`, suffixing `-N` if a run already
+/// started in the same second.
+pub fn create_run_dir(root: &Path, mode: RunMode, timestamp: u64) -> Result<PathBuf, String> {
+    fs::create_dir_all(root).map_err(|e| e.to_string())?;
+    let base = format!("{}_{timestamp}", mode.as_str());
+    let mut candidate = root.join(&base);
+    let mut suffix = 1;
+    while candidate.exists() {
+        candidate = root.join(format!("{base}-{suffix}"));
+        suffix += 1;
+    }
+    fs::create_dir(&candidate).map_err(|e| e.to_string())?;
+    Ok(candidate)
+}
+
+pub fn run_id_of(run_dir: &Path) -> String {
+    run_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+pub fn write_metadata(run_dir: &Path, metadata: &RunMetadata) -> Result<(), String> {
+    let pretty = serde_json::to_string_pretty(metadata).map_err(|e| e.to_string())?;
+    fs::write(run_dir.join(METADATA_FILENAME), pretty).map_err(|e| e.to_string())
+}
+
+pub fn read_metadata(run_dir: &Path) -> Result<RunMetadata, String> {
+    let contents =
+        fs::read_to_string(run_dir.join(METADATA_FILENAME)).map_err(|e| e.to_string())?;
+    serde_json::from_str(&contents).map_err(|e| e.to_string())
+}
+
+/// Records the outcome unless one was already written.
+pub fn finish_run(run_dir: &Path, success: bool, exit_code: Option<i32>) -> Result<(), String> {
+    let mut metadata = read_metadata(run_dir)?;
+    if metadata.finished_at.is_some() {
+        return Ok(());
+    }
+    metadata.finished_at = Some(unix_now());
+    metadata.success = Some(success);
+    metadata.exit_code = exit_code;
+    write_metadata(run_dir, &metadata)
+}
+
+pub fn record_stage_durations(
+    run_dir: &Path,
+    durations: BTreeMap<String, f64>,
+) -> Result<(), String> {
+    let mut metadata = read_metadata(run_dir)?;
+    metadata.stage_durations = durations;
+    write_metadata(run_dir, &metadata)
+}
+
+/// All readable runs under `root`, newest first.
+pub fn list_runs(root: &Path) -> Vec<RunMetadata> {
+    let mut runs: Vec<RunMetadata> = list_dir(root)
+        .iter()
+        .filter(|path| path.is_dir())
+        .filter_map(|path| read_metadata(path).ok())
+        .collect();
+    runs.sort_by_key(|run| std::cmp::Reverse(run.started_at));
+    runs
+}
+
+/// Resolves `run_id` to a directory directly inside `root`, rejecting
+/// anything that could escape it.
+pub fn resolve_run_dir(root: &Path, run_id: &str) -> Result<PathBuf, String> {
+    let valid = !run_id.is_empty()
+        && run_id != "."
+        && run_id != ".."
+        && run_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    if !valid {
+        return Err(format!("Invalid run id `{run_id}`"));
+    }
+    let run_dir = root.join(run_id);
+    if !run_dir.join(METADATA_FILENAME).is_file() {
+        return Err(format!("Run `{run_id}` not found"));
+    }
+    Ok(run_dir)
+}
+
+pub fn sanitize_label(label: &str) -> Option<String> {
+    let cleaned: String = label
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(MAX_LABEL_CHARS)
+        .collect();
+    let cleaned = cleaned.trim();
+    if cleaned.is_empty() {
+        None
+    } else {
+        Some(cleaned.to_string())
+    }
+}
+
+fn label_slug(label: &str) -> String {
+    let mut slug = String::new();
+    for c in label.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_matches('-').to_string()
+}
+
+/// Sets (or clears, for an empty label) the run's label. With `rename`, a
+/// finished run's directory becomes `<original id>__<label slug>`; the run id
+/// changes accordingly and the stored paths are rewritten.
+pub fn label_run(
+    root: &Path,
+    run_id: &str,
+    label: &str,
+    rename: bool,
+) -> Result<RunMetadata, String> {
+    let mut run_dir = resolve_run_dir(root, run_id)?;
+    let mut metadata = read_metadata(&run_dir)?;
+    metadata.label = sanitize_label(label);
+
+    if rename {
+        if metadata.finished_at.is_none() {
+            return Err("Cannot rename a run that is still in progress".into());
+        }
+        let base = run_id
+            .split_once("__")
+            .map(|(base, _)| base)
+            .unwrap_or(run_id);
+        let slug = metadata
+            .label
+            .as_deref()
+            .map(label_slug)
+            .unwrap_or_default();
+        let new_id = if slug.is_empty() {
+            base.to_string()
+        } else {
+            format!("{base}__{slug}")
+        };
+        if new_id != metadata.run_id {
+            let target = root.join(&new_id);
+            if target.exists() {
+                return Err(format!("A run named `{new_id}` already exists"));
+            }
+            fs::rename(&run_dir, &target).map_err(|e| e.to_string())?;
+            metadata.config_path = rebase(&metadata.config_path, &run_dir, &target);
+            metadata.log_path = rebase(&metadata.log_path, &run_dir, &target);
+            metadata.run_id = new_id;
+            run_dir = target;
+        }
+    }
+
+    write_metadata(&run_dir, &metadata)?;
+    Ok(metadata)
+}
+
+fn rebase(path: &str, from: &Path, to: &Path) -> String {
+    Path::new(path)
+        .strip_prefix(from)
+        .map(|relative| to.join(relative).to_string_lossy().to_string())
+        .unwrap_or_else(|_| path.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::log_filename;
+    use crate::RunMode;
+
+    #[test]
+    fn expands_the_default_template() {
+        assert_eq!(
+            log_filename(
+                super::DEFAULT_LOG_FILENAME_TEMPLATE,
+                RunMode::SearchDia,
+                0,
+                "r1"
+            )
+            .unwrap(),
+            format!("{}_1970-01-01T00-00-00Z.log", RunMode::SearchDia.as_str())
+        );
+    }
+
+    #[test]
+    fn rejects_names_of_other_run_files() {
+        assert!(log_filename("run.json", RunMode::SearchDia, 0, "r1").is_err());
+        assert!(log_filename("Search_Params.json", RunMode::SearchDia, 0, "r1").is_err());
+        assert!(log_filename("{run_id}.json", RunMode::SearchDia, 0, "run").is_err());
+        assert!(log_filename("search_params.json", RunMode::BuildSpecLib, 0, "r1").is_ok());
+    }
+}
@@ -0,0 +1,48 @@
+use jsonschema::JSONSchema;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+use crate::encoding::strip_bom;
+
+#[derive(Serialize, Debug)]
+pub struct SchemaViolation {
+    /// JSON pointer to the offending value in the config (`""` for the root).
+    pub instance_path: String,
+    /// JSON pointer to the schema keyword that rejected it.
+    pub schema_path: String,
+    pub message: String,
+}
+
+/// Checks `config` against a user-maintained JSON Schema. This is separate
+/// from the binary-derived checks: it only enforces what the schema file says.
+/// Remote and relative `$ref`s are not resolved, so the schema should be
+/// self-contained.
+pub fn validate_config_external(
+    config: &Value,
+    schema_path: &Path,
+) -> Result<Vec<SchemaViolation>, String> {
+    let contents = fs::read_to_string(schema_path)
+        .map_err(|e| format!("Cannot read schema {}: {e}", schema_path.display()))?;
+    let schema: Value = serde_json::from_str(strip_bom(&contents))
+        .map_err(|e| format!("Schema {} is not valid JSON: {e}", schema_path.display()))?;
+    let compiled = JSONSchema::compile(&schema).map_err(|e| {
+        format!(
+            "Schema {} is not a valid JSON Schema: {e}",
+            schema_path.display()
+        )
+    })?;
+
+    let violations = match compiled.validate(config) {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors
+            .map(|error| SchemaViolation {
+                instance_path: error.instance_path.to_string(),
+                schema_path: error.schema_path.to_string(),
+                message: error.to_string(),
+            })
+            .collect(),
+    };
+    Ok(violations)
+}
@@ -0,0 +1,155 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::Path;
+
+use crate::RunMode;
+
+pub const MANIFEST_FILENAME: &str = "sections.json";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SectionFile {
+    pub key: String,
+    pub file: String,
+}
+
+/// Written alongside the section files so `assemble_config` only picks up
+/// the files `split_config` produced, under their original keys.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SectionManifest {
+    pub mode: RunMode,
+    pub sections: Vec<SectionFile>,
+}
+
+/// File stem for a top-level key, keeping it recognisable while staying safe
+/// on every platform. Collisions get a numeric suffix.
+fn section_stem(key: &str, taken: &[SectionFile]) -> String {
+    let cleaned: String = key
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '_' | '-') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let base = if cleaned.is_empty() {
+        "section".to_string()
+    } else {
+        cleaned
+    };
+    let mut stem = base.clone();
+    let mut suffix = 1;
+    while taken
+        .iter()
+        .any(|section| section.file.eq_ignore_ascii_case(&format!("{stem}.json")))
+        || format!("{stem}.json").eq_ignore_ascii_case(MANIFEST_FILENAME)
+    {
+        stem = format!("{base}-{suffix}");
+        suffix += 1;
+    }
+    stem
+}
+
+/// Writes each top-level entry of `config` to `<dest_dir>/<key>.json` plus a
+/// manifest listing them.
+pub fn split_config(
+    mode: RunMode,
+    config: &Value,
+    dest_dir: &Path,
+) -> Result<SectionManifest, String> {
+    let map = config
+        .as_object()
+        .ok_or_else(|| "Config must be a JSON object".to_string())?;
+    fs::create_dir_all(dest_dir).map_err(|e| e.to_string())?;
+
+    let mut sections: Vec<SectionFile> = Vec::with_capacity(map.len());
+    for (key, value) in map {
+        let file = format!("{}.json", section_stem(key, &sections));
+        let pretty = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+        fs::write(dest_dir.join(&file), pretty).map_err(|e| e.to_string())?;
+        sections.push(SectionFile {
+            key: key.clone(),
+            file,
+        });
+    }
+
+    let manifest = SectionManifest { mode, sections };
+    let pretty = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+    fs::write(dest_dir.join(MANIFEST_FILENAME), pretty).map_err(|e| e.to_string())?;
+    Ok(manifest)
+}
+
+/// Recombines a directory written by `split_config`.
+pub fn assemble_config(source_dir: &Path) -> Result<(RunMode, Value), String> {
+    let manifest_path = source_dir.join(MANIFEST_FILENAME);
+    let contents = fs::read_to_string(&manifest_path)
+        .map_err(|e| format!("Failed to read {}: {e}", manifest_path.display()))?;
+    let manifest: SectionManifest = serde_json::from_str(&contents)
+        .map_err(|e| format!("Invalid {}: {e}", manifest_path.display()))?;
+
+    let mut config = Map::new();
+    for section in manifest.sections {
+        if Path::new(&section.file).file_name() != Some(section.file.as_ref()) {
+            return Err(format!(
+                "Section file `{}` must be a plain file name",
+                section.file
+            ));
+        }
+        let path = source_dir.join(&section.file);
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        let value: Value = serde_json::from_str(&contents)
+            .map_err(|e| format!("Invalid {}: {e}", path.display()))?;
+        config.insert(section.key, value);
+    }
+    Ok((manifest.mode, Value::Object(config)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{assemble_config, split_config, MANIFEST_FILENAME};
+    use crate::RunMode;
+    use serde_json::json;
+    use std::fs;
+
+    #[test]
+    fn split_then_assemble_round_trips() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let config = json!({
+            "a/b": {"x": 1},
+            "a_b": [1, 2],
+            "sections": "not the manifest",
+            "": null,
+        });
+        let manifest = split_config(RunMode::SearchDia, &config, dir.path()).expect("split");
+        let files: Vec<&str> = manifest.sections.iter().map(|s| s.file.as_str()).collect();
+        assert_eq!(
+            files,
+            ["section.json", "a_b.json", "a_b-1.json", "sections-1.json"]
+        );
+
+        let (mode, assembled) = assemble_config(dir.path()).expect("assemble");
+        assert_eq!(mode, RunMode::SearchDia);
+        assert_eq!(assembled, config);
+    }
+
+    #[test]
+    fn rejects_a_non_object_config() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        assert!(split_config(RunMode::BuildSpecLib, &json!([1]), dir.path()).is_err());
+    }
+
+    #[test]
+    fn assemble_refuses_paths_outside_the_directory() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        fs::write(
+            dir.path().join(MANIFEST_FILENAME),
+            r#"{"mode": "searchDia", "sections": [{"key": "a", "file": "../a.json"}]}"#,
+        )
+        .expect("write manifest");
+        let err = assemble_config(dir.path()).expect_err("escaping file name");
+        assert!(err.contains("plain file name"), "{err}");
+    }
+}
@@ -0,0 +1,77 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+use crate::StageInfo;
+
+/// How an output line that matches a stage's keywords moves the run between
+/// stages.
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum StageMatchMode {
+    /// The first later stage that matches; earlier stages are never
+    /// considered.
+    #[default]
+    Forward,
+    /// The highest stage that matches, wherever the run is now. The run still
+    /// only moves forward, so a line that mentions several stages lands on
+    /// the furthest one rather than the nearest.
+    Highest,
+    /// Like `Highest`, but a line matching only earlier stages moves the run
+    /// back, for Pioneer builds that revisit a stage (another tuning pass
+    /// after a first search, say). Progress can then go down.
+    Follow,
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StageMatching {
+    pub mode: StageMatchMode,
+    /// Lines that must match a stage, keyed by stage key (`"write"`,
+    /// `"quant"`, …), before the run enters it. Stages not listed need one,
+    /// so a single stray keyword in another stage's output can be told
+    /// apart from the stage actually starting.
+    pub min_hits: BTreeMap<String, u32>,
+}
+
+/// Keyword hits counted over one run.
+pub struct StageMatcher {
+    settings: StageMatching,
+    hits: Vec<u32>,
+}
+
+impl StageMatcher {
+    pub fn new(settings: StageMatching, stages: &[StageInfo]) -> Self {
+        StageMatcher {
+            settings,
+            hits: vec![0; stages.len()],
+        }
+    }
+
+    /// Counts `line` against every stage's keywords and returns the stage
+    /// the run should move to, if any. `None` means stay in `current`.
+    pub fn observe(&mut self, line: &str, current: usize, stages: &[StageInfo]) -> Option<usize> {
+        let normalized = line.to_lowercase();
+        let mut ready = Vec::new();
+        for (idx, stage) in stages.iter().enumerate() {
+            if !stage
+                .keywords
+                .iter()
+                .any(|keyword| keyword.is_empty() || normalized.contains(keyword))
+            {
+                continue;
+            }
+            self.hits[idx] += 1;
+            let required = self.settings.min_hits.get(stage.key).copied().unwrap_or(1);
+            if self.hits[idx] >= required {
+                ready.push(idx);
+            }
+        }
+
+        let highest = ready.last().copied();
+        match self.settings.mode {
+            StageMatchMode::Forward => ready.into_iter().find(|idx| *idx > current),
+            StageMatchMode::Highest => highest.filter(|idx| *idx > current),
+            StageMatchMode::Follow => highest.filter(|idx| *idx != current),
+        }
+    }
+}
@@ -0,0 +1,403 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::diff::lookup;
+use crate::workspace::disk_usage;
+
+pub const TEST_RUN_SUBDIR: &str = "test_run";
+/// Vendor formats stored as a directory per acquisition (Bruker timsTOF `.d`).
+/// Each counts as one data unit and is never descended into.
+const DIRECTORY_DATASET_EXTENSIONS: &[&str] = &["d"];
+/// Single-file MS formats: Pioneer's Arrow conversions and Thermo `.raw`.
+const MS_FILE_EXTENSIONS: &[&str] = &["arrow", "raw"];
+/// SearchDIA inputs `link_or_copy` may be asked to stage.
+const STAGED_INPUT_KEYS: &[&str] = &["paths.ms_data", "paths.library"];
+const LINK_PROBE_FILENAME: &str = ".pioneer-gui-link-probe";
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MsDataKind {
+    File,
+    Directory,
+}
+
+#[derive(Serialize, Debug)]
+pub struct MsDataUnit {
+    pub name: String,
+    pub path: String,
+    pub kind: MsDataKind,
+    pub bytes: u64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct MsDataInventory {
+    pub dir: String,
+    pub units: Vec<MsDataUnit>,
+    pub file_count: usize,
+    pub directory_count: usize,
+    pub total_bytes: u64,
+    /// Subdirectories that are not a recognised dataset format.
+    pub ignored_directories: Vec<String>,
+}
+
+/// Points a SearchDIA config at a staged copy of its first `max_files` MS
+/// files (a `.d` directory counts as one) and redirects results into a `test_run` subdirectory.
+/// Returns the number of files staged; `on_progress` receives `(staged, total)`.
+pub fn scope_search_inputs(
+    config: &mut Value,
+    staging_dir: &Path,
+    max_files: usize,
+    on_progress: impl FnMut(usize, usize),
+) -> Result<usize, String> {
+    if max_files == 0 {
+        return Err("A test run needs `maxFiles` of at least 1".into());
+    }
+    let paths = config
+        .get_mut("paths")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| "Search config is missing the `paths` section".to_string())?;
+
+    let ms_data = paths
+        .get("ms_data")
+        .and_then(Value::as_str)
+        .map(PathBuf::from)
+        .ok_or_else(|| "Search config is missing `paths.ms_data`".to_string())?;
+
+    let staged_dir = staging_dir.join("ms_data");
+    fs::create_dir_all(&staged_dir).map_err(|e| e.to_string())?;
+    let staged = stage_first_files(&ms_data, &staged_dir, max_files, on_progress)
+        .map_err(|e| format!("Failed to stage MS files from {}: {e}", ms_data.display()))?;
+    if staged == 0 {
+        return Err(format!("No MS files found in {}", ms_data.display()));
+    }
+
+    paths.insert(
+        "ms_data".into(),
+        Value::String(staged_dir.to_string_lossy().to_string()),
+    );
+    if let Some(results) = paths.get("results").and_then(Value::as_str) {
+        let scoped = Path::new(results).join(TEST_RUN_SUBDIR);
+        paths.insert(
+            "results".into(),
+            Value::String(scoped.to_string_lossy().to_string()),
+        );
+    }
+    Ok(staged)
+}
+
+fn stage_first_files(
+    source: &Path,
+    dest: &Path,
+    max_files: usize,
+    mut on_progress: impl FnMut(usize, usize),
+) -> io::Result<usize> {
+    let mut files = ms_files(source)?;
+    files.truncate(max_files);
+
+    let total = files.len();
+    let mut staged = 0;
+    for file in files {
+        let Some(name) = file.file_name() else {
+            continue;
+        };
+        link_or_copy(&file, &dest.join(name))?;
+        staged += 1;
+        on_progress(staged, total);
+    }
+    Ok(staged)
+}
+
+/// Non-hidden data units directly inside `dir` (MS files and directory-based
+/// datasets), sorted. Anything else, such as a README or a conversion log,
+/// is not MS data and is left out.
+fn ms_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| (is_ms_file(path) || is_directory_dataset(path)) && !is_hidden(path))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+fn is_ms_file(path: &Path) -> bool {
+    path.is_file() && has_extension(path, MS_FILE_EXTENSIONS)
+}
+
+fn is_directory_dataset(path: &Path) -> bool {
+    path.is_dir() && has_extension(path, DIRECTORY_DATASET_EXTENSIONS)
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            extensions
+                .iter()
+                .any(|known| ext.eq_ignore_ascii_case(known))
+        })
+}
+
+/// Inventory of the data units in an MS data directory, with `.d` datasets
+/// counted once each rather than by their internal files.
+pub fn scan_ms_data(dir: &Path) -> Result<MsDataInventory, String> {
+    let units = ms_files(dir).map_err(|e| format!("Failed to read {}: {e}", dir.display()))?;
+    let ignored_directories = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read {}: {e}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_dir() && !is_hidden(path) && !is_directory_dataset(path))
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+
+    let units: Vec<MsDataUnit> = units
+        .into_iter()
+        .map(|path| {
+            let kind = if path.is_dir() {
+                MsDataKind::Directory
+            } else {
+                MsDataKind::File
+            };
+            MsDataUnit {
+                name: path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                path: path.to_string_lossy().to_string(),
+                kind,
+                bytes: disk_usage(&path),
+            }
+        })
+        .collect();
+    Ok(MsDataInventory {
+        dir: dir.to_string_lossy().to_string(),
+        file_count: units
+            .iter()
+            .filter(|unit| unit.kind == MsDataKind::File)
+            .count(),
+        directory_count: units
+            .iter()
+            .filter(|unit| unit.kind == MsDataKind::Directory)
+            .count(),
+        total_bytes: units.iter().map(|unit| unit.bytes).sum(),
+        units,
+        ignored_directories,
+    })
+}
+
+#[derive(Serialize, Debug)]
+pub struct FilenameCollision {
+    pub name: String,
+    pub paths: Vec<String>,
+}
+
+/// Groups files from different `dirs` that share a base name. Pioneer names
+/// per-file results after the file stem, so the comparison ignores the
+/// extension and case (results may land on a case-insensitive filesystem).
+pub fn filename_collisions(dirs: &[PathBuf]) -> Result<Vec<FilenameCollision>, String> {
+    let mut groups: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    for dir in dirs {
+        let files = ms_files(dir).map_err(|e| format!("Failed to read {}: {e}", dir.display()))?;
+        for file in files {
+            let Some(stem) = file
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_lowercase())
+            else {
+                continue;
+            };
+            let group = groups.entry(stem).or_default();
+            if !group.contains(&file) {
+                group.push(file);
+            }
+        }
+    }
+
+    Ok(groups
+        .into_iter()
+        .filter(|(_, paths)| paths.iter().any(|path| path.parent() != paths[0].parent()))
+        .map(|(name, paths)| FilenameCollision {
+            name,
+            paths: paths
+                .iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect(),
+        })
+        .collect())
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.starts_with('.'))
+        .unwrap_or(false)
+}
+
+/// Symlinks where the platform allows it, copying otherwise (e.g. Windows
+/// without the symlink privilege). Directory datasets are copied recursively.
+pub fn link_or_copy(src: &Path, dst: &Path) -> io::Result<()> {
+    if symlink(src, dst).is_ok() {
+        return Ok(());
+    }
+    if src.is_dir() {
+        copy_dir(src, dst)
+    } else {
+        fs::copy(src, dst).map(|_| ())
+    }
+}
+
+fn symlink(src: &Path, dst: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(src, dst)
+    }
+    #[cfg(windows)]
+    {
+        if src.is_dir() {
+            std::os::windows::fs::symlink_dir(src, dst)
+        } else {
+            std::os::windows::fs::symlink_file(src, dst)
+        }
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = (src, dst);
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "symlinks are not supported",
+        ))
+    }
+}
+
+fn copy_dir(src: &Path, dst: &Path) -> io::Result<()> {
+    fs::create_dir_all(dst)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let target = dst.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum StagingStrategy {
+    Symlink,
+    Copy,
+}
+
+#[derive(Serialize, Debug)]
+pub struct StagingCheck {
+    pub key: String,
+    pub path: String,
+    pub strategy: StagingStrategy,
+    /// Why symlinking was ruled out, when it was.
+    pub reason: Option<String>,
+    /// Extra disk space staging would use in the temp directory: zero for
+    /// symlinks, the inputs' size for copies.
+    pub copy_bytes: u64,
+}
+
+/// Decides, for each SearchDIA input a run could stage, whether `link_or_copy`
+/// will manage a symlink from `staging_dir` or fall back to copying. The
+/// decision comes from actually creating (and removing) a probe link, since
+/// whether one works depends on the filesystem and, on Windows, privileges.
+/// `max_files` limits the MS files counted towards `copy_bytes`, as a test run
+/// would.
+pub fn check_staging_strategy(
+    config: &Value,
+    staging_dir: &Path,
+    max_files: Option<usize>,
+) -> Vec<StagingCheck> {
+    let mut checks = Vec::new();
+    for key in STAGED_INPUT_KEYS {
+        let Some(path) = lookup(config, key).and_then(Value::as_str) else {
+            continue;
+        };
+        let path = PathBuf::from(path);
+        let units = if *key == "paths.ms_data" && path.is_dir() {
+            let mut units = ms_files(&path).unwrap_or_default();
+            if let Some(max_files) = max_files {
+                units.truncate(max_files);
+            }
+            units
+        } else {
+            vec![path.clone()]
+        };
+        let probe_source = units.first().cloned().unwrap_or_else(|| path.clone());
+        let (strategy, reason) = match probe_symlink(&probe_source, staging_dir) {
+            Ok(()) => (StagingStrategy::Symlink, None),
+            Err(err) => (StagingStrategy::Copy, Some(err)),
+        };
+        let copy_bytes = match strategy {
+            StagingStrategy::Symlink => 0,
+            StagingStrategy::Copy => units.iter().map(|unit| disk_usage(unit)).sum(),
+        };
+        checks.push(StagingCheck {
+            key: key.to_string(),
+            path: path.to_string_lossy().to_string(),
+            strategy,
+            reason,
+            copy_bytes,
+        });
+    }
+    checks
+}
+
+fn probe_symlink(source: &Path, staging_dir: &Path) -> Result<(), String> {
+    if !source.exists() {
+        return Err(format!("{} does not exist", source.display()));
+    }
+    fs::create_dir_all(staging_dir).map_err(|e| e.to_string())?;
+    let link = staging_dir.join(LINK_PROBE_FILENAME);
+    let _ = remove_link(&link);
+    symlink(source, &link).map_err(|e| format!("Cannot create a symlink: {e}"))?;
+    // A link can be created yet not resolve, e.g. from inside a sandbox.
+    let resolved = fs::metadata(&link)
+        .map(|_| ())
+        .map_err(|e| format!("The symlink does not resolve: {e}"));
+    let _ = remove_link(&link);
+    resolved
+}
+
+/// Directory symlinks on Windows are removed as directories.
+fn remove_link(link: &Path) -> io::Result<()> {
+    fs::remove_file(link).or_else(|_| fs::remove_dir(link))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ms_files, scope_search_inputs};
+    use serde_json::json;
+    use std::fs;
+
+    #[test]
+    fn only_ms_files_and_datasets_are_candidates() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["b.arrow", "a.RAW", "notes.txt", ".hidden.arrow"] {
+            fs::write(dir.path().join(name), b"").unwrap();
+        }
+        fs::create_dir(dir.path().join("c.d")).unwrap();
+        fs::create_dir(dir.path().join("qc")).unwrap();
+        let names: Vec<String> = ms_files(dir.path())
+            .unwrap()
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, ["a.RAW", "b.arrow", "c.d"]);
+    }
+
+    #[test]
+    fn zero_max_files_is_rejected_before_staging() {
+        let staging = tempfile::tempdir().unwrap();
+        let mut config = json!({"paths": {"ms_data": "/nonexistent"}});
+        let err = scope_search_inputs(&mut config, staging.path(), 0, |_, _| {}).unwrap_err();
+        assert!(err.contains("maxFiles"), "{err}");
+        assert!(!staging.path().join("ms_data").exists());
+    }
+}
@@ -0,0 +1,228 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::diff::{join_path, lookup};
+use crate::loghealth::Severity;
+
+/// Thresholds that decide which identifications are reported. Above
+/// `LOOSE_FINAL_Q_VALUE` the results are no longer what anyone would publish.
+const FINAL_Q_VALUE_KEYS: &[&str] = &[
+    "global.scoring.q_value_threshold",
+    "optimization.machine_learning.qvalue_threshold",
+];
+const CONVENTIONAL_Q_VALUE: f64 = 0.01;
+const LOOSE_FINAL_Q_VALUE: f64 = 0.05;
+const MEANINGLESS_FINAL_Q_VALUE: f64 = 0.2;
+/// Key fragments marking a q-value threshold anywhere in a config. Keys that
+/// merely mention FDR may hold factors or counts rather than thresholds.
+const Q_VALUE_KEY_MARKERS: &[&str] = &["q_value", "qvalue"];
+
+#[derive(Serialize, Debug)]
+pub struct StatisticsIssue {
+    pub path: String,
+    pub value: Value,
+    pub severity: Severity,
+    pub message: String,
+    /// What to change, in the user's terms.
+    pub guidance: String,
+}
+
+/// Flags FDR and decoy settings that are in range for their type but would
+/// make the identifications unreliable. Works on either workflow's config;
+/// checks whose keys are absent are skipped.
+pub fn sanity_check_statistics(config: &Value) -> Vec<StatisticsIssue> {
+    let mut issues = Vec::new();
+
+    let mut thresholds = Vec::new();
+    collect_thresholds(config, "", &mut thresholds);
+    for (path, value) in &thresholds {
+        let issue = |severity, message: String, guidance: &str| StatisticsIssue {
+            path: path.clone(),
+            value: Value::from(*value),
+            severity,
+            message,
+            guidance: guidance.to_string(),
+        };
+        if *value <= 0.0 {
+            issues.push(issue(
+                Severity::Error,
+                format!("`{path}` is {value}, so nothing can pass it"),
+                "Use a positive threshold; 0.01 (1% FDR) is conventional.",
+            ));
+        } else if *value >= 1.0 {
+            issues.push(issue(
+                Severity::Error,
+                format!("`{path}` is {value}, which lets every identification through"),
+                "q-values are fractions; 1% FDR is 0.01, not 1.0.",
+            ));
+        } else if FINAL_Q_VALUE_KEYS.contains(&path.as_str()) {
+            if *value >= MEANINGLESS_FINAL_Q_VALUE {
+                issues.push(issue(
+                    Severity::Error,
+                    format!("`{path}` is {value}: at least one in five reported identifications is expected to be wrong"),
+                    "Set the final threshold to 0.01 unless you are deliberately exploring noise.",
+                ));
+            } else if *value > LOOSE_FINAL_Q_VALUE {
+                issues.push(issue(
+                    Severity::Warning,
+                    format!("`{path}` is {value}, looser than the usual {CONVENTIONAL_Q_VALUE}–{LOOSE_FINAL_Q_VALUE}"),
+                    "Results at this FDR are rarely accepted for publication; consider 0.01.",
+                ));
+            }
+        }
+    }
+
+    // An intermediate filter stricter than the final one discards candidates
+    // the final FDR would have kept.
+    let final_threshold = FINAL_Q_VALUE_KEYS
+        .iter()
+        .filter_map(|key| lookup(config, key).and_then(Value::as_f64))
+        .fold(None, |min: Option<f64>, value| {
+            Some(min.map_or(value, |min| min.min(value)))
+        });
+    if let Some(final_threshold) = final_threshold.filter(|value| *value > 0.0) {
+        for (path, value) in &thresholds {
+            if !FINAL_Q_VALUE_KEYS.contains(&path.as_str())
+                && *value > 0.0
+                && *value < final_threshold
+            {
+                issues.push(StatisticsIssue {
+                    path: path.clone(),
+                    value: Value::from(*value),
+                    severity: Severity::Warning,
+                    message: format!("`{path}` ({value}) is stricter than the final q-value threshold ({final_threshold})"),
+                    guidance: "Intermediate filters should be at least as loose as the final threshold, or they remove identifications before FDR is estimated.".into(),
+                });
+            }
+        }
+    }
+
+    if let Some(add_decoys) =
+        lookup(config, "fasta_digest_params.add_decoys").and_then(Value::as_bool)
+    {
+        if !add_decoys {
+            issues.push(StatisticsIssue {
+                path: "fasta_digest_params.add_decoys".into(),
+                value: Value::Bool(false),
+                severity: Severity::Error,
+                message:
+                    "The library will contain no decoys, so searches against it cannot estimate FDR"
+                        .into(),
+                guidance: "Enable `add_decoys` unless the FASTA already contains decoy sequences."
+                    .into(),
+            });
+        }
+    }
+
+    if let Some(entrapment) =
+        lookup(config, "fasta_digest_params.entrapment_r").and_then(Value::as_f64)
+    {
+        if entrapment < 0.0 {
+            issues.push(StatisticsIssue {
+                path: "fasta_digest_params.entrapment_r".into(),
+                value: Value::from(entrapment),
+                severity: Severity::Error,
+                message: format!("`entrapment_r` is {entrapment}; the entrapment ratio cannot be negative"),
+                guidance: "Use 0 to disable entrapment, or a positive ratio of entrapment to target sequences.".into(),
+            });
+        }
+    }
+
+    issues
+}
+
+fn collect_thresholds(value: &Value, prefix: &str, out: &mut Vec<(String, f64)>) {
+    let Value::Object(map) = value else {
+        return;
+    };
+    for (key, child) in map {
+        let path = join_path(prefix, key);
+        let lowered = key.to_ascii_lowercase();
+        match child {
+            Value::Object(_) => collect_thresholds(child, &path, out),
+            Value::Number(number)
+                if Q_VALUE_KEY_MARKERS
+                    .iter()
+                    .any(|marker| lowered.contains(marker)) =>
+            {
+                if let Some(number) = number.as_f64() {
+                    out.push((path, number));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sanity_check_statistics;
+    use crate::loghealth::Severity;
+    use serde_json::json;
+
+    fn flagged(config: serde_json::Value) -> Vec<(String, Severity)> {
+        sanity_check_statistics(&config)
+            .into_iter()
+            .map(|issue| (issue.path, issue.severity))
+            .collect()
+    }
+
+    #[test]
+    fn conventional_settings_pass() {
+        let config = json!({
+            "global": {"scoring": {"q_value_threshold": 0.01}},
+            "first_search": {"scoring_settings": {"max_q_value_probit_rescore": 0.05}},
+            "fasta_digest_params": {"add_decoys": true, "entrapment_r": 0},
+        });
+        assert!(flagged(config).is_empty());
+    }
+
+    #[test]
+    fn grades_the_final_threshold() {
+        let path = "global.scoring.q_value_threshold".to_string();
+        for (value, expected) in [
+            (0.0, Some(Severity::Error)),
+            (0.1, Some(Severity::Warning)),
+            (0.2, Some(Severity::Error)),
+            (1.0, Some(Severity::Error)),
+            (0.05, None),
+        ] {
+            let issues = flagged(json!({"global": {"scoring": {"q_value_threshold": value}}}));
+            let found = issues.first().map(|(p, s)| (p.clone(), *s));
+            assert_eq!(found, expected.map(|s| (path.clone(), s)), "{value}");
+        }
+    }
+
+    #[test]
+    fn flags_intermediate_filters_stricter_than_the_final_one() {
+        let config = json!({
+            "global": {"scoring": {"q_value_threshold": 0.01}},
+            "parameter_tuning": {"search_settings": {"max_q_value": 0.001}},
+        });
+        assert_eq!(
+            flagged(config),
+            [(
+                "parameter_tuning.search_settings.max_q_value".to_string(),
+                Severity::Warning
+            )]
+        );
+    }
+
+    #[test]
+    fn flags_missing_decoys_and_negative_entrapment() {
+        let config = json!({"fasta_digest_params": {"add_decoys": false, "entrapment_r": -1}});
+        assert_eq!(
+            flagged(config),
+            [
+                (
+                    "fasta_digest_params.add_decoys".to_string(),
+                    Severity::Error
+                ),
+                (
+                    "fasta_digest_params.entrapment_r".to_string(),
+                    Severity::Error
+                )
+            ]
+        );
+    }
+}
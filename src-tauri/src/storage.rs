@@ -0,0 +1,143 @@
+use serde::Serialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::Path;
+use std::time::Instant;
+
+const PROBE_FILENAME: &str = ".pioneer-gui-speed-probe";
+const PROBE_CHUNK_BYTES: usize = 1024 * 1024;
+/// Large enough to get past most write-back caching overhead, small enough to
+/// finish in well under a second on a local disk.
+const PROBE_CHUNKS: usize = 16;
+/// Below this Pioneer's temporary and result files become a bottleneck.
+const SLOW_MB_PER_S: f64 = 50.0;
+
+/// Filesystem types (as listed in `/proc/mounts`) served over the network.
+#[cfg(target_os = "linux")]
+const NETWORK_FILESYSTEMS: &[&str] = &[
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smbfs",
+    "smb3",
+    "sshfs",
+    "fuse.sshfs",
+    "afs",
+    "9p",
+    "davfs",
+    "fuse.rclone",
+];
+
+#[derive(Serialize, Debug)]
+pub struct StorageSpeed {
+    pub path: String,
+    /// The directory benchmarked: `path` itself, or its nearest existing
+    /// ancestor when Pioneer would create `path` during the run.
+    pub probed_path: String,
+    pub bytes: u64,
+    pub write_mb_per_s: f64,
+    pub read_mb_per_s: f64,
+    /// The network filesystem `path` lives on, when one could be identified.
+    pub network_mount: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Writes, syncs, and reads back a small probe file in `dir` for a rough
+/// throughput figure. Reads may be served from the page cache, so the write
+/// rate is the more telling number.
+pub fn check_storage_speed(dir: &Path) -> Result<StorageSpeed, String> {
+    let existing = dir
+        .ancestors()
+        .find(|ancestor| ancestor.is_dir())
+        .ok_or_else(|| format!("{} has no existing parent directory", dir.display()))?;
+    let probe = existing.join(PROBE_FILENAME);
+    let result = measure(&probe);
+    let _ = fs::remove_file(&probe);
+    let (write_secs, read_secs) = result.map_err(|e| format!("{}: {e}", existing.display()))?;
+
+    let bytes = (PROBE_CHUNK_BYTES * PROBE_CHUNKS) as u64;
+    let rate = |secs: f64| bytes as f64 / (1024.0 * 1024.0) / secs.max(1e-6);
+    let write_mb_per_s = rate(write_secs);
+    let read_mb_per_s = rate(read_secs);
+    let network_mount = network_mount(existing);
+
+    let mut warnings = Vec::new();
+    if let Some(mount) = &network_mount {
+        warnings.push(format!(
+            "{} is on a network filesystem ({mount}); Pioneer runs much faster with results and temporary files on a local disk",
+            dir.display()
+        ));
+    }
+    if write_mb_per_s < SLOW_MB_PER_S {
+        warnings.push(format!(
+            "Writing to {} managed only {write_mb_per_s:.1} MB/s",
+            dir.display()
+        ));
+    }
+
+    Ok(StorageSpeed {
+        path: dir.to_string_lossy().to_string(),
+        probed_path: existing.to_string_lossy().to_string(),
+        bytes,
+        write_mb_per_s,
+        read_mb_per_s,
+        network_mount,
+        warnings,
+    })
+}
+
+fn measure(probe: &Path) -> std::io::Result<(f64, f64)> {
+    let chunk = vec![0xA5u8; PROBE_CHUNK_BYTES];
+    let started = Instant::now();
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(probe)?;
+    for _ in 0..PROBE_CHUNKS {
+        file.write_all(&chunk)?;
+    }
+    file.sync_all()?;
+    drop(file);
+    let write_secs = started.elapsed().as_secs_f64();
+
+    let mut buffer = vec![0u8; PROBE_CHUNK_BYTES];
+    let started = Instant::now();
+    let mut file = File::open(probe)?;
+    while file.read(&mut buffer)? > 0 {}
+    Ok((write_secs, started.elapsed().as_secs_f64()))
+}
+
+/// The filesystem type of the longest `/proc/mounts` entry containing `dir`,
+/// if it is a network one.
+#[cfg(target_os = "linux")]
+fn network_mount(dir: &Path) -> Option<String> {
+    let dir = fs::canonicalize(dir).ok()?;
+    let mounts = fs::read_to_string("/proc/mounts").ok()?;
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _device = fields.next()?;
+            let mount_point = fields.next()?.replace("\\040", " ");
+            let fstype = fields.next()?;
+            dir.starts_with(&mount_point)
+                .then(|| (mount_point.len(), fstype.to_string()))
+        })
+        .max_by_key(|(len, _)| *len)
+        .map(|(_, fstype)| fstype)
+        .filter(|fstype| NETWORK_FILESYSTEMS.contains(&fstype.as_str()))
+}
+
+/// UNC paths (`\\server\share`) are the reliable signal on Windows; mapped
+/// drive letters are not detected.
+#[cfg(windows)]
+fn network_mount(dir: &Path) -> Option<String> {
+    let raw = dir.to_string_lossy();
+    (raw.starts_with(r"\\") && !raw.starts_with(r"\\?\")).then(|| "SMB share".to_string())
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn network_mount(_dir: &Path) -> Option<String> {
+    None
+}
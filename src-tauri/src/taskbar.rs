@@ -0,0 +1,90 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{Manager, Window};
+
+use crate::events;
+use crate::RunMode;
+
+/// Mirrors the states OS taskbars and docks can show for a progress badge.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TaskbarStatus {
+    Running,
+    Paused,
+    /// No run is in progress; the badge should be removed.
+    None,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct TaskbarProgressPayload {
+    pub mode: RunMode,
+    pub status: TaskbarStatus,
+    /// Whole percent, 0–100; `None` once the badge is cleared.
+    pub percent: Option<u8>,
+}
+
+/// Last badge sent per workflow, so the event only fires when the rounded
+/// value or status actually changes.
+#[derive(Default)]
+pub struct TaskbarTracker {
+    last: Mutex<HashMap<RunMode, TaskbarProgressPayload>>,
+}
+
+impl TaskbarTracker {
+    fn changed(&self, payload: &TaskbarProgressPayload) -> bool {
+        let Ok(mut last) = self.last.lock() else {
+            return true;
+        };
+        if last.get(&payload.mode) == Some(payload) {
+            return false;
+        }
+        last.insert(payload.mode, payload.clone());
+        true
+    }
+
+    fn last_percent(&self, mode: RunMode) -> Option<u8> {
+        self.last
+            .lock()
+            .ok()
+            .and_then(|last| last.get(&mode).and_then(|payload| payload.percent))
+    }
+}
+
+/// `progress` is 0–100, from the same source as `pioneer-progress` (or the
+/// batch's overall progress when the run is part of one).
+pub fn report_progress(window: &Window, mode: RunMode, progress: f32) {
+    let percent = progress.clamp(0.0, 100.0).round() as u8;
+    send(window, mode, TaskbarStatus::Running, Some(percent));
+}
+
+/// Keeps the last percentage and switches the badge to its paused look.
+pub fn report_paused(window: &Window, mode: RunMode, paused: bool) {
+    let percent = window
+        .try_state::<TaskbarTracker>()
+        .and_then(|tracker| tracker.last_percent(mode));
+    let status = if paused {
+        TaskbarStatus::Paused
+    } else {
+        TaskbarStatus::Running
+    };
+    send(window, mode, status, percent);
+}
+
+pub fn clear(window: &Window, mode: RunMode) {
+    send(window, mode, TaskbarStatus::None, None);
+}
+
+fn send(window: &Window, mode: RunMode, status: TaskbarStatus, percent: Option<u8>) {
+    let payload = TaskbarProgressPayload {
+        mode,
+        status,
+        percent,
+    };
+    let changed = window
+        .try_state::<TaskbarTracker>()
+        .is_none_or(|tracker| tracker.changed(&payload));
+    if changed {
+        let _ = events::emit(window, events::TASKBAR_PROGRESS, &payload);
+    }
+}
@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tauri::PathResolver;
+#[cfg(any(target_os = "linux", windows))]
+use which::which;
+
+pub const TERMINAL_SETTINGS_FILENAME: &str = "terminal.json";
+
+/// Whether the tail terminal exits on its own once the run's end marker is
+/// written, per platform. Off everywhere by default: the window then stays
+/// open until the user closes it, as before.
+#[derive(Serialize, Deserialize, Default, Clone, Copy, Debug)]
+#[serde(default)]
+pub struct TerminalSettings {
+    pub auto_close_linux: bool,
+    pub auto_close_macos: bool,
+    pub auto_close_windows: bool,
+}
+
+impl TerminalSettings {
+    pub fn auto_close(&self) -> bool {
+        match std::env::consts::OS {
+            "linux" => self.auto_close_linux,
+            "macos" => self.auto_close_macos,
+            "windows" => self.auto_close_windows,
+            _ => false,
+        }
+    }
+}
+
+fn settings_path(resolver: &PathResolver) -> Option<PathBuf> {
+    Some(resolver.app_config_dir()?.join(TERMINAL_SETTINGS_FILENAME))
+}
+
+pub fn load_settings(resolver: &PathResolver) -> TerminalSettings {
+    settings_path(resolver)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_settings(resolver: &PathResolver, settings: &TerminalSettings) -> Result<(), String> {
+    let path =
+        settings_path(resolver).ok_or_else(|| "App config directory is unavailable".to_string())?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let pretty = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    fs::write(path, pretty).map_err(|e| e.to_string())
+}
+
+/// Emulators tried on Linux, in order of preference.
+#[cfg(target_os = "linux")]
+const LINUX_TERMINALS: &[&str] = &[
+    "x-terminal-emulator",
+    "gnome-terminal",
+    "konsole",
+    "xfce4-terminal",
+    "mate-terminal",
+    "xterm",
+];
+
+#[cfg(target_os = "linux")]
+/// The first installed emulator from `LINUX_TERMINALS`.
+pub fn linux_terminal() -> Option<&'static str> {
+    LINUX_TERMINALS
+        .iter()
+        .copied()
+        .find(|term| which(term).is_ok())
+}
+
+/// The terminal `open_terminal_tail` would use, if it could open a window at
+/// all. On Linux an emulator without a display to draw on counts as none.
+pub fn terminal_available() -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        let has_display = ["DISPLAY", "WAYLAND_DISPLAY"]
+            .iter()
+            .any(|var| std::env::var_os(var).is_some_and(|value| !value.is_empty()));
+        has_display
+            .then(linux_terminal)
+            .flatten()
+            .map(str::to_string)
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let installed = [
+            "/System/Applications/Utilities/Terminal.app",
+            "/Applications/Utilities/Terminal.app",
+        ]
+        .iter()
+        .any(|app| Path::new(app).is_dir());
+        (installed && Path::new("/usr/bin/osascript").is_file()).then(|| "Terminal".to_string())
+    }
+
+    #[cfg(windows)]
+    {
+        which("powershell").ok().map(|_| "PowerShell".to_string())
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+    {
+        None
+    }
+}
+
+/// Unique per run so a terminal tailing a reused log path never stops on an
+/// earlier run's marker.
+pub fn end_token(run_key: &str) -> String {
+    format!("[pioneer-gui-end:{run_key}]")
+}
+
+/// Appends the closing line the tail terminal waits for. It is always written,
+/// so even a terminal left open shows plainly that the run is over.
+pub fn write_end_marker(log_path: &Path, token: &str, summary: &str) -> std::io::Result<()> {
+    let mut log = OpenOptions::new().append(true).open(log_path)?;
+    writeln!(log, "=== Pioneer GUI: {summary} {token} ===")
+}
+
+#[cfg(unix)]
+/// Follows the log given as `$1` and exits once a line containing `$2`
+/// appears, taking the `tail` process down with it. `tail` runs as a plain
+/// background job feeding a FIFO, since `$!` only names a process
+/// substitution from bash 4.4 on, and macOS ships bash 3.2.
+const TAIL_SCRIPT: &str = r#"#!/usr/bin/env bash
+fifo=$(mktemp -u "${TMPDIR:-/tmp}/pioneer-gui-tail.XXXXXX")
+mkfifo -m 600 "$fifo" || exit 1
+tail -n +1 -f "$1" > "$fifo" &
+tail_pid=$!
+exec 3< "$fifo"
+rm -f "$fifo"
+while IFS= read -r line <&3; do
+  printf '%s\n' "$line"
+  case "$line" in
+    *"$2"*) break ;;
+  esac
+done
+kill "$tail_pid" 2>/dev/null
+"#;
+
+#[cfg(unix)]
+/// Writes the auto-closing tail script next to the log. Passing the log path
+/// and token as arguments keeps them out of any shell quoting.
+pub fn write_tail_script(log_path: &Path) -> std::io::Result<PathBuf> {
+    let script = log_path.with_extension("tail.sh");
+    fs::write(&script, TAIL_SCRIPT)?;
+    Ok(script)
+}
+
+#[cfg(target_os = "macos")]
+/// Single-quotes `value` for a POSIX shell.
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
@@ -0,0 +1,360 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+use crate::diff::{diff_config, lookup};
+use crate::RunMode;
+
+pub const PARAM_RANGES_FILENAME: &str = "param_ranges.json";
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ValidationIssue {
+    pub path: String,
+    pub message: String,
+}
+
+impl ValidationIssue {
+    pub fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        ValidationIssue {
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct RangeRule {
+    pub path: String,
+    #[serde(default)]
+    pub min: Option<f64>,
+    #[serde(default)]
+    pub max: Option<f64>,
+    #[serde(default)]
+    pub min_exclusive: bool,
+    #[serde(default)]
+    pub max_exclusive: bool,
+    #[serde(default)]
+    pub integer: bool,
+}
+
+/// Shape of the optional `param_ranges.json` override file. Rules whose path
+/// matches a built-in rule replace it; the rest are appended.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct RangeTableFile {
+    build_spec_lib: Vec<RangeRule>,
+    search_dia: Vec<RangeRule>,
+}
+
+const fn rule(path: &'static str, min: Option<f64>, max: Option<f64>) -> BuiltinRule {
+    BuiltinRule {
+        path,
+        min,
+        max,
+        min_exclusive: false,
+        max_exclusive: false,
+        integer: false,
+    }
+}
+
+struct BuiltinRule {
+    path: &'static str,
+    min: Option<f64>,
+    max: Option<f64>,
+    min_exclusive: bool,
+    max_exclusive: bool,
+    integer: bool,
+}
+
+impl BuiltinRule {
+    const fn positive(mut self) -> Self {
+        self.min_exclusive = true;
+        self
+    }
+
+    const fn integer(mut self) -> Self {
+        self.integer = true;
+        self
+    }
+
+    fn to_rule(&self) -> RangeRule {
+        RangeRule {
+            path: self.path.to_string(),
+            min: self.min,
+            max: self.max,
+            min_exclusive: self.min_exclusive,
+            max_exclusive: self.max_exclusive,
+            integer: self.integer,
+        }
+    }
+}
+
+const BUILD_RANGES: &[BuiltinRule] = &[
+    rule("fasta_digest_params.min_length", Some(1.0), None).integer(),
+    rule("fasta_digest_params.max_length", Some(1.0), None).integer(),
+    rule("fasta_digest_params.min_charge", Some(1.0), None).integer(),
+    rule("fasta_digest_params.max_charge", Some(1.0), None).integer(),
+    rule("fasta_digest_params.missed_cleavages", Some(0.0), None).integer(),
+    rule("fasta_digest_params.max_var_mods", Some(0.0), None).integer(),
+    rule("nce_params.nce", Some(0.0), None).positive(),
+    rule("library_params.frag_bin_tol_ppm", Some(0.0), None).positive(),
+    rule("library_params.rt_bin_tol", Some(0.0), None).positive(),
+    rule("library_params.frag_mz_min", Some(0.0), None).positive(),
+    rule("library_params.frag_mz_max", Some(0.0), None).positive(),
+    rule("library_params.prec_mz_min", Some(0.0), None).positive(),
+    rule("library_params.prec_mz_max", Some(0.0), None).positive(),
+    rule("library_params.max_frag_charge", Some(1.0), None).integer(),
+    rule("max_koina_requests", Some(1.0), None).integer(),
+    rule("max_koina_batch", Some(1.0), None).integer(),
+];
+
+const SEARCH_RANGES: &[BuiltinRule] = &[
+    rule("global.scoring.q_value_threshold", Some(0.0), Some(1.0)).positive(),
+    rule(
+        "parameter_tuning.search_settings.max_q_value",
+        Some(0.0),
+        Some(1.0),
+    )
+    .positive(),
+    rule(
+        "parameter_tuning.iteration_settings.init_mass_tol_ppm",
+        Some(0.0),
+        None,
+    )
+    .positive(),
+    rule(
+        "parameter_tuning.fragment_settings.intensity_filter_quantile",
+        Some(0.0),
+        Some(1.0),
+    ),
+    rule(
+        "first_search.scoring_settings.max_q_value_probit_rescore",
+        Some(0.0),
+        Some(1.0),
+    )
+    .positive(),
+    rule(
+        "first_search.scoring_settings.max_PEP",
+        Some(0.0),
+        Some(1.0),
+    ),
+    rule("first_search.fragment_settings.min_count", Some(1.0), None).integer(),
+    rule("quant_search.fragment_settings.min_count", Some(1.0), None).integer(),
+    rule("quant_search.fragment_settings.n_isotopes", Some(1.0), None).integer(),
+    rule("acquisition.nce", Some(0.0), None).positive(),
+    rule("rt_alignment.min_probability", Some(0.0), Some(1.0)),
+    rule(
+        "optimization.machine_learning.qvalue_threshold",
+        Some(0.0),
+        Some(1.0),
+    )
+    .positive(),
+    rule(
+        "optimization.machine_learning.validation_split_ratio",
+        Some(0.0),
+        Some(1.0),
+    )
+    .positive(),
+    rule("output.plots_per_page", Some(1.0), None).integer(),
+];
+
+pub fn range_table(mode: RunMode, overrides: Option<&Path>) -> Result<Vec<RangeRule>, String> {
+    let builtin = match mode {
+        RunMode::BuildSpecLib => BUILD_RANGES,
+        RunMode::SearchDia => SEARCH_RANGES,
+    };
+    let mut rules: Vec<RangeRule> = builtin.iter().map(BuiltinRule::to_rule).collect();
+
+    let Some(path) = overrides.filter(|p| p.is_file()) else {
+        return Ok(rules);
+    };
+    let contents = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let file: RangeTableFile =
+        serde_json::from_str(&contents).map_err(|e| format!("Invalid {}: {e}", path.display()))?;
+    let extra = match mode {
+        RunMode::BuildSpecLib => file.build_spec_lib,
+        RunMode::SearchDia => file.search_dia,
+    };
+    for rule in extra {
+        match rules.iter_mut().find(|existing| existing.path == rule.path) {
+            Some(existing) => *existing = rule,
+            None => rules.push(rule),
+        }
+    }
+    Ok(rules)
+}
+
+/// Checks every rule whose path is present in `config`; missing keys are left
+/// to the schema-level checks. Numeric arrays are checked element-wise.
+pub fn validate_param_ranges(config: &Value, rules: &[RangeRule]) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    for rule in rules {
+        let Some(value) = lookup(config, &rule.path) else {
+            continue;
+        };
+        let values: Vec<&Value> = match value {
+            Value::Array(items) => items.iter().collect(),
+            other => vec![other],
+        };
+        for value in values {
+            let Some(number) = value.as_f64() else {
+                issues.push(ValidationIssue::new(
+                    &rule.path,
+                    format!("Expected a number, found {value}"),
+                ));
+                continue;
+            };
+            if let Some(message) = check_range(number, rule) {
+                issues.push(ValidationIssue::new(&rule.path, message));
+            }
+        }
+    }
+    issues
+}
+
+fn check_range(value: f64, rule: &RangeRule) -> Option<String> {
+    if rule.integer && value.fract() != 0.0 {
+        return Some(format!("{value} must be a whole number"));
+    }
+    if let Some(min) = rule.min {
+        if value < min || (rule.min_exclusive && value == min) {
+            let bound = if rule.min_exclusive { ">" } else { ">=" };
+            return Some(format!("{value} is out of range; expected {bound} {min}"));
+        }
+    }
+    if let Some(max) = rule.max {
+        if value > max || (rule.max_exclusive && value == max) {
+            let bound = if rule.max_exclusive { "<" } else { "<=" };
+            return Some(format!("{value} is out of range; expected {bound} {max}"));
+        }
+    }
+    None
+}
+
+/// Compares `config` against the binary's defaults, which list every
+/// parameter that version accepts: keys the defaults lack are reported as
+/// unknown (usually a typo or a key from another release), keys the config
+/// lacks as missing, and values whose JSON type differs from the default's as
+/// mismatched. Arrays are compared as whole values.
+pub fn validate_config_keys(config: &Value, defaults: &Value) -> Vec<ValidationIssue> {
+    let diff = diff_config(defaults, config);
+    let mut issues = Vec::new();
+    for entry in &diff.added {
+        issues.push(ValidationIssue::new(
+            &entry.path,
+            "Not a known parameter for this Pioneer version",
+        ));
+    }
+    for entry in &diff.removed {
+        let default = entry.old_value.as_ref().unwrap_or(&Value::Null);
+        issues.push(ValidationIssue::new(
+            &entry.path,
+            format!("Missing; the default is {default}"),
+        ));
+    }
+    for entry in &diff.changed {
+        let (Some(expected), Some(found)) = (&entry.old_value, &entry.new_value) else {
+            continue;
+        };
+        // Null defaults carry no type, so anything may replace them.
+        if expected.is_null() || json_type(expected) == json_type(found) {
+            continue;
+        }
+        issues.push(ValidationIssue::new(
+            &entry.path,
+            format!(
+                "Expected {}, found {}",
+                json_type(expected),
+                json_type(found)
+            ),
+        ));
+    }
+    issues
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{range_table, validate_config_keys, validate_param_ranges, PARAM_RANGES_FILENAME};
+    use crate::RunMode;
+    use serde_json::json;
+    use std::fs;
+
+    fn flagged(config: serde_json::Value, mode: RunMode) -> Vec<String> {
+        let rules = range_table(mode, None).expect("built-in rules");
+        validate_param_ranges(&config, &rules)
+            .into_iter()
+            .map(|issue| issue.path)
+            .collect()
+    }
+
+    #[test]
+    fn checks_bounds_exclusivity_and_whole_numbers() {
+        let config = json!({
+            "fasta_digest_params": {"min_length": 7, "max_length": 0, "min_charge": 2.5},
+            "nce_params": {"nce": 0},
+            "library_params": {"frag_mz_min": 150.0},
+        });
+        assert_eq!(
+            flagged(config, RunMode::BuildSpecLib),
+            [
+                "fasta_digest_params.max_length",
+                "fasta_digest_params.min_charge",
+                "nce_params.nce"
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_arrays_element_wise_and_skips_missing_keys() {
+        let config = json!({"global": {"scoring": {"q_value_threshold": [0.01, 1.5, "x"]}}});
+        assert_eq!(
+            flagged(config, RunMode::SearchDia),
+            [
+                "global.scoring.q_value_threshold",
+                "global.scoring.q_value_threshold"
+            ]
+        );
+    }
+
+    #[test]
+    fn override_file_replaces_and_extends_rules() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join(PARAM_RANGES_FILENAME);
+        fs::write(
+            &path,
+            r#"{"searchDia": [
+                {"path": "output.plots_per_page", "min": 5},
+                {"path": "custom.limit", "max": 2}
+            ]}"#,
+        )
+        .expect("write overrides");
+        let rules = range_table(RunMode::SearchDia, Some(&path)).expect("rules");
+        let config = json!({"output": {"plots_per_page": 3.5}, "custom": {"limit": 3}});
+        let issues = validate_param_ranges(&config, &rules);
+        // The replacement rule no longer requires a whole number.
+        assert_eq!(issues.len(), 2);
+        assert!(issues[0].message.contains(">= 5"));
+        assert_eq!(issues[1].path, "custom.limit");
+    }
+
+    #[test]
+    fn key_check_reports_unknown_missing_and_mistyped() {
+        let defaults = json!({"a": 1, "b": "x", "c": null, "d": true});
+        let config = json!({"a": "1", "c": [1], "d": false, "e": 0});
+        let issues = validate_config_keys(&config, &defaults);
+        let paths: Vec<&str> = issues.iter().map(|issue| issue.path.as_str()).collect();
+        assert_eq!(paths, ["e", "b", "a"]);
+    }
+}
@@ -0,0 +1,316 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::PathResolver;
+use tempfile::TempDir;
+
+/// Every tempdir the app creates carries this prefix so leftovers from a
+/// crashed session can be recognised and cleaned up later.
+pub const TEMP_DIR_PREFIX: &str = "pioneer-gui-";
+pub const RUNS_DIR: &str = "runs";
+pub const RETENTION_SETTINGS_FILENAME: &str = "retention.json";
+
+const DEFAULT_KEEP_RUNS: usize = 50;
+const DEFAULT_MAX_RUN_AGE_DAYS: u64 = 30;
+const DEFAULT_ORPHAN_MIN_AGE_HOURS: u64 = 24;
+
+pub fn app_tempdir() -> io::Result<TempDir> {
+    tempfile::Builder::new().prefix(TEMP_DIR_PREFIX).tempdir()
+}
+
+pub fn runs_root(resolver: &PathResolver) -> Option<PathBuf> {
+    Some(resolver.app_data_dir()?.join(RUNS_DIR))
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct CleanWorkspaceOptions {
+    /// Only report what would be removed unless this is set.
+    pub delete: bool,
+    pub clear_cache: bool,
+    pub keep_runs: Option<usize>,
+    pub max_run_age_days: Option<u64>,
+    pub orphan_min_age_hours: Option<u64>,
+}
+
+#[derive(Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum CleanupKind {
+    Cache,
+    RunDirectory,
+    OrphanedTempDir,
+}
+
+#[derive(Serialize)]
+pub struct CleanupItem {
+    pub path: String,
+    pub kind: CleanupKind,
+    pub bytes: u64,
+    pub removed: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Default)]
+pub struct CleanWorkspaceReport {
+    pub items: Vec<CleanupItem>,
+    pub bytes_found: u64,
+    pub bytes_reclaimed: u64,
+    /// Run directories and tempdirs left alone because a run still uses them.
+    pub skipped_active: Vec<String>,
+}
+
+/// `is_active` is consulted per run id and `in_use` per tempdir, as in
+/// `prune_runs`, so nothing a live run reads or writes is reported or removed.
+/// Staged inputs are symlinks whose own mtimes never change, so age alone
+/// cannot tell a long search's tempdir from an orphan.
+pub fn clean_workspace(
+    resolver: &PathResolver,
+    options: &CleanWorkspaceOptions,
+    is_active: impl Fn(&str) -> bool,
+    in_use: impl Fn(&Path) -> bool,
+) -> CleanWorkspaceReport {
+    let mut report = CleanWorkspaceReport::default();
+    let mut candidates: Vec<(PathBuf, CleanupKind, PathBuf)> = Vec::new();
+
+    if options.clear_cache {
+        if let Some(cache_dir) = resolver.app_cache_dir() {
+            for entry in list_dir(&cache_dir) {
+                candidates.push((entry, CleanupKind::Cache, cache_dir.clone()));
+            }
+        }
+    }
+
+    if let Some(runs_dir) = runs_root(resolver) {
+        let keep = options.keep_runs.unwrap_or(DEFAULT_KEEP_RUNS);
+        let max_age = days(options.max_run_age_days.unwrap_or(DEFAULT_MAX_RUN_AGE_DAYS));
+        for run_dir in expired_runs(&runs_dir, keep, max_age) {
+            let run_id = run_dir
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            if is_active(&run_id) {
+                report
+                    .skipped_active
+                    .push(run_dir.to_string_lossy().to_string());
+                continue;
+            }
+            candidates.push((run_dir, CleanupKind::RunDirectory, runs_dir.clone()));
+        }
+    }
+
+    let temp_root = std::env::temp_dir();
+    let orphan_age = Duration::from_secs(
+        options
+            .orphan_min_age_hours
+            .unwrap_or(DEFAULT_ORPHAN_MIN_AGE_HOURS)
+            * 3600,
+    );
+    for entry in list_dir(&temp_root) {
+        let is_ours = entry
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name.starts_with(TEMP_DIR_PREFIX))
+            .unwrap_or(false);
+        if !is_ours || !entry.is_dir() || age(&latest_mtime(&entry)) < orphan_age {
+            continue;
+        }
+        if in_use(&entry) {
+            report
+                .skipped_active
+                .push(entry.to_string_lossy().to_string());
+            continue;
+        }
+        candidates.push((entry, CleanupKind::OrphanedTempDir, temp_root.clone()));
+    }
+
+    for (path, kind, root) in candidates {
+        let bytes = disk_usage(&path);
+        report.bytes_found += bytes;
+        let mut item = CleanupItem {
+            path: path.to_string_lossy().to_string(),
+            kind,
+            bytes,
+            removed: false,
+            error: None,
+        };
+        if options.delete {
+            match remove_within(&path, &root) {
+                Ok(()) => {
+                    item.removed = true;
+                    report.bytes_reclaimed += bytes;
+                }
+                Err(err) => item.error = Some(err),
+            }
+        }
+        report.items.push(item);
+    }
+    report
+}
+
+/// Run directories beyond the newest `keep`, plus any older than `max_age`.
+pub fn expired_runs(runs_dir: &Path, keep: usize, max_age: Duration) -> Vec<PathBuf> {
+    let mut runs: Vec<(PathBuf, SystemTime)> = list_dir(runs_dir)
+        .into_iter()
+        .filter(|path| path.is_dir())
+        .map(|path| {
+            let modified = latest_mtime(&path);
+            (path, modified)
+        })
+        .collect();
+    runs.sort_by_key(|(_, modified)| std::cmp::Reverse(*modified));
+
+    runs.into_iter()
+        .enumerate()
+        .filter(|(idx, (_, modified))| *idx >= keep || age(modified) > max_age)
+        .map(|(_, (path, _))| path)
+        .collect()
+}
+
+/// Startup pruning of run directories, persisted in `retention.json`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    pub enabled: bool,
+    pub keep_runs: usize,
+    pub max_age_days: u64,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            keep_runs: DEFAULT_KEEP_RUNS,
+            max_age_days: DEFAULT_MAX_RUN_AGE_DAYS,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Default)]
+pub struct PruneReport {
+    pub pruned: Vec<String>,
+    pub bytes_reclaimed: u64,
+    /// Expired runs kept because they are still running.
+    pub skipped_active: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+fn retention_settings_path(resolver: &PathResolver) -> Option<PathBuf> {
+    Some(resolver.app_config_dir()?.join(RETENTION_SETTINGS_FILENAME))
+}
+
+pub fn load_retention_policy(resolver: &PathResolver) -> RetentionPolicy {
+    retention_settings_path(resolver)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_retention_policy(
+    resolver: &PathResolver,
+    policy: &RetentionPolicy,
+) -> Result<(), String> {
+    let path = retention_settings_path(resolver)
+        .ok_or_else(|| "App config directory is unavailable".to_string())?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let pretty = serde_json::to_string_pretty(policy).map_err(|e| e.to_string())?;
+    fs::write(path, pretty).map_err(|e| e.to_string())
+}
+
+/// Removes run directories the policy expires. `is_active` is consulted per
+/// run id so a run still executing is never deleted from under Pioneer.
+pub fn prune_runs(
+    runs_dir: &Path,
+    policy: &RetentionPolicy,
+    is_active: impl Fn(&str) -> bool,
+) -> PruneReport {
+    let mut report = PruneReport::default();
+    if !policy.enabled {
+        return report;
+    }
+    for run_dir in expired_runs(runs_dir, policy.keep_runs, days(policy.max_age_days)) {
+        let run_id = run_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if is_active(&run_id) {
+            report.skipped_active.push(run_id);
+            continue;
+        }
+        let bytes = disk_usage(&run_dir);
+        match remove_within(&run_dir, runs_dir) {
+            Ok(()) => {
+                report.pruned.push(run_id);
+                report.bytes_reclaimed += bytes;
+            }
+            Err(err) => report.errors.push(format!("{run_id}: {err}")),
+        }
+    }
+    report
+}
+
+/// Deletes `path` only if it resolves inside `root`, so a bad symlink or
+/// configuration can never make cleanup wander outside the app's directories.
+pub fn remove_within(path: &Path, root: &Path) -> Result<(), String> {
+    let root = root.canonicalize().map_err(|e| e.to_string())?;
+    let parent = path
+        .parent()
+        .ok_or_else(|| format!("Refusing to remove {}", path.display()))?
+        .canonicalize()
+        .map_err(|e| e.to_string())?;
+    if !parent.starts_with(&root) {
+        return Err(format!(
+            "Refusing to remove {} outside of {}",
+            path.display(),
+            root.display()
+        ));
+    }
+
+    let metadata = fs::symlink_metadata(path).map_err(|e| e.to_string())?;
+    if metadata.is_dir() {
+        fs::remove_dir_all(path).map_err(|e| e.to_string())
+    } else {
+        fs::remove_file(path).map_err(|e| e.to_string())
+    }
+}
+
+pub fn list_dir(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| entries.filter_map(|e| e.ok().map(|e| e.path())).collect())
+        .unwrap_or_default()
+}
+
+/// Size on disk without following symlinks.
+pub fn disk_usage(path: &Path) -> u64 {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if metadata.is_dir() {
+        list_dir(path).iter().map(|child| disk_usage(child)).sum()
+    } else {
+        metadata.len()
+    }
+}
+
+/// Most recent modification time of a directory or its direct children; a
+/// log being appended to keeps an otherwise static run directory "fresh".
+fn latest_mtime(path: &Path) -> SystemTime {
+    let own = fs::metadata(path)
+        .and_then(|m| m.modified())
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+    list_dir(path)
+        .iter()
+        .filter_map(|child| fs::symlink_metadata(child).and_then(|m| m.modified()).ok())
+        .fold(own, |latest, modified| latest.max(modified))
+}
+
+fn age(time: &SystemTime) -> Duration {
+    SystemTime::now().duration_since(*time).unwrap_or_default()
+}
+
+fn days(count: u64) -> Duration {
+    Duration::from_secs(count * 24 * 3600)
+}
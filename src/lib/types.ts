@@ -14,15 +14,69 @@ export interface LoadConfigsResponse {
   search: ConfigSet;
   source: ConfigSource;
   binary_error?: string | null;
+  version_warnings?: ConfigVersionWarning[];
+  cache?: DefaultsCacheReport;
+}
+
+export type CacheStatus = 'hit' | 'miss' | 'stale' | 'unavailable';
+
+export interface DefaultsCacheReport {
+  build: CacheStatus;
+  search: CacheStatus;
 }
 
 export type RunMode = 'buildSpecLib' | 'searchDia';
 
+export interface ModeConfigResponse {
+  mode: RunMode;
+  config: ConfigSet;
+  source: ConfigSource;
+  binary_error?: string | null;
+  version_warning?: ConfigVersionWarning | null;
+}
+
+export interface ConfigVersionWarning {
+  mode: RunMode;
+  persisted_version: string;
+  current_version: string;
+  message: string;
+}
+
 export interface RunStartedPayload {
   mode: RunMode;
+  run_id?: string | null;
   log_path: string;
   config_path: string;
   persisted_path?: string | null;
+  max_files?: number | null;
+  stdout_log_path?: string | null;
+  stderr_log_path?: string | null;
+  storage_warnings: string[];
+  resume?: ResumePlan | null;
+}
+
+export interface CommandPreview {
+  mode: RunMode;
+  command_line: string;
+  program: string;
+  args: string[];
+  env: Record<string, string>;
+  config_path: string;
+  config: string;
+}
+
+export interface ResumePlan {
+  previous_run_id: string;
+  results_dir: string;
+  resume_stage: string;
+  skipped_stages: string[];
+}
+
+export interface PreparePayload {
+  mode: RunMode;
+  step: string;
+  current?: number | null;
+  total?: number | null;
 }
 
 export interface ProgressPayload {
@@ -30,21 +84,634 @@ export interface ProgressPayload {
   stage_key: string;
   stage_label: string;
   progress: number;
+  eta_seconds?: number | null;
 }
 
 export interface LogPayload {
   mode: RunMode;
-  stream: 'stdout' | 'stderr';
+  stream: 'stdout' | 'stderr' | 'combined';
   line: string;
+  severity?: Severity | null;
+}
+
+export type Severity = 'error' | 'warning';
+
+export interface LogLevelPayload {
+  mode: RunMode;
+  run_id?: string | null;
+  stream: 'stdout' | 'stderr' | 'combined';
+  severity: Severity;
+  line: string;
+  error_count: number;
+  warning_count: number;
+}
+
+export interface LogHealth {
+  errors: number;
+  warnings: number;
+  error_samples: string[];
+  warning_samples: string[];
+}
+
+export interface LogPatterns {
+  error: string[];
+  warning: string[];
 }
 
+export interface LineClassification {
+  line: string;
+  severity: Severity | null;
+}
+
+export type FailureCategory = 'outOfMemory' | 'diskFull' | 'permission' | 'missingInput' | 'timedOut' | 'unknown';
+
 export interface RunCompletePayload {
   mode: RunMode;
   success: boolean;
-  exit_code: number | null;
+  exitCode: number | null;
+  message?: string | null;
+  failureCategory?: FailureCategory | null;
+  outputs?: RunOutputs | null;
+  cancelled: boolean;
+  errorCount: number;
+  warningCount: number;
+  logHealth?: LogHealth | null;
+}
+
+export interface OutputFile {
+  path: string;
+  size: number;
+}
+
+export interface RunOutputs {
+  files: OutputFile[];
+  truncated: boolean;
+  error?: string | null;
+}
+
+export interface RunMetadata {
+  run_id: string;
+  mode: RunMode;
+  label?: string | null;
+  started_at: number;
+  finished_at?: number | null;
+  success?: boolean | null;
+  exit_code?: number | null;
+  config_path: string;
+  log_path: string;
+  stage_durations?: Record<string, number>;
+  input_hashes?: InputHashes;
+  environment?: RunEnvironment;
+  resumed_from?: string;
+}
+
+export interface ValidationIssue {
+  path: string;
+  message: string;
+}
+
+export interface WritableCheck {
+  key: string;
+  path: string;
+  probed_path?: string | null;
+  writable: boolean;
+  error?: string | null;
+}
+
+export interface ExistingResults {
+  key: string;
+  path: string;
+  entries: string[];
+  count: number;
+  message: string;
+}
+
+export interface DiskSpaceCheck {
+  key: string;
+  path: string;
+  probed_path?: string | null;
+  free_bytes?: number | null;
+  min_free_bytes: number;
+  low: boolean;
+  message?: string | null;
+}
+
+export interface PreflightReport {
+  inputs: ValidationIssue[];
+  outputs: WritableCheck[];
+  existing_results: ExistingResults[];
+  disk_space: DiskSpaceCheck[];
+  clear: boolean;
+}
+
+export interface DiffEntry {
+  path: string;
+  old_value?: unknown;
+  new_value?: unknown;
+}
+
+export interface PersistedConfigDiff {
+  mode: RunMode;
+  source: ConfigSource;
+  persisted_path?: string | null;
+  added: DiffEntry[];
+  removed: DiffEntry[];
+  changed: DiffEntry[];
+}
+
+export interface ConfigProfile {
+  name: string;
+  path: string;
+  bytes: number;
+  modified?: number | null;
+}
+
+export interface ConfigBackup {
+  index: number;
+  path: string;
+  bytes: number;
+  modified?: number | null;
+}
+
+export interface HistoryRecord {
+  run_id?: string | null;
+  mode: RunMode;
+  started_at: number;
+  finished_at: number;
+  success: boolean;
+  cancelled: boolean;
+  exit_code?: number | null;
+  config_path: string;
+  log_path: string;
+  message?: string | null;
+}
+
+export interface RunEnvironment {
+  os: string;
+  os_version: string | null;
+  arch: string;
+  cpu_model: string | null;
+  logical_cores: number | null;
+  total_memory_bytes: number | null;
+  pioneer_path: string;
+  pioneer_version: string | null;
+  gui_version: string;
+  env_vars: Record<string, string>;
+}
+
+export interface InputHash {
+  key: string;
+  path: string;
+  kind: 'file' | 'manifest';
+  sha256: string;
+  files: number;
+  bytes: number;
+  content_hashed: boolean;
+}
+
+export interface InputHashes {
+  mode: RunMode;
+  full_content: boolean;
+  inputs: InputHash[];
+  errors: string[];
+}
+
+export interface StageWeight {
+  key: string;
+  label: string;
+  weight: number;
+  mean_seconds?: number | null;
+}
+
+export interface StageWeights {
+  mode: RunMode;
+  learned: boolean;
+  runs_used: number;
+  stages: StageWeight[];
+}
+
+export interface InstallProgressPayload {
+  url: string;
+  downloaded: number;
+  total?: number | null;
+}
+
+export interface SectionFile {
+  key: string;
+  file: string;
+}
+
+export interface SectionManifest {
+  mode: RunMode;
+  sections: SectionFile[];
+}
+
+export interface AssembledConfig {
+  mode: RunMode;
+  config: JsonValue;
+}
+
+export interface FilenameCollision {
+  name: string;
+  paths: string[];
+}
+
+export interface ParallelismReport {
+  mode: RunMode;
+  logical_cores?: number | null;
+  configured: { path: string; value: JsonValue }[];
+  env_overrides: { name: string; value: string }[];
+  effective_threads?: number | null;
+  warnings: string[];
+}
+
+export interface StageTimingPayload {
+  mode: RunMode;
+  run_id?: string | null;
+  stage_key: string;
+  elapsed_ms: number;
+  next_stage_key: string;
+}
+
+export interface StageDuration {
+  stage_key: string;
+  elapsed_ms: number;
+}
+
+export interface StageSummaryPayload {
+  mode: RunMode;
+  run_id?: string | null;
+  stages: StageDuration[];
+  total_ms: number;
+}
+
+export interface StageDebugPayload {
+  mode: RunMode;
+  line: string;
+  matched_stage?: string | null;
+  stage_index: number;
+  stage_key: string;
+}
+
+export type RunState = 'running' | 'paused' | 'succeeded' | 'failed' | 'unknown';
+
+export interface RunStatus {
+  run_id: string;
+  mode: RunMode;
+  state: RunState;
+  pid?: number | null;
+}
+
+export interface PathChange {
+  path: string;
+  old_value: string;
+  new_value: string;
+}
+
+export interface NormalizedPaths {
+  config: JsonValue;
+  changes: PathChange[];
+  warnings: string[];
+}
+
+export interface LogPeek {
+  run_id?: string | null;
+  mode?: RunMode | null;
+  head: string[];
+  tail: string[];
+  lines: number;
+  clamped: boolean;
+}
+
+export interface FormatRange {
+  min: number;
+  max: number;
+}
+
+export type FormatStatus = 'compatible' | 'incompatible' | 'unknown';
+
+export interface LibraryVersionCheck {
+  path: string;
+  format_version?: number | null;
+  supported_formats?: FormatRange | null;
+  status: FormatStatus;
   message?: string | null;
 }
 
+export interface Redaction {
+  path: string;
+  placeholder: string;
+  original: string;
+}
+
+export interface RedactedConfig {
+  config: JsonValue;
+  redactions: Redaction[];
+}
+
+export interface BridgeStatus {
+  running: boolean;
+  port: number | null;
+  /** Pass as `?token=` when connecting; renewed each time the bridge starts. */
+  token: string | null;
+  clients: number;
+}
+
+export interface FileNormalization {
+  path: string;
+  bom_removed: boolean;
+  line_endings_changed: boolean;
+  normalized: boolean;
+}
+
+export interface RetentionPolicy {
+  enabled: boolean;
+  keep_runs: number;
+  max_age_days: number;
+}
+
+export interface PruneReport {
+  pruned: string[];
+  bytes_reclaimed: number;
+  skipped_active: string[];
+  errors: string[];
+}
+
+export interface TerminalSettings {
+  auto_close_linux: boolean;
+  auto_close_macos: boolean;
+  auto_close_windows: boolean;
+}
+
+export interface MsDataUnit {
+  name: string;
+  path: string;
+  kind: 'file' | 'directory';
+  bytes: number;
+}
+
+export interface MsDataInventory {
+  dir: string;
+  units: MsDataUnit[];
+  file_count: number;
+  directory_count: number;
+  total_bytes: number;
+  ignored_directories: string[];
+}
+
+export interface WarmupReport {
+  path: string;
+  bytes: number;
+  elapsed_ms: number;
+  already_warm: boolean;
+}
+
+export interface RangeMismatch {
+  field: string;
+  build_path: string;
+  search_path: string;
+  build_range: [number, number];
+  search_range: [number, number];
+  search_exceeds_build: boolean;
+  message: string;
+}
+
+export interface UncheckedRange {
+  field: string;
+  reason: string;
+}
+
+export interface RangeConsistency {
+  mismatches: RangeMismatch[];
+  unchecked: UncheckedRange[];
+}
+
+export interface BatchProgressPayload {
+  batch_id: string;
+  total_runs: number;
+  completed_runs: number;
+  failed_runs: number;
+  progress: number;
+  done: boolean;
+}
+
+export interface AuxiliaryDatabase {
+  key: string;
+  path: string;
+  entries: number | null;
+  problem: string | null;
+}
+
+export interface StorageSpeed {
+  path: string;
+  probed_path: string;
+  bytes: number;
+  write_mb_per_s: number;
+  read_mb_per_s: number;
+  network_mount: string | null;
+  warnings: string[];
+}
+
+export interface ModelInfo {
+  name: string;
+  version: string | null;
+  path: string;
+}
+
+export interface ModelList {
+  binary_version: string | null;
+  models: ModelInfo[];
+  searched_dirs: string[];
+  cached: boolean;
+  message: string | null;
+}
+
+export interface MemoryBudget {
+  mode: RunMode;
+  threads: number;
+  threads_assumed: boolean;
+  input_bytes: number;
+  precursors: number | null;
+  estimated_peak_bytes: number;
+  total_memory_bytes: number | null;
+  threshold_bytes: number | null;
+  likely_to_exceed: boolean;
+  suggested_threads: number | null;
+  warnings: string[];
+}
+
+export interface SchemaViolation {
+  instance_path: string;
+  schema_path: string;
+  message: string;
+}
+
+export interface DiskSpaceSettings {
+  min_free_bytes: number;
+}
+
+export interface OutputSubdirs {
+  buildSpecLib: string[];
+  searchDia: string[];
+}
+
+export interface StatisticsIssue {
+  path: string;
+  value: unknown;
+  severity: Severity;
+  message: string;
+  guidance: string;
+}
+
+export interface LastCompletion extends RunCompletePayload {
+  runId: string | null;
+}
+
+export type StagingStrategy = 'symlink' | 'copy';
+
+export interface StagingCheck {
+  key: string;
+  path: string;
+  strategy: StagingStrategy;
+  reason: string | null;
+  copy_bytes: number;
+}
+
+export type BinarySource =
+  | { kind: 'configured' }
+  | { kind: 'envVar'; name: string }
+  | { kind: 'path' };
+
+export interface BinaryCheck {
+  path: string;
+  source: BinarySource;
+  executable: boolean;
+}
+
+export interface BinaryCapabilities {
+  path: string;
+  subcommands: string[];
+  predict: boolean;
+  search: boolean;
+  params_predict: boolean;
+  params_search: boolean;
+  library_formats?: FormatRange | null;
+  exit_code?: number | null;
+}
+
+export interface PioneerVersionInfo {
+  path: string;
+  version: string | null;
+  exit_code: number | null;
+  stderr: string | null;
+}
+
+export interface PipelineStage {
+  phase: RunMode;
+  key: string;
+  label: string;
+}
+
+export interface PipelineStartedPayload {
+  pipeline_id: string;
+  build_run_id: string | null;
+  stages: PipelineStage[];
+}
+
+export interface PipelineProgressPayload {
+  pipeline_id: string;
+  phase: RunMode;
+  stage_index: number;
+  stage_key: string;
+  stage_label: string;
+  progress: number;
+}
+
+export interface PipelineCompletePayload {
+  pipeline_id: string;
+  success: boolean;
+  failed_phase: RunMode | null;
+  library_path: string | null;
+  search_run_id: string | null;
+  message: string | null;
+}
+
+export interface HookOutputPayload {
+  mode: RunMode;
+  run_id: string | null;
+  stream: 'stdout' | 'stderr';
+  line: string;
+}
+
+export interface HookCompletePayload {
+  mode: RunMode;
+  run_id: string | null;
+  success: boolean;
+  exit_code: number | null;
+  message: string | null;
+}
+
+export type TaskbarStatus = 'running' | 'paused' | 'none';
+
+export interface TaskbarProgressPayload {
+  mode: RunMode;
+  status: TaskbarStatus;
+  percent: number | null;
+}
+
+export interface OutputLock {
+  path: string;
+  key: string;
+  bytes: number;
+  age_secs: number | null;
+  pid: number | null;
+  process_alive: boolean | null;
+  stale: boolean;
+}
+
+export type ArrayMerge = 'replace' | 'concat' | 'union' | 'mergeByIndex';
+
+export type LeafSource = 'default' | 'overridden' | 'persistedOnly';
+
+export interface LeafOrigin {
+  path: string;
+  source: LeafSource;
+  same_as_default: boolean;
+}
+
+export interface MergePreview {
+  merged: Record<string, unknown>;
+  leaves: LeafOrigin[];
+}
+
+export interface RuleViolation {
+  kind: 'requires' | 'conflicts';
+  path: string;
+  other_path: string;
+  message: string;
+}
+
+export interface PolicyViolation {
+  path: string;
+  expected: unknown;
+  actual: unknown | null;
+  message: string;
+}
+
+export interface ReproducerOptions {
+  redact_paths?: boolean;
+  include_fasta?: boolean;
+  include_large_inputs?: boolean;
+}
+
+export interface ReproducerReport {
+  path: string;
+  entries: string[];
+  bytes: number;
+  redacted: boolean;
+  missing_inputs: string[];
+}
+
 export interface ConfigState {
   defaults: JsonValue;
   current: JsonValue;